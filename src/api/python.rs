@@ -3872,6 +3872,9 @@ impl PythonPolynomial {
     ///
     /// If `print_stats=True` intermediate statistics will be printed.
     ///
+    /// If `modular=True`, the basis is computed modulo several primes
+    /// and is reconstructed using rational reconstruction.
+    ///
     /// Examples
     /// --------
     /// >>> basis = Polynomial.groebner_basis(
@@ -3884,20 +3887,25 @@ impl PythonPolynomial {
     /// >>> )
     /// >>> for p in basis:
     /// >>>     print(p)
-    #[pyo3(signature = (system, grevlex = true, print_stats = false))]
+    #[pyo3(signature = (system, grevlex = true, print_stats = false, modular = false))]
     #[classmethod]
     pub fn groebner_basis(
         _cls: &PyType,
         system: Vec<Self>,
         grevlex: bool,
         print_stats: bool,
+        modular: bool,
     ) -> Vec<Self> {
         if grevlex {
             let grevlex_ideal: Vec<_> = system
                 .iter()
                 .map(|p| p.poly.reorder::<GrevLexOrder>())
                 .collect();
-            let gb = GroebnerBasis::new(&grevlex_ideal, print_stats);
+            let gb = if modular {
+                GroebnerBasis::new_modular(&grevlex_ideal, print_stats)
+            } else {
+                GroebnerBasis::new(&grevlex_ideal, print_stats)
+            };
 
             gb.system
                 .into_iter()
@@ -3907,7 +3915,11 @@ impl PythonPolynomial {
                 .collect()
         } else {
             let ideal: Vec<_> = system.iter().map(|p| p.poly.clone()).collect();
-            let gb = GroebnerBasis::new(&ideal, print_stats);
+            let gb = if modular {
+                GroebnerBasis::new_modular(&ideal, print_stats)
+            } else {
+                GroebnerBasis::new(&ideal, print_stats)
+            };
             gb.system.into_iter().map(|p| Self { poly: p }).collect()
         }
     }
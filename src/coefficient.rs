@@ -434,7 +434,6 @@ impl CoefficientView<'_> {
                 if n2 <= u32::MAX as i64 {
                     if let Some(pn) = n1.checked_pow(n2 as u32) {
                         if let Some(pd) = d1.checked_pow(n2 as u32) {
-                            // TODO: simplify 4^(1/2)
                             return ((pn, pd).into(), (1, d2).into());
                         }
                     }
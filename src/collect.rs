@@ -2,8 +2,8 @@ use ahash::HashMap;
 
 use crate::{
     atom::{Add, AsAtomView, Atom, AtomView, Symbol},
-    coefficient::CoefficientView,
-    domains::{integer::Z, rational::Q},
+    coefficient::{Coefficient, CoefficientView},
+    domains::{integer::Z, rational::Q, Field, Ring},
     poly::{factor::Factorize, polynomial::MultivariatePolynomial},
     state::Workspace,
 };
@@ -75,6 +75,11 @@ impl Atom {
     pub fn factor(&self) -> Atom {
         self.as_view().factor()
     }
+
+    /// Move numerical radicals out of denominators, e.g. `1/2^(1/2) = 2^(1/2)/2`.
+    pub fn rationalize_denominator(&self) -> Atom {
+        self.as_view().rationalize_denominator()
+    }
 }
 
 impl<'a> AtomView<'a> {
@@ -590,6 +595,129 @@ impl<'a> AtomView<'a> {
         }
     }
 
+    /// Move numerical radicals out of denominators, e.g. `1/2^(1/2) = 2^(1/2)/2`.
+    pub fn rationalize_denominator(&self) -> Atom {
+        let mut out = Atom::new();
+        self.rationalize_denominator_into(&mut out);
+        out
+    }
+
+    /// Move numerical radicals out of denominators, e.g. `1/2^(1/2) = 2^(1/2)/2`.
+    pub fn rationalize_denominator_into(&self, out: &mut Atom) {
+        Workspace::get_local().with(|ws| {
+            self.rationalize_denominator_with_ws_into(ws, out);
+        });
+    }
+
+    fn rationalize_denominator_with_ws_into(&self, ws: &Workspace, out: &mut Atom) -> bool {
+        match self {
+            AtomView::Num(_) | AtomView::Var(_) => {
+                out.set_from_view(self);
+                false
+            }
+            AtomView::Pow(p) => {
+                let (b, e) = p.get_base_exp();
+
+                if let (AtomView::Num(bn), AtomView::Num(en)) = (b, e) {
+                    if let (Coefficient::Rational(r), CoefficientView::Natural(n, d)) =
+                        (bn.get_coeff_view().to_owned(), en.get_coeff_view())
+                    {
+                        if n > 0 && d > 1 && !r.denominator().is_one() {
+                            // (a/b)^(n/d) = (a*b^(d-1))^(n/d)/b^n
+                            let den = r.denominator().to_rational();
+                            let new_base = ws.new_num(Q.mul(&r, &Q.pow(&den, d as u64)));
+
+                            let mut pow = ws.new_atom();
+                            pow.to_pow(new_base.as_view(), e);
+
+                            let mut mul = ws.new_atom();
+                            let mul_view = mul.to_mul();
+                            mul_view.extend(pow.as_view());
+                            mul_view.extend(ws.new_num(Q.inv(&Q.pow(&den, n as u64))).as_view());
+                            mul_view.as_view().normalize(ws, out);
+                            return true;
+                        }
+                    }
+
+                    out.set_from_view(self);
+                    return false;
+                }
+
+                let mut new_base = ws.new_atom();
+                let mut new_exp = ws.new_atom();
+                let base_changed = b.rationalize_denominator_with_ws_into(ws, &mut new_base);
+                let exp_changed = e.rationalize_denominator_with_ws_into(ws, &mut new_exp);
+
+                if base_changed || exp_changed {
+                    let mut pow = ws.new_atom();
+                    pow.to_pow(new_base.as_view(), new_exp.as_view());
+                    pow.as_view().normalize(ws, out);
+                    true
+                } else {
+                    out.set_from_view(self);
+                    false
+                }
+            }
+            AtomView::Fun(f) => {
+                let mut fun = ws.new_atom();
+                let fun_view = fun.to_fun(f.get_symbol());
+
+                let mut changed = false;
+                let mut tmp = ws.new_atom();
+                for arg in f.iter() {
+                    changed |= arg.rationalize_denominator_with_ws_into(ws, &mut tmp);
+                    fun_view.add_arg(tmp.as_view());
+                }
+
+                if changed {
+                    fun.as_view().normalize(ws, out);
+                    true
+                } else {
+                    out.set_from_view(self);
+                    false
+                }
+            }
+            AtomView::Mul(m) => {
+                let mut mul = ws.new_atom();
+                let mul_view = mul.to_mul();
+
+                let mut changed = false;
+                let mut tmp = ws.new_atom();
+                for arg in m.iter() {
+                    changed |= arg.rationalize_denominator_with_ws_into(ws, &mut tmp);
+                    mul_view.extend(tmp.as_view());
+                }
+
+                if changed {
+                    mul.as_view().normalize(ws, out);
+                    true
+                } else {
+                    out.set_from_view(self);
+                    false
+                }
+            }
+            AtomView::Add(a) => {
+                let mut add = ws.new_atom();
+                let add_view = add.to_add();
+
+                let mut changed = false;
+                let mut tmp = ws.new_atom();
+                for arg in a.iter() {
+                    changed |= arg.rationalize_denominator_with_ws_into(ws, &mut tmp);
+                    add_view.extend(tmp.as_view());
+                }
+
+                if changed {
+                    add.as_view().normalize(ws, out);
+                    true
+                } else {
+                    out.set_from_view(self);
+                    false
+                }
+            }
+        }
+    }
+
     /// Factor the expression over the rationals.
     pub fn factor(&self) -> Atom {
         let r = self.to_rational_polynomial::<_, _, u16>(&Q, &Z, None);
@@ -754,4 +882,14 @@ mod test {
 
         assert_eq!(out, ref_out);
     }
+
+    #[test]
+    fn rationalize_denominator() {
+        let input = Atom::parse("v1/2^(1/2)+f1((1/3)^(1/2))+(2/9)^(1/3)").unwrap();
+        let out = input.rationalize_denominator();
+
+        let ref_out = Atom::parse("2^(1/2)/2*v1+f1(3^(1/2)/3)+6^(1/3)/3").unwrap();
+
+        assert_eq!(out, ref_out);
+    }
 }
@@ -8,7 +8,11 @@ use crate::{
     coefficient::{Coefficient, CoefficientView},
     combinatorics::CombinationWithReplacementIterator,
     domains::{atom::AtomField, integer::Integer, rational::Rational},
-    poly::{series::Series, Variable},
+    poly::{
+        multivariate_series::{MultivariateSeries, SeriesTruncation},
+        series::Series,
+        Exponent, Variable,
+    },
     state::{State, Workspace},
 };
 
@@ -34,6 +38,18 @@ impl Atom {
     ) -> Result<Series<AtomField>, &'static str> {
         self.as_view().series(x, expansion_point, depth)
     }
+
+    /// Series expand in the variables `vars` around `expansion_point`, keeping all terms
+    /// allowed by `truncation`.
+    pub fn series_multi<E: Exponent>(
+        &self,
+        vars: &[Symbol],
+        expansion_point: &[AtomView],
+        truncation: SeriesTruncation,
+    ) -> Result<MultivariateSeries<AtomField, E>, &'static str> {
+        self.as_view()
+            .series_multi(vars, expansion_point, truncation)
+    }
 }
 
 impl<'a> AtomView<'a> {
@@ -376,6 +392,137 @@ impl<'a> AtomView<'a> {
         })
     }
 
+    /// Series expand in the variables `vars` around `expansion_point`, keeping all terms
+    /// allowed by `truncation`. The result is a multivariate Taylor series, so the
+    /// expression may not have poles or branch cuts at the expansion point.
+    pub fn series_multi<E: Exponent>(
+        &self,
+        vars: &[Symbol],
+        expansion_point: &[AtomView],
+        truncation: SeriesTruncation,
+    ) -> Result<MultivariateSeries<AtomField, E>, &'static str> {
+        if vars.len() != expansion_point.len() {
+            return Err("The expansion point does not match the number of variables");
+        }
+
+        let info = MultivariateSeries::new(
+            &AtomField::new(),
+            Arc::new(vars.iter().map(|x| Variable::Symbol(*x)).collect()),
+            expansion_point.iter().map(|p| p.to_owned()).collect(),
+            truncation,
+        );
+
+        self.series_multi_impl(vars, &info)
+    }
+
+    /// Series expand in the variables `vars` around the expansion point of `info`,
+    /// inheriting its truncation.
+    fn series_multi_impl<E: Exponent>(
+        &self,
+        vars: &[Symbol],
+        info: &MultivariateSeries<AtomField, E>,
+    ) -> Result<MultivariateSeries<AtomField, E>, &'static str> {
+        if !vars.iter().any(|x| self.contains_symbol(*x)) {
+            return Ok(info.constant(self.to_owned()));
+        }
+
+        match self {
+            AtomView::Num(n) => Ok(info.constant(n.to_owned().into())),
+            AtomView::Var(v) => {
+                if let Some(i) = vars.iter().position(|x| *x == v.get_symbol()) {
+                    Ok(info.variable(i))
+                } else {
+                    Ok(info.constant(v.to_owned().into()))
+                }
+            }
+            AtomView::Fun(f) => {
+                let mut args_series = Vec::with_capacity(f.get_nargs());
+                for arg in f.iter() {
+                    args_series.push(arg.series_multi_impl(vars, info)?);
+                }
+
+                match f.get_symbol() {
+                    State::COS => Ok(args_series[0].cos()),
+                    State::SIN => Ok(args_series[0].sin()),
+                    State::EXP => Ok(args_series[0].exp()),
+                    State::LOG => args_series[0].log(),
+                    State::SQRT => args_series[0].rpow((1, 2).into()),
+                    _ => {
+                        // expand around the constant terms of the arguments
+                        let mut constants = vec![];
+                        for x in &mut args_series {
+                            let c = x.get_constant();
+                            *x = &*x - &x.constant(c.clone());
+                            constants.push(c);
+                        }
+
+                        let mut f_eval = FunctionBuilder::new(f.get_symbol());
+                        for c in &constants {
+                            f_eval = f_eval.add_arg(c);
+                        }
+                        let constant = f_eval.finish();
+
+                        let mut result = info.constant(constant.clone());
+                        for i in 1..=info.truncation().max_degree() {
+                            let mut it =
+                                CombinationWithReplacementIterator::new(args_series.len(), i);
+
+                            while let Some(x) = it.next() {
+                                let mut f_der = FunctionBuilder::new(State::DERIVATIVE);
+                                let mut term = info.one();
+                                for (arg, pow) in x.iter().enumerate() {
+                                    term = &term * &args_series[arg].npow(*pow as usize);
+                                    f_der = f_der.add_arg(&Atom::new_num(*pow as i64));
+                                }
+
+                                f_der = f_der.add_arg(&constant);
+
+                                let coeff = &(f_der.finish()
+                                    * &Atom::new_num(Integer::multinom(x)))
+                                    / &Atom::new_num(Integer::factorial(i));
+                                result = &result + &term.mul_coeff(&coeff);
+                            }
+                        }
+
+                        Ok(result)
+                    }
+                }
+            }
+            AtomView::Pow(p) => {
+                let (base, exp) = p.get_base_exp();
+
+                let base_series = base.series_multi_impl(vars, info)?;
+
+                if let AtomView::Num(n) = exp {
+                    if let CoefficientView::Natural(n, d) = n.get_coeff_view() {
+                        base_series.rpow((n, d).into())
+                    } else {
+                        Err("Cannot series expand with large exponents yet")
+                    }
+                } else {
+                    let e = exp.series_multi_impl(vars, info)?;
+                    base_series.pow(&e)
+                }
+            }
+            AtomView::Mul(args) => {
+                let mut series = info.one();
+                for arg in args.iter() {
+                    series = &series * &arg.series_multi_impl(vars, info)?;
+                }
+
+                Ok(series)
+            }
+            AtomView::Add(args) => {
+                let mut series = info.zero();
+                for arg in args.iter() {
+                    series = &series + &arg.series_multi_impl(vars, info)?;
+                }
+
+                Ok(series)
+            }
+        }
+    }
+
     /// Series expand in `x` around `expansion_point` to depth `depth`.
     pub fn series_with_ws(
         &self,
@@ -716,7 +863,7 @@ impl Sub<&Atom> for &Series<AtomField> {
 
 #[cfg(test)]
 mod test {
-    use crate::{atom::Atom, state::State};
+    use crate::{atom::Atom, poly::multivariate_series::SeriesTruncation, state::State};
 
     #[test]
     fn derivative() {
@@ -914,4 +1061,93 @@ mod test {
 
         assert_eq!(r.to_atom(), Atom::parse("v1-v1^2+v1^3-v1^4").unwrap());
     }
+
+    #[test]
+    fn series_multi() {
+        let v1 = State::get_symbol("v1");
+        let v2 = State::get_symbol("v2");
+        let zero = Atom::new_num(0);
+        let one = Atom::new_num(1);
+
+        let t = Atom::parse("exp(v1+v2)")
+            .unwrap()
+            .series_multi::<u8>(
+                &[v1, v2],
+                &[zero.as_view(), zero.as_view()],
+                SeriesTruncation::TotalDegree(2),
+            )
+            .unwrap();
+        assert_eq!(
+            t.to_atom(),
+            Atom::parse("1+v1+v2+1/2*v1^2+v1*v2+1/2*v2^2").unwrap()
+        );
+
+        let t = Atom::parse("1/(1-v1-v2)")
+            .unwrap()
+            .series_multi::<u8>(
+                &[v1, v2],
+                &[zero.as_view(), zero.as_view()],
+                SeriesTruncation::PerVariable(vec![1, 2]),
+            )
+            .unwrap();
+        assert_eq!(
+            t.to_atom(),
+            Atom::parse("1+v1+v2+2*v1*v2+v2^2+3*v1*v2^2").unwrap()
+        );
+
+        let t = Atom::parse("log(v1)*sin(v2)+exp(v1)*cos(v2)")
+            .unwrap()
+            .series_multi::<u8>(
+                &[v1, v2],
+                &[one.as_view(), zero.as_view()],
+                SeriesTruncation::TotalDegree(2),
+            )
+            .unwrap();
+        assert_eq!(
+            t.to_atom(),
+            Atom::parse("exp(1)+exp(1)*(v1-1)+1/2*exp(1)*(v1-1)^2-1/2*exp(1)*v2^2+(v1-1)*v2")
+                .unwrap()
+        );
+
+        assert!(Atom::parse("log(v1)")
+            .unwrap()
+            .series_multi::<u8>(&[v1], &[zero.as_view()], SeriesTruncation::TotalDegree(1))
+            .is_err());
+    }
+
+    #[test]
+    fn series_multi_compose() {
+        let v1 = State::get_symbol("v1");
+        let v2 = State::get_symbol("v2");
+        let v3 = State::get_symbol("v3");
+        let zero = Atom::new_num(0);
+
+        let f = Atom::parse("exp(v1)")
+            .unwrap()
+            .series_multi::<u8>(&[v1], &[zero.as_view()], SeriesTruncation::TotalDegree(3))
+            .unwrap();
+        let g = Atom::parse("sin(v2)+v3")
+            .unwrap()
+            .series_multi::<u8>(
+                &[v2, v3],
+                &[zero.as_view(), zero.as_view()],
+                SeriesTruncation::TotalDegree(3),
+            )
+            .unwrap();
+
+        let r = Atom::parse("exp(sin(v2)+v3)")
+            .unwrap()
+            .series_multi::<u8>(
+                &[v2, v3],
+                &[zero.as_view(), zero.as_view()],
+                SeriesTruncation::TotalDegree(3),
+            )
+            .unwrap();
+
+        assert_eq!(f.compose(&[g.clone()]).unwrap(), r);
+        assert_eq!(g.exp(), r);
+
+        let inv = g.exp().inv().unwrap();
+        assert_eq!(&inv * &r, r.one());
+    }
 }
@@ -3,6 +3,7 @@ pub mod atom;
 pub mod factorized_rational_polynomial;
 pub mod finite_field;
 pub mod float;
+pub mod galois_field;
 pub mod integer;
 pub mod rational;
 pub mod rational_polynomial;
@@ -1,12 +1,13 @@
-use std::rc::Rc;
+use std::{rc::Rc, sync::Arc};
 
 use crate::{
-    poly::{gcd::PolynomialGCD, polynomial::MultivariatePolynomial},
+    poly::{factor::Factorize, polynomial::MultivariatePolynomial, Exponent, Variable},
     printer::PolynomialPrinter,
 };
 
 use super::{
     finite_field::{FiniteField, FiniteFieldCore, FiniteFieldWorkspace, ToFiniteField},
+    rational::{RationalField, Q},
     EuclideanDomain, Field, Ring,
 };
 
@@ -199,8 +200,14 @@ impl<R: Ring> Ring for AlgebraicNumberRing<R> {
         self.poly.field.is_characteristic_zero()
     }
 
-    fn sample(&self, _rng: &mut impl rand::RngCore, _range: (i64, i64)) -> Self::Element {
-        todo!("Sampling an algebraic number is not implemented yet")
+    /// Sample an element where every coefficient of the representation
+    /// in the basis of the number field is sampled from the base ring.
+    fn sample(&self, rng: &mut impl rand::RngCore, range: (i64, i64)) -> Self::Element {
+        let mut poly = self.poly.zero();
+        for i in 0..self.poly.degree(0) {
+            poly.append_monomial(self.poly.field.sample(rng, range), &[i]);
+        }
+        AlgebraicNumber { poly }
     }
 
     fn fmt_display(
@@ -235,7 +242,7 @@ impl<R: Ring> Ring for AlgebraicNumberRing<R> {
     }
 }
 
-impl<R: Field + PolynomialGCD<u8>> EuclideanDomain for AlgebraicNumberRing<R> {
+impl<R: Field> EuclideanDomain for AlgebraicNumberRing<R> {
     fn rem(&self, _a: &Self::Element, _b: &Self::Element) -> Self::Element {
         // TODO: due to the remainder requiring an inverse, we need to have R be a field
         // instead of a Euclidean domain. Relax this condition by doing a pseudo-division
@@ -256,7 +263,7 @@ impl<R: Field + PolynomialGCD<u8>> EuclideanDomain for AlgebraicNumberRing<R> {
     }
 }
 
-impl<R: Field + PolynomialGCD<u8>> Field for AlgebraicNumberRing<R> {
+impl<R: Field> Field for AlgebraicNumberRing<R> {
     fn div(&self, a: &Self::Element, b: &Self::Element) -> Self::Element {
         self.mul(a, &self.inv(b))
     }
@@ -276,11 +283,133 @@ impl<R: Field + PolynomialGCD<u8>> Field for AlgebraicNumberRing<R> {
     }
 }
 
+/// A factor over an algebraic extension of the rationals together with its multiplicity.
+pub type AlgebraicFactor<E> = (
+    MultivariatePolynomial<AlgebraicNumberRing<RationalField>, E>,
+    usize,
+);
+
+/// A splitting field of the rationals and the roots in it with their multiplicity.
+pub type SplittingField = (
+    AlgebraicNumberRing<RationalField>,
+    Vec<(AlgebraicNumber<RationalField>, usize)>,
+);
+
+impl<E: Exponent> MultivariatePolynomial<RationalField, E> {
+    /// Factor the univariate polynomial into linear factors over its splitting field,
+    /// which is the coefficient ring of the factors. The splitting field is constructed
+    /// as a simple extension of the rationals, whose primitive element is represented
+    /// by the variable `var`. The variable `var` should not appear in the polynomial.
+    ///
+    /// The splitting field is built by repeatedly adjoining a root of a non-linear factor,
+    /// using the square-free norm to find the minimal polynomial of the new primitive element.
+    /// An error is returned if the polynomial is not univariate, if it depends on `var`,
+    /// or if the degree of the splitting field exceeds 255.
+    pub fn absolute_factorization(&self, var: Variable) -> Result<Vec<AlgebraicFactor<E>>, String> {
+        let vars: Vec<_> = (0..self.nvars())
+            .filter(|v| self.degree(*v) > E::zero())
+            .collect();
+        if vars.len() > 1 {
+            Err(format!(
+                "Polynomial {} is not univariate, cannot compute the splitting field",
+                self
+            ))?;
+        }
+
+        if vars.iter().any(|v| self.variables[*v] == var) {
+            Err(format!(
+                "The variable {} of the splitting field appears in the polynomial {}",
+                var, self
+            ))?;
+        }
+
+        let sff = self.square_free_factorization();
+
+        // start with the trivial extension
+        let mut min_poly = MultivariatePolynomial::new(&Q, None, Arc::new(vec![var]));
+        min_poly.append_monomial(Q.one(), &[1]);
+
+        loop {
+            let field = AlgebraicNumberRing::new(min_poly.clone());
+
+            let mut constant = self.constant(Q.one()).to_number_field(&field);
+            let mut factors = vec![];
+            let mut extension = None;
+            'factor: for (f, p) in &sff {
+                for (g, _) in f.to_number_field(&field).factor() {
+                    if g.is_constant() {
+                        constant = constant * &g.pow(*p);
+                    } else if g.degree(vars[0]) > E::one() {
+                        extension = Some(g);
+                        break 'factor;
+                    } else {
+                        factors.push((g, *p));
+                    }
+                }
+            }
+
+            let Some(g) = extension else {
+                if factors.is_empty() || !constant.is_one() {
+                    factors.push((constant, 1));
+                }
+
+                return Ok(factors);
+            };
+
+            let (_, norm) = g.square_free_norm(vars[0]);
+            if norm.degree(vars[0]).to_u32() > u8::MAX as u32 {
+                Err("The degree of the splitting field is too high")?;
+            }
+
+            min_poly = min_poly.zero();
+            for t in &norm {
+                min_poly
+                    .append_monomial(t.coefficient.into(), &[t.exponents[vars[0]].to_u32() as u8]);
+            }
+            min_poly = min_poly.make_monic();
+        }
+    }
+
+    /// Compute the splitting field of the univariate polynomial over the rationals
+    /// and the roots of the polynomial with their multiplicity, expressed in the primitive
+    /// element `var` of the splitting field. The variable `var` should not appear in the polynomial.
+    ///
+    /// The minimal polynomial of the primitive element can be obtained from [AlgebraicNumberRing::poly].
+    /// An error is returned if the degree of the splitting field exceeds 255.
+    pub fn splitting_field(&self, var: Variable) -> Result<SplittingField, String> {
+        let factors = self.absolute_factorization(var)?;
+        let field = factors[0].0.field.clone();
+
+        let mut roots = vec![];
+        for (f, p) in factors {
+            if f.is_constant() {
+                continue;
+            }
+
+            let mut c0 = field.zero();
+            let mut c1 = field.zero();
+            for t in &f {
+                if t.exponents.iter().all(|e| e.is_zero()) {
+                    c0 = t.coefficient.clone();
+                } else {
+                    c1 = t.coefficient.clone();
+                }
+            }
+
+            roots.push((field.neg(&field.div(&c0, &c1)), p));
+        }
+
+        Ok((field, roots))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::atom::Atom;
     use crate::domains::algebraic_number::AlgebraicNumberRing;
     use crate::domains::rational::Q;
+    use crate::domains::{Field, Ring};
+    use crate::state::State;
 
     #[test]
     fn gcd_number_field() -> Result<(), String> {
@@ -302,4 +431,47 @@ mod tests {
         assert_eq!(r, expected);
         Ok(())
     }
+
+    #[test]
+    fn splitting_field() -> Result<(), String> {
+        let poly = Atom::parse("(x^3-2)*(x^2+1)^2")?
+            .expand()
+            .to_polynomial::<_, u8>(&Q, None);
+
+        let (field, roots) = poly.splitting_field(State::get_symbol("a").into())?;
+        assert_eq!(field.poly().degree(0), 12);
+        assert_eq!(roots.len(), 5);
+
+        let f = poly
+            .to_number_field(&field)
+            .to_univariate_from_univariate(0);
+        for (r, p) in &roots {
+            assert!(f.evaluate(r).poly.is_zero());
+            assert_eq!(
+                *p,
+                if field.add(&field.mul(r, r), &field.one()).poly.is_zero() {
+                    2
+                } else {
+                    1
+                }
+            );
+        }
+
+        let factors = poly.absolute_factorization(State::get_symbol("a").into())?;
+        let mut r = poly.one().to_number_field(&field);
+        for (f, p) in &factors {
+            assert!(f.degree(0) <= 1);
+            r = r * &f.pow(*p);
+        }
+        assert_eq!(r, poly.to_number_field(&field));
+
+        let multivariate = Atom::parse("x^2 - y")?.to_polynomial::<_, u8>(&Q, None);
+        assert!(multivariate
+            .absolute_factorization(State::get_symbol("a").into())
+            .is_err());
+        assert!(poly
+            .absolute_factorization(State::get_symbol("x").into())
+            .is_err());
+        Ok(())
+    }
 }
@@ -6,6 +6,7 @@ use std::ops::Neg;
 use crate::domains::integer::Integer;
 use crate::printer::PrintOptions;
 
+use super::galois_field::{GaloisField, GaloisFieldElement};
 use super::integer::Z;
 use super::{EuclideanDomain, Field, Ring};
 
@@ -54,6 +55,81 @@ pub trait FiniteFieldCore<UField: FiniteFieldWorkspace>: Field {
     fn to_symmetric_integer(&self, a: &Self::Element) -> Integer;
 }
 
+/// A finite field with `p^k` elements, such as a prime field or a [GaloisField].
+pub trait FiniteFieldLike: Field {
+    /// Get the characteristic `p` of the field.
+    fn characteristic(&self) -> Integer;
+    /// Get the number of elements `p^k` of the field.
+    fn size(&self) -> Integer;
+    /// Enumerate the elements of the field: every `n < p^k` yields a different element.
+    fn nth_element(&self, n: u64) -> Self::Element;
+    /// Compute the unique `b` for which `b^p = a`.
+    fn pth_root(&self, a: &Self::Element) -> Self::Element;
+    /// Represent the field as a [GaloisField], if it fits.
+    fn to_galois_field(&self) -> Option<GaloisField>;
+    /// Map an element to the field returned by [FiniteFieldLike::to_galois_field].
+    fn to_galois_element(&self, a: &Self::Element) -> GaloisFieldElement;
+    /// Map an element from the field returned by [FiniteFieldLike::to_galois_field].
+    fn element_from_galois(&self, a: &GaloisFieldElement) -> Self::Element;
+
+    /// Create an extension of the field with at least `min_size` elements, together
+    /// with the image of the generator of the field returned by [FiniteFieldLike::to_galois_field].
+    ///
+    /// Panics when the field cannot be represented as a [GaloisField].
+    fn field_extension(&self, min_size: u64) -> (GaloisField, GaloisFieldElement) {
+        let field = self
+            .to_galois_field()
+            .unwrap_or_else(|| panic!("Cannot extend the field{}", self));
+        let q = self.size().to_i64().unwrap() as u64;
+
+        let mut degree = 2;
+        while q.saturating_pow(degree) < min_size {
+            degree += 1;
+        }
+
+        field.extension(degree as usize)
+    }
+}
+
+impl<UField: FiniteFieldWorkspace> FiniteFieldLike for FiniteField<UField>
+where
+    FiniteField<UField>: FiniteFieldCore<UField>,
+{
+    fn characteristic(&self) -> Integer {
+        self.get_prime().to_u64().into()
+    }
+
+    fn size(&self) -> Integer {
+        self.get_prime().to_u64().into()
+    }
+
+    fn nth_element(&self, n: u64) -> Self::Element {
+        self.nth(n)
+    }
+
+    fn pth_root(&self, a: &Self::Element) -> Self::Element {
+        a.clone()
+    }
+
+    fn to_galois_field(&self) -> Option<GaloisField> {
+        GaloisField::from_modulus(self.get_prime().to_u64(), &[0, 1]).ok()
+    }
+
+    fn to_galois_element(&self, a: &Self::Element) -> GaloisFieldElement {
+        let i = self.to_symmetric_integer(a);
+        let i = if i.is_negative() {
+            &i + &self.size()
+        } else {
+            i
+        };
+        GaloisFieldElement(i.to_i64().unwrap() as u64)
+    }
+
+    fn element_from_galois(&self, a: &GaloisFieldElement) -> Self::Element {
+        self.nth(a.0)
+    }
+}
+
 /// The modular ring `Z / mZ`, where `m` can be any positive integer. In most cases,
 /// `m` will be a prime, and the domain will be a field.
 ///
@@ -6,8 +6,11 @@ use std::{
 use rand::Rng;
 use wide::{f64x2, f64x4};
 
-use super::rational::Rational;
-use rug::{ops::CompleteRound, Float as MultiPrecisionFloat, Rational as MultiPrecisionRational};
+use super::{integer::Integer, rational::Rational};
+use rug::{
+    float::Round, ops::CompleteRound, Float as MultiPrecisionFloat,
+    Rational as MultiPrecisionRational,
+};
 
 pub trait NumericalFloatLike:
     PartialEq
@@ -474,6 +477,197 @@ impl Rational {
     }
 }
 
+impl Integer {
+    /// Find a non-trivial integer relation `a` such that `sum_i a_i * x_i = 0`, where the
+    /// sum should be smaller than `tolerance`, using the PSLQ algorithm.
+    /// The computations are performed with the precision of the first entry of `x`, which
+    /// should be sufficiently larger than the number of digits of the expected relation.
+    ///
+    /// This can be used to fit a high-precision numerical result to a basis of constants,
+    /// for example `x = [r, pi^2, zeta(3), log(2)]`.
+    ///
+    /// Returns `None` if there is no relation with coefficients bounded by `max_coeff`, or if
+    /// no relation is found within `max_iter` iterations.
+    pub fn solve_integer_relation(
+        x: &[MultiPrecisionFloat],
+        tolerance: &MultiPrecisionFloat,
+        max_coeff: &Integer,
+        max_iter: usize,
+    ) -> Option<Vec<Integer>> {
+        let n = x.len();
+        if n < 2 {
+            return None;
+        }
+
+        // a zero entry yields a trivial relation
+        if let Some(i) = x.iter().position(|xi| xi.clone().abs() < *tolerance) {
+            let mut r = vec![Integer::zero(); n];
+            r[i] = Integer::one();
+            return Some(r);
+        }
+
+        let prec = x[0].prec();
+        let gamma = (MultiPrecisionFloat::with_val(prec, 4) / 3u32).sqrt();
+        // the Euclidean norm of a relation with coefficients bounded by `max_coeff`
+        let max_norm = MultiPrecisionFloat::with_val(prec, max_coeff.clone().to_multi_prec())
+            * MultiPrecisionFloat::with_val(prec, n as u64).sqrt();
+
+        // s_k = sqrt(sum_{j >= k} x_j^2), normalized such that s_0 = 1
+        let mut s = vec![MultiPrecisionFloat::new(prec); n];
+        let mut acc = MultiPrecisionFloat::new(prec);
+        for k in (0..n).rev() {
+            acc += x[k].clone().square();
+            s[k] = acc.clone().sqrt();
+        }
+
+        let t = s[0].clone();
+        let mut y: Vec<_> = x.iter().map(|xi| xi.clone() / &t).collect();
+        for sk in &mut s {
+            *sk /= &t;
+        }
+
+        // the n x (n - 1) lower trapezoidal matrix H
+        let mut h = vec![vec![MultiPrecisionFloat::new(prec); n - 1]; n];
+        for i in 0..n {
+            if i < n - 1 && !s[i].is_zero() {
+                h[i][i] = s[i + 1].clone() / &s[i];
+            }
+
+            for j in 0..i {
+                let sjj = s[j].clone() * &s[j + 1];
+                if !sjj.is_zero() {
+                    h[i][j] = -(y[i].clone() * &y[j]) / &sjj;
+                }
+            }
+        }
+
+        // the columns of b are the candidate relations
+        let mut b: Vec<Vec<Integer>> = (0..n)
+            .map(|i| {
+                (0..n)
+                    .map(|j| {
+                        if i == j {
+                            Integer::one()
+                        } else {
+                            Integer::zero()
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+
+        /// Reduce row `i` of `h` using row `j`. Returns `false` if the diagonal entry of row `j` is zero.
+        fn reduce(
+            i: usize,
+            j: usize,
+            h: &mut [Vec<MultiPrecisionFloat>],
+            y: &mut [MultiPrecisionFloat],
+            b: &mut [Vec<Integer>],
+        ) -> bool {
+            if h[j][j].is_zero() {
+                return false;
+            }
+
+            let t = (h[i][j].clone() / &h[j][j]).round();
+            if t.is_zero() {
+                return true;
+            }
+
+            let tmp = t.clone() * &y[i];
+            y[j] += tmp;
+            // j < i, so row j is in the first part
+            let (upper, lower) = h.split_at_mut(i);
+            for (hik, hjk) in lower[0][..=j].iter_mut().zip(&upper[j][..=j]) {
+                let tmp = t.clone() * hjk;
+                *hik -= tmp;
+            }
+
+            let ti = Integer::from_large(t.to_integer().unwrap());
+            for row in b.iter_mut() {
+                let tmp = &ti * &row[i];
+                row[j] = &row[j] + &tmp;
+            }
+
+            true
+        }
+
+        for i in 1..n {
+            for j in (0..i).rev() {
+                reduce(i, j, &mut h, &mut y, &mut b);
+            }
+        }
+
+        for _ in 0..max_iter {
+            // select the row that maximizes gamma^(i+1) * |H_ii|
+            let mut m = 0;
+            let mut best = MultiPrecisionFloat::new(prec);
+            let mut g = gamma.clone();
+            for (i, hi) in h.iter().enumerate().take(n - 1) {
+                let v = g.clone() * &hi[i].clone().abs();
+                if v > best {
+                    best = v;
+                    m = i;
+                }
+                g *= &gamma;
+            }
+
+            y.swap(m, m + 1);
+            h.swap(m, m + 1);
+            for row in &mut b {
+                row.swap(m, m + 1);
+            }
+
+            // restore the lower trapezoidal form
+            if m + 2 < n {
+                let t0 = (h[m][m].clone().square() + h[m][m + 1].clone().square()).sqrt();
+                if t0.is_zero() {
+                    return None;
+                }
+
+                let t1 = h[m][m].clone() / &t0;
+                let t2 = h[m][m + 1].clone() / &t0;
+                for hi in &mut h[m..] {
+                    let (t3, t4) = (hi[m].clone(), hi[m + 1].clone());
+                    hi[m] = t1.clone() * &t3 + t2.clone() * &t4;
+                    hi[m + 1] = t1.clone() * &t4 - t2.clone() * &t3;
+                }
+            }
+
+            for i in m + 1..n {
+                for j in (0..=(i - 1).min(m + 1)).rev() {
+                    if !reduce(i, j, &mut h, &mut y, &mut b) {
+                        break;
+                    }
+                }
+            }
+
+            for (i, yi) in y.iter().enumerate() {
+                if yi.clone().abs() < *tolerance {
+                    let r: Vec<_> = b.iter().map(|row| row[i].clone()).collect();
+                    if r.iter().all(|c| c.abs() <= *max_coeff) {
+                        return Some(r);
+                    }
+                }
+            }
+
+            // the norm of any relation is bounded from below by 1 / max |H_ij|
+            let mut max_h = MultiPrecisionFloat::new(prec);
+            for hij in h.iter().flatten() {
+                let a = hij.clone().abs();
+                if a > max_h {
+                    max_h = a;
+                }
+            }
+
+            if !max_h.is_zero() && MultiPrecisionFloat::with_val(prec, 1) / max_h > max_norm {
+                return None;
+            }
+        }
+
+        None
+    }
+}
+
 macro_rules! simd_impl {
     ($t:ty, $p:ident) => {
         impl NumericalFloatLike for $t {
@@ -1195,39 +1389,1018 @@ impl<'a, T: Real + From<&'a Rational>> From<&'a Rational> for Complex<T> {
     }
 }
 
-#[cfg(test)]
-mod test {
-    use super::*;
+/// A dual number `real + dual[0]*ε_0 + ... + dual[n-1]*ε_{n-1}` with `ε_i*ε_j = 0`,
+/// which can be used for forward-mode automatic differentiation
+/// with respect to `n` variables at once.
+///
+/// Missing entries in `dual` are treated as zero, so that constants
+/// can be represented with an empty derivative vector.
+#[derive(Clone)]
+pub struct Dual<T: Real> {
+    pub real: T,
+    pub dual: Vec<T>,
+}
 
-    #[test]
-    fn double() {
-        let a = 5.;
-        let b = 7.;
+impl<T: Real> Dual<T> {
+    #[inline]
+    pub fn new(real: T, dual: Vec<T>) -> Dual<T> {
+        Dual { real, dual }
+    }
 
-        let r = a.sqrt() + b.log() + b.sin() - a.cos() + b.tan() - 0.3.asin() + 0.5.acos()
-            - a.atan2(b)
-            + b.sinh()
-            - a.cosh()
-            + b.tanh()
-            - 0.7.asinh()
-            + b.acosh() / 0.4.atanh()
-            + b.powf(a);
-        assert_eq!(r, 17293.219725825093);
+    /// Create a dual number that has derivative zero with respect to all variables.
+    #[inline]
+    pub fn constant(real: T) -> Dual<T> {
+        Dual { real, dual: vec![] }
     }
 
-    #[test]
-    fn complex() {
-        let a = Complex::new(1., 2.);
-        let b: Complex<f64> = Complex::new(3., 4.);
+    /// Create the dual number for variable `index` out of `n` variables,
+    /// i.e., with derivative one with respect to itself and zero for all others.
+    pub fn variable(real: T, index: usize, n: usize) -> Dual<T> {
+        let mut dual = vec![real.zero(); n];
+        dual[index] = real.one();
+        Dual { real, dual }
+    }
 
-        let r = &a.sqrt() + &b.log() - a.exp() + b.sin() - a.cos() + b.tan() - a.asin() + b.acos()
-            - a.atan2(&b)
-            + b.sinh()
-            - a.cosh()
-            + b.tanh()
-            - a.asinh()
-            + &b.acosh() / a.atanh()
-            + b.powf(a);
-        assert_eq!(r, Complex::new(0.1924131450685842, -39.83285329561913));
+    /// Get the derivative with respect to variable `index`.
+    pub fn derivative(&self, index: usize) -> T {
+        self.dual
+            .get(index)
+            .cloned()
+            .unwrap_or_else(|| self.real.zero())
+    }
+
+    /// Apply the chain rule for a function `f` with derivative `df` at `self.real`.
+    #[inline]
+    fn chain(&self, f: T, df: &T) -> Self {
+        Dual {
+            real: f,
+            dual: self.dual.iter().map(|d| d.clone() * df).collect(),
+        }
+    }
+
+    #[inline]
+    fn extend_dual(&mut self, len: usize) {
+        if self.dual.len() < len {
+            let zero = self.real.zero();
+            self.dual.resize(len, zero);
+        }
+    }
+}
+
+impl<T: Real> PartialEq for Dual<T> {
+    fn eq(&self, other: &Self) -> bool {
+        if self.real != other.real {
+            return false;
+        }
+
+        let zero = self.real.zero();
+        let len = self.dual.len().max(other.dual.len());
+        (0..len).all(|i| self.dual.get(i).unwrap_or(&zero) == other.dual.get(i).unwrap_or(&zero))
+    }
+}
+
+impl<T: Real> AddAssign<&Dual<T>> for Dual<T> {
+    #[inline]
+    fn add_assign(&mut self, rhs: &Self) {
+        self.real += &rhs.real;
+        self.extend_dual(rhs.dual.len());
+        for (d, r) in self.dual.iter_mut().zip(&rhs.dual) {
+            *d += r;
+        }
+    }
+}
+
+impl<T: Real> SubAssign<&Dual<T>> for Dual<T> {
+    #[inline]
+    fn sub_assign(&mut self, rhs: &Self) {
+        self.real -= &rhs.real;
+        self.extend_dual(rhs.dual.len());
+        for (d, r) in self.dual.iter_mut().zip(&rhs.dual) {
+            *d -= r;
+        }
+    }
+}
+
+impl<T: Real> MulAssign<&Dual<T>> for Dual<T> {
+    #[inline]
+    fn mul_assign(&mut self, rhs: &Self) {
+        for d in &mut self.dual {
+            *d *= &rhs.real;
+        }
+        self.extend_dual(rhs.dual.len());
+        for (d, r) in self.dual.iter_mut().zip(&rhs.dual) {
+            *d += self.real.clone() * r;
+        }
+        self.real *= &rhs.real;
+    }
+}
+
+impl<T: Real> DivAssign<&Dual<T>> for Dual<T> {
+    #[inline]
+    fn div_assign(&mut self, rhs: &Self) {
+        // (a/b)' = (a' - (a/b) b') / b
+        self.real /= &rhs.real;
+        self.extend_dual(rhs.dual.len());
+        for (d, r) in self.dual.iter_mut().zip(&rhs.dual) {
+            *d -= self.real.clone() * r;
+        }
+        for d in &mut self.dual {
+            *d /= &rhs.real;
+        }
+    }
+}
+
+macro_rules! dual_op_impl {
+    ($op:ident, $op_fn:ident, $op_assign:ident, $op_assign_fn:ident) => {
+        impl<T: Real> $op_assign for Dual<T> {
+            #[inline]
+            fn $op_assign_fn(&mut self, rhs: Self) {
+                self.$op_assign_fn(&rhs)
+            }
+        }
+
+        impl<T: Real> $op<Dual<T>> for Dual<T> {
+            type Output = Self;
+
+            #[inline]
+            fn $op_fn(mut self, rhs: Self) -> Self::Output {
+                self.$op_assign_fn(&rhs);
+                self
+            }
+        }
+
+        impl<T: Real> $op<&Dual<T>> for Dual<T> {
+            type Output = Self;
+
+            #[inline]
+            fn $op_fn(mut self, rhs: &Self) -> Self::Output {
+                self.$op_assign_fn(rhs);
+                self
+            }
+        }
+
+        impl<'a, 'b, T: Real> $op<&'a Dual<T>> for &'b Dual<T> {
+            type Output = Dual<T>;
+
+            #[inline]
+            fn $op_fn(self, rhs: &'a Dual<T>) -> Self::Output {
+                self.clone().$op_fn(rhs)
+            }
+        }
+    };
+}
+
+dual_op_impl!(Add, add, AddAssign, add_assign);
+dual_op_impl!(Sub, sub, SubAssign, sub_assign);
+dual_op_impl!(Mul, mul, MulAssign, mul_assign);
+dual_op_impl!(Div, div, DivAssign, div_assign);
+
+impl<T: Real> Neg for Dual<T> {
+    type Output = Dual<T>;
+
+    #[inline]
+    fn neg(self) -> Dual<T> {
+        Dual {
+            real: -self.real,
+            dual: self.dual.into_iter().map(|d| -d).collect(),
+        }
+    }
+}
+
+impl<T: Real> Display for Dual<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!("{}", self.real))?;
+        for (i, d) in self.dual.iter().enumerate() {
+            f.write_fmt(format_args!("+{}*ε{}", d, i))?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: Real> std::fmt::Debug for Dual<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!("({:?}, {:?})", self.real, self.dual))
+    }
+}
+
+impl<T: Real> NumericalFloatLike for Dual<T> {
+    #[inline]
+    fn mul_add(&self, a: &Self, b: &Self) -> Self {
+        self.clone() * a + b
+    }
+
+    #[inline]
+    fn neg(&self) -> Self {
+        -self.clone()
+    }
+
+    fn norm(&self) -> Self {
+        // the derivative of |x| is sign(x), which we set to 0 at x = 0
+        let n = self.real.norm();
+        if self.real == self.real.zero() {
+            self.chain(n, &self.real.zero())
+        } else {
+            let sign = n.clone() / &self.real;
+            self.chain(n, &sign)
+        }
+    }
+
+    #[inline]
+    fn zero(&self) -> Self {
+        Dual::constant(self.real.zero())
+    }
+
+    #[inline]
+    fn new_zero() -> Self {
+        Dual::constant(T::new_zero())
+    }
+
+    #[inline]
+    fn one(&self) -> Self {
+        Dual::constant(self.real.one())
+    }
+
+    fn pow(&self, e: u64) -> Self {
+        if e == 0 {
+            return self.one();
+        }
+
+        let p = self.real.pow(e - 1);
+        let df = p.clone() * &self.real.from_usize(e as usize);
+        self.chain(p * &self.real, &df)
+    }
+
+    fn inv(&self) -> Self {
+        let inv = self.real.inv();
+        let df = -(inv.clone() * &inv);
+        self.chain(inv, &df)
+    }
+
+    #[inline]
+    fn from_usize(&self, a: usize) -> Self {
+        Dual::constant(self.real.from_usize(a))
+    }
+
+    #[inline]
+    fn from_i64(&self, a: i64) -> Self {
+        Dual::constant(self.real.from_i64(a))
+    }
+
+    fn sample_unit<R: Rng + ?Sized>(&self, rng: &mut R) -> Self {
+        Dual::constant(self.real.sample_unit(rng))
+    }
+}
+
+impl<T: Real> Real for Dual<T> {
+    #[inline]
+    fn sqrt(&self) -> Self {
+        let s = self.real.sqrt();
+        let df = (s.clone() + &s).inv();
+        self.chain(s, &df)
+    }
+
+    #[inline]
+    fn log(&self) -> Self {
+        self.chain(self.real.log(), &self.real.inv())
+    }
+
+    #[inline]
+    fn exp(&self) -> Self {
+        let e = self.real.exp();
+        self.chain(e.clone(), &e)
+    }
+
+    #[inline]
+    fn sin(&self) -> Self {
+        self.chain(self.real.sin(), &self.real.cos())
+    }
+
+    #[inline]
+    fn cos(&self) -> Self {
+        self.chain(self.real.cos(), &-self.real.sin())
+    }
+
+    #[inline]
+    fn tan(&self) -> Self {
+        let t = self.real.tan();
+        let df = self.real.one() + t.clone() * &t;
+        self.chain(t, &df)
+    }
+
+    #[inline]
+    fn asin(&self) -> Self {
+        let df = (self.real.one() - self.real.clone() * &self.real)
+            .sqrt()
+            .inv();
+        self.chain(self.real.asin(), &df)
+    }
+
+    #[inline]
+    fn acos(&self) -> Self {
+        let df = -(self.real.one() - self.real.clone() * &self.real)
+            .sqrt()
+            .inv();
+        self.chain(self.real.acos(), &df)
+    }
+
+    fn atan2(&self, x: &Self) -> Self {
+        // d atan2(y, x) = (x dy - y dx) / (x^2 + y^2)
+        let n = (x.real.clone() * &x.real + self.real.clone() * &self.real).inv();
+        let mut r = self.chain(self.real.atan2(&x.real), &(x.real.clone() * &n));
+        r.extend_dual(x.dual.len());
+        let dy = self.real.clone() * &n;
+        for (d, xd) in r.dual.iter_mut().zip(&x.dual) {
+            *d -= dy.clone() * xd;
+        }
+        r
+    }
+
+    #[inline]
+    fn sinh(&self) -> Self {
+        self.chain(self.real.sinh(), &self.real.cosh())
+    }
+
+    #[inline]
+    fn cosh(&self) -> Self {
+        self.chain(self.real.cosh(), &self.real.sinh())
+    }
+
+    #[inline]
+    fn tanh(&self) -> Self {
+        let t = self.real.tanh();
+        let df = self.real.one() - t.clone() * &t;
+        self.chain(t, &df)
+    }
+
+    #[inline]
+    fn asinh(&self) -> Self {
+        let df = (self.real.clone() * &self.real + self.real.one())
+            .sqrt()
+            .inv();
+        self.chain(self.real.asinh(), &df)
+    }
+
+    #[inline]
+    fn acosh(&self) -> Self {
+        let df = (self.real.clone() * &self.real - self.real.one())
+            .sqrt()
+            .inv();
+        self.chain(self.real.acosh(), &df)
+    }
+
+    #[inline]
+    fn atanh(&self) -> Self {
+        let df = (self.real.one() - self.real.clone() * &self.real).inv();
+        self.chain(self.real.atanh(), &df)
+    }
+
+    fn powf(&self, e: Self) -> Self {
+        // d x^e = e x^(e-1) dx + x^e log(x) de
+        let p = self.real.powf(e.real.clone());
+        let df = e.real.clone() * self.real.powf(e.real.clone() - self.real.one());
+        let mut r = self.chain(p, &df);
+
+        let zero = self.real.zero();
+        if e.dual.iter().any(|d| *d != zero) {
+            r.extend_dual(e.dual.len());
+            let dg = r.real.clone() * self.real.log();
+            for (d, ed) in r.dual.iter_mut().zip(&e.dual) {
+                *d += dg.clone() * ed;
+            }
+        }
+
+        r
+    }
+}
+
+impl<'a, T: Real + From<&'a Rational>> From<&'a Rational> for Dual<T> {
+    fn from(value: &'a Rational) -> Self {
+        Dual::constant(value.into())
+    }
+}
+
+/// An interval `[lo, hi]` of multi-precision floats that is guaranteed to contain
+/// the exact result of every operation, by rounding the lower bound down
+/// and the upper bound up. It can be used to obtain certified error bounds
+/// on the evaluation of expressions.
+#[derive(Clone, PartialEq)]
+pub struct Interval {
+    lo: MultiPrecisionFloat,
+    hi: MultiPrecisionFloat,
+}
+
+impl Interval {
+    /// Create a new interval `[lo, hi]`.
+    pub fn new(lo: MultiPrecisionFloat, hi: MultiPrecisionFloat) -> Interval {
+        debug_assert!(
+            lo <= hi || lo.is_nan() || hi.is_nan(),
+            "Lower bound {} is larger than upper bound {}",
+            lo,
+            hi
+        );
+        Interval { lo, hi }
+    }
+
+    /// Create an interval that only contains `x`.
+    pub fn from_point(x: MultiPrecisionFloat) -> Interval {
+        Interval {
+            lo: x.clone(),
+            hi: x,
+        }
+    }
+
+    /// Create an interval with precision `prec` that contains π.
+    pub fn pi(prec: u32) -> Interval {
+        Interval {
+            lo: MultiPrecisionFloat::with_val_round(prec, rug::float::Constant::Pi, Round::Down).0,
+            hi: MultiPrecisionFloat::with_val_round(prec, rug::float::Constant::Pi, Round::Up).0,
+        }
+    }
+
+    /// Create the interval that contains all floats.
+    pub fn entire(prec: u32) -> Interval {
+        Interval {
+            lo: MultiPrecisionFloat::with_val(prec, rug::float::Special::NegInfinity),
+            hi: MultiPrecisionFloat::with_val(prec, rug::float::Special::Infinity),
+        }
+    }
+
+    #[inline]
+    pub fn lower(&self) -> &MultiPrecisionFloat {
+        &self.lo
+    }
+
+    #[inline]
+    pub fn upper(&self) -> &MultiPrecisionFloat {
+        &self.hi
+    }
+
+    #[inline]
+    pub fn prec(&self) -> u32 {
+        self.lo.prec().max(self.hi.prec())
+    }
+
+    /// Get the midpoint of the interval.
+    pub fn midpoint(&self) -> MultiPrecisionFloat {
+        (MultiPrecisionFloat::with_val(self.prec(), &self.lo + &self.hi)) / 2
+    }
+
+    /// Get the width `hi - lo` of the interval, rounded up.
+    pub fn width(&self) -> MultiPrecisionFloat {
+        MultiPrecisionFloat::with_val_round(self.prec(), &self.hi - &self.lo, Round::Up).0
+    }
+
+    /// Returns `true` iff `x` is in the interval.
+    pub fn contains(&self, x: &MultiPrecisionFloat) -> bool {
+        self.lo <= *x && *x <= self.hi
+    }
+
+    /// Returns `true` iff zero is in the interval.
+    pub fn contains_zero(&self) -> bool {
+        self.lo <= 0 && self.hi >= 0
+    }
+
+    /// Returns `true` iff the interval lies within `[-tolerance, tolerance]`.
+    pub fn is_within(&self, tolerance: f64) -> bool {
+        self.lo >= -tolerance && self.hi <= tolerance
+    }
+
+    /// Get the number of decimal digits of the midpoint that are guaranteed to be correct,
+    /// relative to the magnitude of the interval. Returns 0 if no digit is certain.
+    pub fn correct_digits(&self) -> u32 {
+        if self.lo == self.hi {
+            return (self.prec() as f64 * std::f64::consts::LOG10_2) as u32;
+        }
+
+        let mag = self.lo.clone().abs().max(&self.hi.clone().abs());
+        let rel = (self.width() / mag).to_f64();
+        if !rel.is_finite() || rel >= 1. {
+            0
+        } else {
+            (-rel.log10()) as u32
+        }
+    }
+
+    /// Apply a function that is monotonically increasing on the interval.
+    fn increasing(&self, f: fn(&mut MultiPrecisionFloat, Round) -> std::cmp::Ordering) -> Self {
+        let mut lo = self.lo.clone();
+        let mut hi = self.hi.clone();
+        f(&mut lo, Round::Down);
+        f(&mut hi, Round::Up);
+        Interval { lo, hi }
+    }
+
+    /// Apply a function that is monotonically decreasing on the interval.
+    fn decreasing(&self, f: fn(&mut MultiPrecisionFloat, Round) -> std::cmp::Ordering) -> Self {
+        let mut lo = self.hi.clone();
+        let mut hi = self.lo.clone();
+        f(&mut lo, Round::Down);
+        f(&mut hi, Round::Up);
+        Interval { lo, hi }
+    }
+
+    /// Returns `true` if the interval may contain a point `offset + 2πk` for an integer `k`.
+    fn may_contain_period_point(&self, offset: &Interval) -> bool {
+        let pi = Interval::pi(self.prec());
+        let t = (self.clone() - offset) / (pi.clone() + &pi);
+        if !t.lo.is_finite() || !t.hi.is_finite() {
+            return true;
+        }
+
+        t.hi.floor() >= t.lo.ceil()
+    }
+}
+
+impl AddAssign<&Interval> for Interval {
+    #[inline]
+    fn add_assign(&mut self, rhs: &Self) {
+        let p = self.prec().max(rhs.prec());
+        self.lo = MultiPrecisionFloat::with_val_round(p, &self.lo + &rhs.lo, Round::Down).0;
+        self.hi = MultiPrecisionFloat::with_val_round(p, &self.hi + &rhs.hi, Round::Up).0;
+    }
+}
+
+impl SubAssign<&Interval> for Interval {
+    #[inline]
+    fn sub_assign(&mut self, rhs: &Self) {
+        let p = self.prec().max(rhs.prec());
+        self.lo = MultiPrecisionFloat::with_val_round(p, &self.lo - &rhs.hi, Round::Down).0;
+        self.hi = MultiPrecisionFloat::with_val_round(p, &self.hi - &rhs.lo, Round::Up).0;
+    }
+}
+
+impl MulAssign<&Interval> for Interval {
+    fn mul_assign(&mut self, rhs: &Self) {
+        let p = self.prec().max(rhs.prec());
+
+        let mut lo = MultiPrecisionFloat::with_val(p, rug::float::Special::Infinity);
+        let mut hi = MultiPrecisionFloat::with_val(p, rug::float::Special::NegInfinity);
+        for a in [&self.lo, &self.hi] {
+            for b in [&rhs.lo, &rhs.hi] {
+                let d = MultiPrecisionFloat::with_val_round(p, a * b, Round::Down).0;
+                let u = MultiPrecisionFloat::with_val_round(p, a * b, Round::Up).0;
+                if d < lo {
+                    lo = d;
+                }
+                if u > hi {
+                    hi = u;
+                }
+            }
+        }
+
+        self.lo = lo;
+        self.hi = hi;
+    }
+}
+
+impl DivAssign<&Interval> for Interval {
+    fn div_assign(&mut self, rhs: &Self) {
+        let p = self.prec().max(rhs.prec());
+
+        if rhs.contains_zero() {
+            *self = Interval::entire(p);
+            return;
+        }
+
+        let mut lo = MultiPrecisionFloat::with_val(p, rug::float::Special::Infinity);
+        let mut hi = MultiPrecisionFloat::with_val(p, rug::float::Special::NegInfinity);
+        for a in [&self.lo, &self.hi] {
+            for b in [&rhs.lo, &rhs.hi] {
+                let d = MultiPrecisionFloat::with_val_round(p, a / b, Round::Down).0;
+                let u = MultiPrecisionFloat::with_val_round(p, a / b, Round::Up).0;
+                if d < lo {
+                    lo = d;
+                }
+                if u > hi {
+                    hi = u;
+                }
+            }
+        }
+
+        self.lo = lo;
+        self.hi = hi;
+    }
+}
+
+macro_rules! interval_op_impl {
+    ($op:ident, $op_fn:ident, $op_assign:ident, $op_assign_fn:ident) => {
+        impl $op_assign for Interval {
+            #[inline]
+            fn $op_assign_fn(&mut self, rhs: Self) {
+                self.$op_assign_fn(&rhs)
+            }
+        }
+
+        impl $op<Interval> for Interval {
+            type Output = Self;
+
+            #[inline]
+            fn $op_fn(mut self, rhs: Self) -> Self::Output {
+                self.$op_assign_fn(&rhs);
+                self
+            }
+        }
+
+        impl $op<&Interval> for Interval {
+            type Output = Self;
+
+            #[inline]
+            fn $op_fn(mut self, rhs: &Self) -> Self::Output {
+                self.$op_assign_fn(rhs);
+                self
+            }
+        }
+
+        impl<'a, 'b> $op<&'a Interval> for &'b Interval {
+            type Output = Interval;
+
+            #[inline]
+            fn $op_fn(self, rhs: &'a Interval) -> Self::Output {
+                self.clone().$op_fn(rhs)
+            }
+        }
+    };
+}
+
+interval_op_impl!(Add, add, AddAssign, add_assign);
+interval_op_impl!(Sub, sub, SubAssign, sub_assign);
+interval_op_impl!(Mul, mul, MulAssign, mul_assign);
+interval_op_impl!(Div, div, DivAssign, div_assign);
+
+impl Neg for Interval {
+    type Output = Interval;
+
+    #[inline]
+    fn neg(self) -> Interval {
+        Interval {
+            lo: -self.hi,
+            hi: -self.lo,
+        }
+    }
+}
+
+impl Display for Interval {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!("[{}, {}]", self.lo, self.hi))
+    }
+}
+
+impl std::fmt::Debug for Interval {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!("[{:?}, {:?}]", self.lo, self.hi))
+    }
+}
+
+impl NumericalFloatLike for Interval {
+    #[inline]
+    fn mul_add(&self, a: &Self, b: &Self) -> Self {
+        self.clone() * a + b
+    }
+
+    #[inline]
+    fn neg(&self) -> Self {
+        -self.clone()
+    }
+
+    fn norm(&self) -> Self {
+        if self.contains_zero() {
+            let hi = self.lo.clone().abs().max(&self.hi);
+            Interval {
+                lo: MultiPrecisionFloat::new(self.prec()),
+                hi,
+            }
+        } else if self.lo > 0 {
+            self.clone()
+        } else {
+            -self.clone()
+        }
+    }
+
+    #[inline]
+    fn zero(&self) -> Self {
+        Interval::from_point(MultiPrecisionFloat::new(self.prec()))
+    }
+
+    #[inline]
+    fn new_zero() -> Self {
+        Interval::from_point(MultiPrecisionFloat::new(1))
+    }
+
+    #[inline]
+    fn one(&self) -> Self {
+        Interval::from_point(MultiPrecisionFloat::with_val(self.prec(), 1))
+    }
+
+    fn pow(&self, e: u64) -> Self {
+        let p = self.prec();
+
+        if e == 0 {
+            return self.one();
+        }
+
+        let pow_round = |x: &MultiPrecisionFloat, r: Round| {
+            MultiPrecisionFloat::with_val_round(p, rug::ops::Pow::pow(x, e), r).0
+        };
+
+        if e % 2 == 1 || self.lo >= 0 {
+            Interval {
+                lo: pow_round(&self.lo, Round::Down),
+                hi: pow_round(&self.hi, Round::Up),
+            }
+        } else if self.hi <= 0 {
+            Interval {
+                lo: pow_round(&self.hi, Round::Down),
+                hi: pow_round(&self.lo, Round::Up),
+            }
+        } else {
+            let m = self.lo.clone().abs().max(&self.hi);
+            Interval {
+                lo: MultiPrecisionFloat::new(p),
+                hi: pow_round(&m, Round::Up),
+            }
+        }
+    }
+
+    fn inv(&self) -> Self {
+        if self.contains_zero() {
+            Interval::entire(self.prec())
+        } else {
+            self.decreasing(MultiPrecisionFloat::recip_round)
+        }
+    }
+
+    #[inline]
+    fn from_usize(&self, a: usize) -> Self {
+        Interval {
+            lo: MultiPrecisionFloat::with_val_round(self.prec(), a, Round::Down).0,
+            hi: MultiPrecisionFloat::with_val_round(self.prec(), a, Round::Up).0,
+        }
+    }
+
+    #[inline]
+    fn from_i64(&self, a: i64) -> Self {
+        Interval {
+            lo: MultiPrecisionFloat::with_val_round(self.prec(), a, Round::Down).0,
+            hi: MultiPrecisionFloat::with_val_round(self.prec(), a, Round::Up).0,
+        }
+    }
+
+    fn sample_unit<R: Rng + ?Sized>(&self, rng: &mut R) -> Self {
+        let f: f64 = rng.gen();
+        Interval::from_point(MultiPrecisionFloat::with_val(self.prec(), f))
+    }
+}
+
+impl Real for Interval {
+    fn sqrt(&self) -> Self {
+        let mut r = self.clone();
+        if r.lo < 0 {
+            r.lo = MultiPrecisionFloat::new(self.prec());
+        }
+        r.increasing(MultiPrecisionFloat::sqrt_round)
+    }
+
+    #[inline]
+    fn log(&self) -> Self {
+        self.increasing(MultiPrecisionFloat::ln_round)
+    }
+
+    #[inline]
+    fn exp(&self) -> Self {
+        self.increasing(MultiPrecisionFloat::exp_round)
+    }
+
+    fn sin(&self) -> Self {
+        let p = self.prec();
+        let one = MultiPrecisionFloat::with_val(p, 1);
+
+        let half_pi = Interval::pi(p) / Interval::from_point(MultiPrecisionFloat::with_val(p, 2));
+        let has_max = self.may_contain_period_point(&half_pi);
+        let has_min = self.may_contain_period_point(&-half_pi);
+
+        let mut r = self.increasing(MultiPrecisionFloat::sin_round);
+        let mut r2 = Interval {
+            lo: self.hi.clone(),
+            hi: self.lo.clone(),
+        }
+        .increasing(MultiPrecisionFloat::sin_round);
+        if r2.lo < r.lo {
+            std::mem::swap(&mut r.lo, &mut r2.lo);
+        }
+        if r2.hi > r.hi {
+            std::mem::swap(&mut r.hi, &mut r2.hi);
+        }
+
+        if has_max {
+            r.hi = one.clone();
+        }
+        if has_min {
+            r.lo = -one;
+        }
+        r
+    }
+
+    fn cos(&self) -> Self {
+        let p = self.prec();
+        let half_pi = Interval::pi(p) / Interval::from_point(MultiPrecisionFloat::with_val(p, 2));
+        (self.clone() + half_pi).sin()
+    }
+
+    fn tan(&self) -> Self {
+        self.sin() / self.cos()
+    }
+
+    #[inline]
+    fn asin(&self) -> Self {
+        self.increasing(MultiPrecisionFloat::asin_round)
+    }
+
+    #[inline]
+    fn acos(&self) -> Self {
+        self.decreasing(MultiPrecisionFloat::acos_round)
+    }
+
+    fn atan2(&self, x: &Self) -> Self {
+        let p = self.prec().max(x.prec());
+        let pi = Interval::pi(p);
+
+        if x.lo > 0 {
+            (self.clone() / x).increasing(MultiPrecisionFloat::atan_round)
+        } else if x.hi < 0 && self.lo >= 0 {
+            (self.clone() / x).increasing(MultiPrecisionFloat::atan_round) + pi
+        } else if x.hi < 0 && self.hi < 0 {
+            (self.clone() / x).increasing(MultiPrecisionFloat::atan_round) - pi
+        } else {
+            Interval {
+                lo: -pi.hi.clone(),
+                hi: pi.hi,
+            }
+        }
+    }
+
+    #[inline]
+    fn sinh(&self) -> Self {
+        self.increasing(MultiPrecisionFloat::sinh_round)
+    }
+
+    fn cosh(&self) -> Self {
+        if self.lo >= 0 {
+            self.increasing(MultiPrecisionFloat::cosh_round)
+        } else if self.hi <= 0 {
+            self.decreasing(MultiPrecisionFloat::cosh_round)
+        } else {
+            let m = self.lo.clone().abs().max(&self.hi);
+            let mut r = Interval::from_point(m).increasing(MultiPrecisionFloat::cosh_round);
+            r.lo = MultiPrecisionFloat::with_val(self.prec(), 1);
+            r
+        }
+    }
+
+    #[inline]
+    fn tanh(&self) -> Self {
+        self.increasing(MultiPrecisionFloat::tanh_round)
+    }
+
+    #[inline]
+    fn asinh(&self) -> Self {
+        self.increasing(MultiPrecisionFloat::asinh_round)
+    }
+
+    #[inline]
+    fn acosh(&self) -> Self {
+        self.increasing(MultiPrecisionFloat::acosh_round)
+    }
+
+    #[inline]
+    fn atanh(&self) -> Self {
+        self.increasing(MultiPrecisionFloat::atanh_round)
+    }
+
+    fn powf(&self, e: Self) -> Self {
+        (e * self.log()).exp()
+    }
+}
+
+impl Rational {
+    /// Convert the rational number to the smallest interval of multi-precision
+    /// floats with precision `prec` that contains it.
+    pub fn to_multi_prec_interval(&self, prec: u32) -> Interval {
+        let r = rug::Rational::from((
+            self.numerator().to_multi_prec(),
+            self.denominator().to_multi_prec(),
+        ));
+
+        Interval {
+            lo: MultiPrecisionFloat::with_val_round(prec, &r, Round::Down).0,
+            hi: MultiPrecisionFloat::with_val_round(prec, &r, Round::Up).0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn double() {
+        let a = 5.;
+        let b = 7.;
+
+        let r = a.sqrt() + b.log() + b.sin() - a.cos() + b.tan() - 0.3.asin() + 0.5.acos()
+            - a.atan2(b)
+            + b.sinh()
+            - a.cosh()
+            + b.tanh()
+            - 0.7.asinh()
+            + b.acosh() / 0.4.atanh()
+            + b.powf(a);
+        assert_eq!(r, 17293.219725825093);
+    }
+
+    #[test]
+    fn complex() {
+        let a = Complex::new(1., 2.);
+        let b: Complex<f64> = Complex::new(3., 4.);
+
+        let r = &a.sqrt() + &b.log() - a.exp() + b.sin() - a.cos() + b.tan() - a.asin() + b.acos()
+            - a.atan2(&b)
+            + b.sinh()
+            - a.cosh()
+            + b.tanh()
+            - a.asinh()
+            + &b.acosh() / a.atanh()
+            + b.powf(a);
+        assert_eq!(r, Complex::new(0.1924131450685842, -39.83285329561913));
+    }
+
+    #[test]
+    fn dual() {
+        let x = Dual::variable(2., 0, 2);
+        let y = Dual::variable(3., 1, 2);
+
+        // f = x^2*y + sin(x*y)/y + y^x
+        let r = x.pow(2) * &y + (x.clone() * &y).sin() / &y + y.powf(x.clone());
+
+        let (xv, yv) = (2f64, 3f64);
+        let df_dx = 2. * xv * yv + (xv * yv).cos() + yv.powf(xv) * yv.ln();
+        let df_dy = xv * xv
+            + (xv * (xv * yv).cos() * yv - (xv * yv).sin()) / (yv * yv)
+            + xv * yv.powf(xv - 1.);
+
+        assert!((r.real - (xv * xv * yv + (xv * yv).sin() / yv + yv.powf(xv))).abs() < 1e-12);
+        assert!((r.derivative(0) - df_dx).abs() < 1e-12);
+        assert!((r.derivative(1) - df_dy).abs() < 1e-12);
+    }
+
+    #[test]
+    fn interval() {
+        let x = Rational::Natural(1, 3).to_multi_prec_interval(100);
+        assert!(x.lower() < x.upper());
+
+        // sin(x)^2 + cos(x)^2 - 1 must contain 0
+        let r = x.sin().pow(2) + x.cos().pow(2) - x.one();
+        assert!(r.contains_zero());
+        assert!(r.width() < 1e-25);
+
+        // cancellation makes the result uncertain at low precision
+        let big = Rational::Natural(10, 1).pow(20).to_multi_prec_interval(53);
+        let y = Rational::Natural(1, 3).to_multi_prec_interval(53);
+        let r = (big.clone() + &y) - &big;
+        assert!(r.contains(&(MultiPrecisionFloat::with_val(53, 1) / 3)));
+        assert_eq!(r.correct_digits(), 0);
+
+        let big = Rational::Natural(10, 1).pow(20).to_multi_prec_interval(200);
+        let y = Rational::Natural(1, 3).to_multi_prec_interval(200);
+        let r = (big.clone() + &y) - &big;
+        assert!(r.correct_digits() > 30);
+
+        // a result that cancels to zero has no certain significant digits
+        let r = (big.clone() + &y) - &big - &y;
+        assert!(r.contains_zero());
+        assert_eq!(r.correct_digits(), 0);
+    }
+
+    #[test]
+    fn integer_relation() {
+        let prec = 200;
+        let pi = MultiPrecisionFloat::with_val(prec, rug::float::Constant::Pi);
+        let log2 = MultiPrecisionFloat::with_val(prec, rug::float::Constant::Log2);
+        let pi2 = pi.square();
+
+        // r = 2*log(2) - pi^2/3
+        let r = log2.clone() * 2u32 - pi2.clone() / 3u32;
+
+        let tolerance = MultiPrecisionFloat::with_val(prec, 1e-40);
+        let rel = Integer::solve_integer_relation(
+            &[r, log2, pi2],
+            &tolerance,
+            &Integer::from(1000),
+            1000,
+        )
+        .unwrap();
+
+        let expected = [3, -6, 1].map(Integer::from);
+        let neg_expected = [-3, 6, -1].map(Integer::from);
+        assert!(rel == expected || rel == neg_expected);
     }
 }
@@ -0,0 +1,881 @@
+use rand::Rng;
+use std::fmt::{Display, Error, Formatter};
+use std::sync::Arc;
+
+use crate::{
+    coefficient::{Coefficient, CoefficientView, ConvertToRing},
+    poly::{
+        polynomial::MultivariatePolynomial, univariate::UnivariatePolynomial, Exponent, Variable,
+    },
+    printer::PrintOptions,
+};
+
+use super::{
+    finite_field::{is_prime_u64, FiniteFieldLike},
+    integer::Integer,
+    EuclideanDomain, Field, Ring,
+};
+
+/// A number in a Galois field. The coefficients of the polynomial
+/// representation are packed into a single word.
+#[derive(Debug, Copy, Clone, Hash, PartialEq, PartialOrd, Eq)]
+pub struct GaloisFieldElement(pub(crate) u64);
+
+/// The Galois field `GF(p^k)`, represented as `Z_p[x]/m(x)` where `m` is
+/// a monic irreducible polynomial of degree `k`.
+///
+/// The `k` coefficients of an element are packed into a single `u64`,
+/// such that all coefficients can be added in a few word operations.
+/// For `p = 2`, addition is a single `xor` and multiplication is a carry-less product.
+/// The packing requires `k * (bits(p) + 1) <= 64` for odd `p` and `k < 64` for `p = 2`.
+///
+/// Fields with at most `2^16` elements multiply using logarithm and antilogarithm tables
+/// with respect to a primitive element.
+///
+/// Elements are printed as their index `c_0 + c_1 p + ... + c_{k-1} p^{k-1}`,
+/// so that the element `x^7 + x + 1` of `GF(2^8)` is printed as `131`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GaloisField {
+    p: u64,
+    k: usize,
+    size: u64,
+    /// The width of a coefficient in the packed representation.
+    bits: u32,
+    /// The defining polynomial, from the constant term to the leading `1`.
+    modulus: Vec<u64>,
+    /// The lowest bit of every coefficient.
+    low_mask: u64,
+    /// The highest bit of every coefficient.
+    high_mask: u64,
+    /// The packed modulus for `p = 2`.
+    binary_modulus: u128,
+    /// The logarithm tables for small fields.
+    tables: Option<Arc<LogTables>>,
+}
+
+/// The fields with at most this many elements use logarithm tables for multiplication.
+const MAX_TABLE_SIZE: u64 = 1 << 16;
+
+/// The discrete logarithm of every nonzero element with respect to a primitive element `g`,
+/// indexed by the index of the element, and the powers of `g`.
+/// The tables are determined by the modulus and are therefore ignored in comparisons.
+#[derive(Clone)]
+struct LogTables {
+    log: Vec<u32>,
+    exp: Vec<u64>,
+}
+
+impl std::fmt::Debug for LogTables {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "LogTables")
+    }
+}
+
+impl PartialEq for LogTables {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for LogTables {}
+
+impl std::hash::Hash for LogTables {
+    fn hash<H: std::hash::Hasher>(&self, _: &mut H) {}
+}
+
+impl GaloisField {
+    /// Create the Galois field `GF(p^k)`. The first irreducible polynomial of
+    /// degree `k` in lexicographic order of its coefficients is used as modulus,
+    /// which for `GF(2^8)` is the AES polynomial `x^8+x^4+x^3+x+1`.
+    ///
+    /// Panics when `p` is not a prime or when `p^k` is too large for the packed representation.
+    pub fn new(p: u64, k: usize) -> GaloisField {
+        assert!(is_prime_u64(p), "{} is not a prime", p);
+        assert!(k > 0, "The extension degree should be positive");
+        assert!(
+            Self::fits(p, k),
+            "GF({}^{}) does not fit in a packed representation",
+            p,
+            k
+        );
+
+        let mut modulus = vec![0; k + 1];
+        modulus[k] = 1;
+        loop {
+            if Self::is_irreducible(p, &modulus) {
+                return Self::new_unchecked(p, modulus).with_tables();
+            }
+
+            // go to the next candidate
+            for c in &mut modulus[..k] {
+                *c += 1;
+                if *c < p {
+                    break;
+                }
+                *c = 0;
+            }
+        }
+    }
+
+    /// Create the Galois field `Z_p[x]/m(x)`, where the coefficients of `m`
+    /// are given from the constant term to the leading coefficient.
+    pub fn from_modulus(p: u64, modulus: &[u64]) -> Result<GaloisField, String> {
+        if !is_prime_u64(p) {
+            return Err(format!("{} is not a prime", p));
+        }
+
+        if modulus.len() < 2 || modulus.last() != Some(&1) {
+            return Err("The modulus should be a monic polynomial of positive degree".to_owned());
+        }
+
+        if !Self::fits(p, modulus.len() - 1) {
+            return Err(format!(
+                "GF({}^{}) does not fit in a packed representation",
+                p,
+                modulus.len() - 1
+            ));
+        }
+
+        let modulus: Vec<_> = modulus.iter().map(|c| c % p).collect();
+        if !Self::is_irreducible(p, &modulus) {
+            return Err("The modulus is not irreducible".to_owned());
+        }
+
+        Ok(Self::new_unchecked(p, modulus).with_tables())
+    }
+
+    fn fits(p: u64, k: usize) -> bool {
+        if p == 2 {
+            k < 64
+        } else {
+            k * (Self::bits(p) as usize) <= 64
+        }
+    }
+
+    fn bits(p: u64) -> u32 {
+        if p == 2 {
+            1
+        } else {
+            65 - p.leading_zeros()
+        }
+    }
+
+    fn new_unchecked(p: u64, modulus: Vec<u64>) -> GaloisField {
+        let k = modulus.len() - 1;
+        let bits = Self::bits(p);
+
+        let mut low_mask = 0;
+        for i in 0..k {
+            low_mask |= 1 << (i as u32 * bits);
+        }
+
+        let mut binary_modulus = 0;
+        if p == 2 {
+            for (i, c) in modulus.iter().enumerate() {
+                binary_modulus |= (*c as u128) << i;
+            }
+        }
+
+        GaloisField {
+            p,
+            k,
+            size: p.pow(k as u32),
+            bits,
+            modulus,
+            low_mask,
+            high_mask: low_mask << (bits - 1),
+            binary_modulus,
+            tables: None,
+        }
+    }
+
+    /// Construct the logarithm tables if the field is small enough.
+    fn with_tables(mut self) -> GaloisField {
+        if self.k == 1 || self.size > MAX_TABLE_SIZE {
+            return self;
+        }
+
+        let n = self.size - 1;
+        let mut prime_factors = vec![];
+        let mut m = n;
+        let mut d = 2;
+        while d * d <= m {
+            if m % d == 0 {
+                prime_factors.push(d);
+                while m % d == 0 {
+                    m /= d;
+                }
+            }
+            d += 1;
+        }
+        if m > 1 {
+            prime_factors.push(m);
+        }
+
+        // find a primitive element
+        let g = (1..self.size)
+            .map(|i| self.index_to_element(i))
+            .find(|g| {
+                prime_factors
+                    .iter()
+                    .all(|q| !self.is_one(&self.pow(g, n / q)))
+            })
+            .unwrap();
+
+        let mut log = vec![0; self.size as usize];
+        let mut exp = Vec::with_capacity(n as usize);
+        let mut x = self.one();
+        for i in 0..n {
+            exp.push(x.0);
+            log[self.element_to_index(&x) as usize] = i as u32;
+            x = self.mul(&x, &g);
+        }
+
+        self.tables = Some(Arc::new(LogTables { log, exp }));
+        self
+    }
+
+    /// Test if `modulus` is irreducible over `Z_p` using Rabin's test.
+    fn is_irreducible(p: u64, modulus: &[u64]) -> bool {
+        let k = modulus.len() - 1;
+        let base = GaloisField::new_unchecked(p, vec![0, 1]);
+        let var = Arc::new(Variable::Temporary(0));
+
+        let mut f = UnivariatePolynomial::new(&base, Some(k + 1), var);
+        f.coefficients = modulus.iter().map(|c| GaloisFieldElement(*c)).collect();
+        let x = f.monomial(base.one(), 1);
+
+        let pp = Integer::from(p);
+        if x.exp_mod(pp.pow(k as u64), &mut f) != x.rem(&f) {
+            return false;
+        }
+
+        for r in 2..=k {
+            if k % r == 0 && (2..r).all(|d| r % d != 0) {
+                let h = &x.exp_mod(pp.pow((k / r) as u64), &mut f) - &x;
+                if !h.gcd(&f).is_one() {
+                    return false;
+                }
+            }
+        }
+
+        true
+    }
+
+    /// Get the characteristic `p` of the field.
+    pub fn get_prime(&self) -> u64 {
+        self.p
+    }
+
+    /// Get the degree `k` of the extension over `Z_p`.
+    pub fn get_extension_degree(&self) -> usize {
+        self.k
+    }
+
+    /// Get the coefficients of the modulus, from the constant term to the leading `1`.
+    pub fn get_modulus(&self) -> &[u64] {
+        &self.modulus
+    }
+
+    /// Get the generator `x` of the extension, which is a root of the modulus.
+    pub fn generator(&self) -> GaloisFieldElement {
+        if self.k == 1 {
+            self.neg(&self.nth(self.modulus[0]))
+        } else {
+            GaloisFieldElement(1 << self.bits)
+        }
+    }
+
+    /// Create an element from the coefficients of a polynomial in the generator,
+    /// starting from the constant term.
+    pub fn from_coefficients(&self, coeffs: &[u64]) -> GaloisFieldElement {
+        if coeffs.len() <= self.k {
+            let mut r = 0;
+            for (i, c) in coeffs.iter().enumerate() {
+                r |= (c % self.p) << (i as u32 * self.bits);
+            }
+            return GaloisFieldElement(r);
+        }
+
+        // reduce using Horner's scheme
+        let x = self.generator();
+        let mut r = self.zero();
+        for c in coeffs.iter().rev() {
+            r = self.add(&self.mul(&r, &x), &self.nth(*c));
+        }
+        r
+    }
+
+    /// Get the `k` coefficients of the polynomial representation of `a`, starting from the constant term.
+    pub fn to_coefficients(&self, a: &GaloisFieldElement) -> Vec<u64> {
+        (0..self.k).map(|i| self.coefficient(a.0, i)).collect()
+    }
+
+    /// Create the extension `GF(p^(k*degree))` of the field, together with
+    /// the image of the generator of `self` in the extension.
+    pub fn extension(&self, degree: usize) -> (GaloisField, GaloisFieldElement) {
+        let ext = GaloisField::new(self.p, self.k * degree);
+
+        if self.k == 1 {
+            return (ext.clone(), ext.neg(&ext.nth(self.modulus[0])));
+        }
+
+        // the modulus splits into linear factors over the extension
+        let var = Arc::new(vec![Variable::Temporary(0)]);
+        let mut poly = MultivariatePolynomial::<_, u16>::new(&ext, Some(self.k + 1), var);
+        for (i, c) in self.modulus.iter().enumerate() {
+            if *c != 0 {
+                poly.append_monomial(ext.nth(*c), &[i as u16]);
+            }
+        }
+
+        let root = poly
+            .equal_degree_factorization(1)
+            .into_iter()
+            .next()
+            .unwrap()
+            .make_monic();
+        let root = ext.neg(&root.get_constant());
+        (ext, root)
+    }
+
+    /// Map `a` into the extension field `ext`, where `root` is the image of the generator of `self`.
+    pub fn to_extension(
+        &self,
+        ext: &GaloisField,
+        root: &GaloisFieldElement,
+        a: &GaloisFieldElement,
+    ) -> GaloisFieldElement {
+        let mut r = ext.zero();
+        for i in (0..self.k).rev() {
+            r = ext.add(&ext.mul(&r, root), &ext.nth(self.coefficient(a.0, i)));
+        }
+        r
+    }
+
+    /// Map `a` from the extension field `ext` back to `self`, where `root` is the image of the generator
+    /// of `self`. Returns `None` if `a` is not in the image of `self`.
+    pub fn from_extension(
+        &self,
+        ext: &GaloisField,
+        root: &GaloisFieldElement,
+        a: &GaloisFieldElement,
+    ) -> Option<GaloisFieldElement> {
+        if self.k == 1 {
+            let c = ext.to_coefficients(a);
+            return if c[1..].iter().all(|x| *x == 0) {
+                Some(GaloisFieldElement(c[0]))
+            } else {
+                None
+            };
+        }
+
+        // solve a = c_0 + c_1 root + ... + c_{k-1} root^{k-1} using Gaussian elimination
+        let p = self.p as u128;
+        let mut powers = vec![];
+        let mut r = ext.one();
+        for _ in 0..self.k {
+            powers.push(ext.to_coefficients(&r));
+            r = ext.mul(&r, root);
+        }
+
+        let mut rows: Vec<Vec<u64>> = (0..ext.k)
+            .map(|i| {
+                let mut row: Vec<_> = powers.iter().map(|c| c[i]).collect();
+                row.push(ext.coefficient(a.0, i));
+                row
+            })
+            .collect();
+
+        for col in 0..self.k {
+            // the powers of the root are linearly independent
+            let pivot = (col..rows.len()).find(|r| rows[*r][col] != 0).unwrap();
+            rows.swap(col, pivot);
+
+            let inv = self.pow(&self.nth(rows[col][col]), self.p - 2).0 as u128;
+            for x in &mut rows[col] {
+                *x = (*x as u128 * inv % p) as u64;
+            }
+
+            let pivot_row = rows[col].clone();
+            for (r, row) in rows.iter_mut().enumerate() {
+                let f = row[col] as u128;
+                if r != col && f != 0 {
+                    for (x, y) in row[col..].iter_mut().zip(&pivot_row[col..]) {
+                        *x = ((*x as u128 + (p - f) * *y as u128) % p) as u64;
+                    }
+                }
+            }
+        }
+
+        if rows[self.k..].iter().any(|r| r[self.k] != 0) {
+            return None;
+        }
+
+        let c: Vec<_> = rows[..self.k].iter().map(|r| r[self.k]).collect();
+        Some(self.from_coefficients(&c))
+    }
+
+    #[inline(always)]
+    fn coefficient(&self, a: u64, i: usize) -> u64 {
+        (a >> (i as u32 * self.bits)) & (u64::MAX >> (64 - self.bits))
+    }
+
+    /// Get the element with index `n < p^k`.
+    fn index_to_element(&self, mut n: u64) -> GaloisFieldElement {
+        let mut r = 0;
+        for i in 0..self.k {
+            r |= (n % self.p) << (i as u32 * self.bits);
+            n /= self.p;
+        }
+        GaloisFieldElement(r)
+    }
+
+    /// Get the index `c_0 + c_1 p + ... + c_{k-1} p^{k-1}` of an element.
+    fn element_to_index(&self, a: &GaloisFieldElement) -> u64 {
+        let mut r = 0;
+        for i in (0..self.k).rev() {
+            r = r * self.p + self.coefficient(a.0, i);
+        }
+        r
+    }
+
+    /// Subtract `p` from every coefficient that is at least `p`,
+    /// where all coefficients are smaller than `2p`.
+    #[inline(always)]
+    fn reduce_packed(&self, a: u64) -> u64 {
+        let half = 1 << (self.bits - 1);
+        let overflow = (a + (half - self.p) * self.low_mask) & self.high_mask;
+        a - (overflow >> (self.bits - 1)) * self.p
+    }
+
+    fn mul_binary(&self, a: u64, b: u64) -> u64 {
+        let mut r: u128 = 0;
+        for i in 0..self.k {
+            if (b >> i) & 1 == 1 {
+                r ^= (a as u128) << i;
+            }
+        }
+
+        for d in (self.k..2 * self.k - 1).rev() {
+            if (r >> d) & 1 == 1 {
+                r ^= self.binary_modulus << (d - self.k);
+            }
+        }
+
+        r as u64
+    }
+
+    #[inline]
+    fn mul_table(&self, tables: &LogTables, a: &GaloisFieldElement, b: &GaloisFieldElement) -> u64 {
+        if a.0 == 0 || b.0 == 0 {
+            return 0;
+        }
+
+        let n = tables.exp.len();
+        let la = tables.log[self.element_to_index(a) as usize] as usize;
+        let lb = tables.log[self.element_to_index(b) as usize] as usize;
+        let l = la + lb;
+        tables.exp[if l >= n { l - n } else { l }]
+    }
+
+    /// Multiply the packed coefficients, reducing every coefficient modulo `p` only once
+    /// during the product and once during the reduction by the modulus. Since
+    /// `k * bits(p) <= 64`, the unreduced sums fit in a `u128`.
+    fn mul_odd(&self, a: u64, b: u64) -> u64 {
+        let k = self.k;
+        let p = self.p as u128;
+        let mut bc = [0u128; 64];
+        for (j, x) in bc[..k].iter_mut().enumerate() {
+            *x = self.coefficient(b, j) as u128;
+        }
+
+        let mut c = [0u128; 128];
+        for i in 0..k {
+            let ai = self.coefficient(a, i) as u128;
+            if ai == 0 {
+                continue;
+            }
+
+            for (cj, bj) in c[i..i + k].iter_mut().zip(&bc[..k]) {
+                *cj += ai * bj;
+            }
+        }
+
+        for d in (k..2 * k - 1).rev() {
+            let t = c[d] % p;
+            if t == 0 {
+                continue;
+            }
+
+            let t = p - t;
+            for (cj, m) in c[d - k..d].iter_mut().zip(&self.modulus) {
+                *cj += t * *m as u128;
+            }
+        }
+
+        let mut r = 0;
+        for (i, x) in c[..k].iter().enumerate() {
+            r |= ((x % p) as u64) << (i as u32 * self.bits);
+        }
+        r
+    }
+}
+
+impl Display for GaloisField {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        write!(f, " % GF({}^{})", self.p, self.k)
+    }
+}
+
+impl Ring for GaloisField {
+    type Element = GaloisFieldElement;
+
+    #[inline]
+    fn add(&self, a: &Self::Element, b: &Self::Element) -> Self::Element {
+        if self.p == 2 {
+            GaloisFieldElement(a.0 ^ b.0)
+        } else {
+            GaloisFieldElement(self.reduce_packed(a.0 + b.0))
+        }
+    }
+
+    #[inline]
+    fn sub(&self, a: &Self::Element, b: &Self::Element) -> Self::Element {
+        self.add(a, &self.neg(b))
+    }
+
+    #[inline]
+    fn mul(&self, a: &Self::Element, b: &Self::Element) -> Self::Element {
+        if let Some(tables) = &self.tables {
+            GaloisFieldElement(self.mul_table(tables, a, b))
+        } else if self.p == 2 {
+            GaloisFieldElement(self.mul_binary(a.0, b.0))
+        } else {
+            GaloisFieldElement(self.mul_odd(a.0, b.0))
+        }
+    }
+
+    #[inline]
+    fn add_assign(&self, a: &mut Self::Element, b: &Self::Element) {
+        *a = self.add(a, b);
+    }
+
+    #[inline]
+    fn sub_assign(&self, a: &mut Self::Element, b: &Self::Element) {
+        *a = self.sub(a, b);
+    }
+
+    #[inline]
+    fn mul_assign(&self, a: &mut Self::Element, b: &Self::Element) {
+        *a = self.mul(a, b);
+    }
+
+    fn add_mul_assign(&self, a: &mut Self::Element, b: &Self::Element, c: &Self::Element) {
+        self.add_assign(a, &self.mul(b, c));
+    }
+
+    fn sub_mul_assign(&self, a: &mut Self::Element, b: &Self::Element, c: &Self::Element) {
+        self.sub_assign(a, &self.mul(b, c));
+    }
+
+    /// Computes `p - c` for every coefficient `c`.
+    #[inline]
+    fn neg(&self, a: &Self::Element) -> Self::Element {
+        if self.p == 2 {
+            *a
+        } else {
+            GaloisFieldElement(self.reduce_packed(self.p * self.low_mask - a.0))
+        }
+    }
+
+    #[inline]
+    fn zero(&self) -> Self::Element {
+        GaloisFieldElement(0)
+    }
+
+    #[inline]
+    fn one(&self) -> Self::Element {
+        GaloisFieldElement(1)
+    }
+
+    #[inline]
+    fn nth(&self, n: u64) -> Self::Element {
+        GaloisFieldElement(n % self.p)
+    }
+
+    /// Compute b^e.
+    fn pow(&self, b: &Self::Element, mut e: u64) -> Self::Element {
+        let mut b = *b;
+        let mut x = self.one();
+        while e != 0 {
+            if e & 1 != 0 {
+                x = self.mul(&x, &b);
+            }
+            b = self.mul(&b, &b);
+            e /= 2;
+        }
+
+        x
+    }
+
+    #[inline]
+    fn is_zero(a: &Self::Element) -> bool {
+        a.0 == 0
+    }
+
+    #[inline]
+    fn is_one(&self, a: &Self::Element) -> bool {
+        a.0 == 1
+    }
+
+    fn one_is_gcd_unit() -> bool {
+        true
+    }
+
+    fn is_characteristic_zero(&self) -> bool {
+        false
+    }
+
+    fn sample(&self, rng: &mut impl rand::RngCore, range: (i64, i64)) -> Self::Element {
+        let r = rng.gen_range(range.0.max(0)..range.1.min(self.size.min(i64::MAX as u64) as i64));
+        self.index_to_element(r as u64)
+    }
+
+    fn fmt_display(
+        &self,
+        element: &Self::Element,
+        _opts: &PrintOptions,
+        _in_product: bool,
+        f: &mut Formatter<'_>,
+    ) -> Result<(), Error> {
+        self.element_to_index(element).fmt(f)
+    }
+}
+
+impl EuclideanDomain for GaloisField {
+    #[inline]
+    fn rem(&self, _: &Self::Element, _: &Self::Element) -> Self::Element {
+        self.zero()
+    }
+
+    #[inline]
+    fn quot_rem(&self, a: &Self::Element, b: &Self::Element) -> (Self::Element, Self::Element) {
+        (self.div(a, b), self.zero())
+    }
+
+    #[inline]
+    fn gcd(&self, _: &Self::Element, _: &Self::Element) -> Self::Element {
+        self.one()
+    }
+}
+
+impl Field for GaloisField {
+    #[inline]
+    fn div(&self, a: &Self::Element, b: &Self::Element) -> Self::Element {
+        self.mul(a, &self.inv(b))
+    }
+
+    #[inline]
+    fn div_assign(&self, a: &mut Self::Element, b: &Self::Element) {
+        *a = self.div(a, b);
+    }
+
+    /// Computes `a^-1 = a^(p^k-2)`.
+    fn inv(&self, a: &Self::Element) -> Self::Element {
+        assert!(a.0 != 0, "0 is not invertible");
+
+        if let Some(tables) = &self.tables {
+            let n = tables.exp.len();
+            let l = tables.log[self.element_to_index(a) as usize] as usize;
+            return GaloisFieldElement(tables.exp[(n - l) % n]);
+        }
+
+        self.pow(a, self.size - 2)
+    }
+}
+
+impl FiniteFieldLike for GaloisField {
+    fn characteristic(&self) -> Integer {
+        self.p.into()
+    }
+
+    fn size(&self) -> Integer {
+        self.size.into()
+    }
+
+    fn nth_element(&self, n: u64) -> Self::Element {
+        self.index_to_element(n % self.size)
+    }
+
+    /// Compute `a^(1/p) = a^(p^(k-1))`.
+    fn pth_root(&self, a: &Self::Element) -> Self::Element {
+        self.pow(a, self.size / self.p)
+    }
+
+    fn to_galois_field(&self) -> Option<GaloisField> {
+        Some(self.clone())
+    }
+
+    fn to_galois_element(&self, a: &Self::Element) -> GaloisFieldElement {
+        *a
+    }
+
+    fn element_from_galois(&self, a: &GaloisFieldElement) -> Self::Element {
+        *a
+    }
+}
+
+impl<F: FiniteFieldLike, E: Exponent> MultivariatePolynomial<F, E> {
+    /// Map the polynomial to an extension `ext` of its field, where `root` is the image
+    /// of the generator of the field, as created by [FiniteFieldLike::field_extension].
+    pub fn to_galois_field_extension(
+        &self,
+        ext: &GaloisField,
+        root: &GaloisFieldElement,
+    ) -> MultivariatePolynomial<GaloisField, E> {
+        let field = self.field.to_galois_field().unwrap();
+        self.map_coeff(
+            |c| field.to_extension(ext, root, &self.field.to_galois_element(c)),
+            ext.clone(),
+        )
+    }
+
+    /// Map a polynomial from an extension created with [FiniteFieldLike::field_extension]
+    /// back to the field of `self`.
+    ///
+    /// Panics when a coefficient is not in the image of the field.
+    pub fn from_galois_field_extension(
+        &self,
+        poly: &MultivariatePolynomial<GaloisField, E>,
+        root: &GaloisFieldElement,
+    ) -> Self {
+        let field = self.field.to_galois_field().unwrap();
+        poly.map_coeff(
+            |c| {
+                let c = field
+                    .from_extension(&poly.field, root, c)
+                    .unwrap_or_else(|| panic!("Coefficient is not in the field{}", self.field));
+                self.field.element_from_galois(&c)
+            },
+            self.field.clone(),
+        )
+    }
+}
+
+impl ConvertToRing for GaloisField {
+    fn element_from_integer(&self, number: Integer) -> Self::Element {
+        let r = number.symmetric_mod(&self.p.into());
+        let r = if r.is_negative() {
+            &r + &self.p.into()
+        } else {
+            r
+        };
+        self.nth(r.to_i64().unwrap() as u64)
+    }
+
+    fn element_from_coefficient(&self, number: Coefficient) -> Self::Element {
+        match number {
+            Coefficient::Rational(r) => self.div(
+                &self.element_from_integer(r.numerator()),
+                &self.element_from_integer(r.denominator()),
+            ),
+            Coefficient::FiniteField(_, _) => panic!("Cannot convert finite field to other one"),
+            Coefficient::RationalPolynomial(_) => {
+                panic!("Cannot convert rational polynomial to finite field")
+            }
+        }
+    }
+
+    fn element_from_coefficient_view(&self, number: CoefficientView<'_>) -> Self::Element {
+        self.element_from_coefficient(number.to_owned())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        atom::Atom,
+        domains::{finite_field::FiniteFieldLike, Field, Ring},
+        poly::factor::Factorize,
+    };
+
+    use super::GaloisField;
+
+    #[test]
+    fn aes_field() {
+        let field = GaloisField::new(2, 8);
+        assert_eq!(field.get_modulus(), &[1, 1, 0, 1, 1, 0, 0, 0, 1]);
+
+        let a = field.nth_element(0x57);
+        let b = field.nth_element(0x83);
+        assert_eq!(field.mul(&a, &b), field.nth_element(0xc1));
+        assert_eq!(field.add(&a, &b), field.nth_element(0xd4));
+        assert_eq!(field.inv(&field.nth_element(0x53)), field.nth_element(0xca));
+        assert_eq!(format!("{}", field.printer(&b)), "131");
+    }
+
+    #[test]
+    fn odd_characteristic() {
+        let field = GaloisField::new(7, 3);
+        let a = field.from_coefficients(&[3, 6, 2]);
+        let b = field.from_coefficients(&[5, 1, 4]);
+
+        assert_eq!(field.to_coefficients(&field.add(&a, &b)), vec![1, 0, 6]);
+        assert_eq!(field.sub(&field.add(&a, &b), &b), a);
+        assert_eq!(field.mul(&a, &field.inv(&a)), field.one());
+
+        let x = field.generator();
+        let m: Vec<_> = field.get_modulus().to_vec();
+        assert!(GaloisField::is_zero(&field.from_coefficients(&m)));
+        assert_eq!(field.pow(&field.pth_root(&x), 7), x);
+    }
+
+    #[test]
+    fn table_multiplication() {
+        for (p, k) in [(2, 5), (3, 4), (251, 2)] {
+            let field = GaloisField::new(p, k);
+            assert!(field.tables.is_some());
+            let plain = GaloisField::new_unchecked(p, field.get_modulus().to_vec());
+
+            for i in (0..field.size).step_by(7) {
+                let a = field.nth_element(i);
+                let b = field.nth_element((i * 13 + 5) % field.size);
+                assert_eq!(field.mul(&a, &b), plain.mul(&a, &b));
+                if i != 0 {
+                    assert_eq!(field.inv(&a), plain.inv(&a));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn factor() {
+        let field = GaloisField::new(3, 2);
+        let poly = Atom::parse("v1^9-v1")
+            .unwrap()
+            .to_polynomial::<_, u8>(&field, None);
+        let factors = poly.factor();
+        assert_eq!(factors.len(), 9);
+        assert!(factors.iter().all(|(f, p)| f.degree(0) == 1 && *p == 1));
+    }
+
+    #[test]
+    fn factor_extension() {
+        // requires an extension to find suitable sample points
+        let field = GaloisField::new(2, 1);
+        let poly = Atom::parse("(v1*(v2+v3)+1)*(v1*(v3^2+1)+v2+1)*(v1*(v4+v4^2+v2)+v4+1)")
+            .unwrap()
+            .expand()
+            .to_polynomial::<_, u8>(&field, None);
+
+        let factors = poly.factor();
+        assert_eq!(factors.len(), 3);
+        let mut r = poly.one();
+        for (f, p) in &factors {
+            assert_eq!(*p, 1);
+            r = &r * f;
+        }
+        assert_eq!(r, poly);
+    }
+}
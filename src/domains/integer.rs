@@ -5,6 +5,7 @@ use std::{
     str::FromStr,
 };
 
+use ahash::{HashMap, HashSet};
 use rand::Rng;
 use rug::{
     integer::IntegerExt64,
@@ -16,12 +17,16 @@ use crate::{printer::PrintOptions, utils};
 
 use super::{
     finite_field::{
-        FiniteField, FiniteFieldCore, FiniteFieldWorkspace, Mersenne64, ToFiniteField, Zp, Zp64,
+        is_prime_u64, FiniteField, FiniteFieldCore, FiniteFieldWorkspace, Mersenne64,
+        ToFiniteField, Zp, Zp64,
     },
     rational::Rational,
     EuclideanDomain, Ring,
 };
 
+/// The maximal number of bits of an integer for which perfect powers are extracted.
+pub const MAX_PERFECT_POWER_BITS: u32 = 1024;
+
 pub const SMALL_PRIMES: [i64; 100] = [
     2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79, 83, 89, 97,
     101, 103, 107, 109, 113, 127, 131, 137, 139, 149, 151, 157, 163, 167, 173, 179, 181, 191, 193,
@@ -616,6 +621,1002 @@ impl Integer {
 
         t0
     }
+
+    /// Check if the integer is a prime. Integers that fit in a `u64` are tested using
+    /// a deterministic Miller-Rabin test and larger integers using the Baillie-PSW test,
+    /// for which no counterexamples are known.
+    pub fn is_prime(&self) -> bool {
+        match self {
+            Integer::Natural(n) => *n > 1 && is_prime_u64(*n as u64),
+            Integer::Double(n) => {
+                if *n > 1 && *n <= u64::MAX as i128 {
+                    is_prime_u64(*n as u64)
+                } else {
+                    is_prime_bpsw(&MultiPrecisionInteger::from(*n))
+                }
+            }
+            Integer::Large(n) => is_prime_bpsw(n),
+        }
+    }
+
+    /// Factor the integer into primes. The primes are returned in ascending order
+    /// together with their multiplicity. For negative integers, `-1` is added as
+    /// the first factor.
+    ///
+    /// Small factors are found using trial division, after which factors are split off using
+    /// Pollard's rho algorithm and the elliptic curve method (ECM). Composites of at most
+    /// 72 digits that survive a short ECM run, such as balanced semiprimes, are split with the
+    /// self-initializing quadratic sieve (SIQS). Larger composites rely on ECM alone,
+    /// whose work is bounded by [ECM_MAX_B1]. An error is returned if a composite factor
+    /// could not be split within this bound.
+    pub fn factor(&self) -> Result<Vec<(Integer, u32)>, String> {
+        if self.is_zero() {
+            return Ok(vec![(Integer::zero(), 1)]);
+        }
+
+        let mut factors = vec![];
+        if self.is_negative() {
+            factors.push((Integer::Natural(-1), 1));
+        }
+
+        let (mut primes, rest) = self.abs().trial_division();
+        factor_rec(rest.to_multi_prec(), &mut primes)?;
+        primes.sort();
+
+        for p in primes {
+            if let Some((last, e)) = factors.last_mut() {
+                if *last == p {
+                    *e += 1;
+                    continue;
+                }
+            }
+            factors.push((p, 1));
+        }
+
+        Ok(factors)
+    }
+
+    /// Remove all factors from [SMALL_PRIMES] from the positive integer `self`.
+    /// Returns the prime factors, with repetition, and the remaining cofactor.
+    fn trial_division(mut self) -> (Vec<Integer>, Integer) {
+        let mut primes = vec![];
+        for p in SMALL_PRIMES {
+            let p = Integer::Natural(p);
+            loop {
+                let (q, r) = Z.quot_rem(&self, &p);
+                if !r.is_zero() {
+                    break;
+                }
+                primes.push(p.clone());
+                self = q;
+            }
+
+            if self.is_one() {
+                break;
+            }
+        }
+
+        (primes, self)
+    }
+
+    /// Write the absolute value of `self` as `a^n*b`, where `a` is as large as
+    /// can be determined cheaply. Integers that fit in a `u64` are fully factored,
+    /// for larger integers only small prime factors are taken into account
+    /// and the cofactor is tested for being a perfect power. Integers with more than
+    /// [MAX_PERFECT_POWER_BITS] bits are not split.
+    pub(crate) fn split_perfect_power(&self, n: u32) -> (Integer, Integer) {
+        let abs = self.abs();
+        if let Integer::Large(l) = &abs {
+            if l.significant_bits() > MAX_PERFECT_POWER_BITS {
+                return (Integer::one(), abs);
+            }
+        }
+
+        let (primes, rest) = if abs <= Integer::from(u64::MAX) {
+            (
+                abs.factor()
+                    .unwrap() // integers that fit in a u64 are always fully factored
+                    .into_iter()
+                    .flat_map(|(p, e)| std::iter::repeat(p).take(e as usize))
+                    .collect(),
+                Integer::one(),
+            )
+        } else {
+            abs.trial_division()
+        };
+
+        let mut a = Integer::one();
+        let mut b = Integer::one();
+        let mut i = 0;
+        while i < primes.len() {
+            let mut e = 1;
+            while i + e < primes.len() && primes[i + e] == primes[i] {
+                e += 1;
+            }
+
+            a *= &primes[i].pow(e as u64 / n as u64);
+            b *= &primes[i].pow(e as u64 % n as u64);
+            i += e;
+        }
+
+        let rest = rest.to_multi_prec();
+        let root = rest.clone().root(n);
+        if root.clone().pow(n) == rest {
+            a *= &Integer::from_large(root);
+        } else {
+            b *= &Integer::from_large(rest);
+        }
+
+        (a, b)
+    }
+}
+
+/// Split the positive integer `n` that has no small prime factors into primes.
+fn factor_rec(n: MultiPrecisionInteger, primes: &mut Vec<Integer>) -> Result<(), String> {
+    if n == 1 {
+        return Ok(());
+    }
+
+    if let Some(n) = n.to_u64() {
+        factor_u64(n, primes);
+        return Ok(());
+    }
+
+    if is_prime_bpsw(&n) {
+        primes.push(Integer::from_large(n));
+        return Ok(());
+    }
+
+    let Some(d) = pollard_brent(&n, 1 << 16)
+        .or_else(|| {
+            if n.significant_bits() <= SIQS_MAX_BITS {
+                ecm(&n, 2000).or_else(|| siqs(&n))
+            } else {
+                None
+            }
+        })
+        .or_else(|| ecm(&n, ECM_MAX_B1))
+    else {
+        return Err(format!(
+            "Could not split the composite factor {} within the ECM bound B1 = {}",
+            n, ECM_MAX_B1
+        ));
+    };
+
+    let q = (&n / &d).complete();
+    factor_rec(d, primes)?;
+    factor_rec(q, primes)
+}
+
+/// Split the odd integer `n` into primes.
+fn factor_u64(n: u64, primes: &mut Vec<Integer>) {
+    if n == 1 {
+        return;
+    }
+
+    if is_prime_u64(n) {
+        primes.push(n.into());
+        return;
+    }
+
+    let d = pollard_brent_u64(n);
+    factor_u64(d, primes);
+    factor_u64(n / d, primes);
+}
+
+/// Find a non-trivial factor of the odd composite `n` using Brent's variant of Pollard's rho algorithm.
+fn pollard_brent_u64(n: u64) -> u64 {
+    let f = Zp64::new(n);
+    let gcd = |a: u64| utils::gcd_unsigned(a, n);
+
+    for c in 1.. {
+        let c = f.to_element(c);
+        let mut x = f.to_element(2);
+        let mut y = x;
+        let mut ys = x;
+        let mut q = f.one();
+        let mut g = 1;
+        let mut r = 1;
+
+        while g == 1 {
+            x = y;
+            for _ in 0..r {
+                y = f.add(&f.mul(&y, &y), &c);
+            }
+
+            let mut k = 0;
+            while k < r && g == 1 {
+                ys = y;
+                for _ in 0..128.min(r - k) {
+                    y = f.add(&f.mul(&y, &y), &c);
+                    q = f.mul(&q, &f.sub(&x, &y));
+                }
+                g = gcd(q.0);
+                k += 128;
+            }
+            r *= 2;
+        }
+
+        if g == n {
+            // backtrack to find the factor
+            loop {
+                ys = f.add(&f.mul(&ys, &ys), &c);
+                g = gcd(f.sub(&x, &ys).0);
+                if g > 1 {
+                    break;
+                }
+            }
+        }
+
+        if g != n {
+            return g;
+        }
+    }
+
+    unreachable!()
+}
+
+/// Find a non-trivial factor of the odd composite `n` using Brent's variant of Pollard's rho algorithm,
+/// using at most `max_iterations` iterations.
+fn pollard_brent(n: &MultiPrecisionInteger, max_iterations: u64) -> Option<MultiPrecisionInteger> {
+    let step = |y: &MultiPrecisionInteger, c: u64| (y.square_ref().complete() + c) % n;
+
+    for c in 1..4 {
+        let mut x;
+        let mut y = MultiPrecisionInteger::from(2);
+        let mut ys = y.clone();
+        let mut q = MultiPrecisionInteger::from(1);
+        let mut g = MultiPrecisionInteger::from(1);
+        let mut r = 1;
+
+        while g == 1 && r <= max_iterations {
+            x = y.clone();
+            for _ in 0..r {
+                y = step(&y, c);
+            }
+
+            let mut k = 0;
+            while k < r && g == 1 {
+                ys = y.clone();
+                for _ in 0..128.min(r - k) {
+                    y = step(&y, c);
+                    q = (q * (&x - &y).complete()) % n;
+                }
+                g = q.gcd_ref(n).complete();
+                k += 128;
+            }
+            r *= 2;
+
+            if g == *n {
+                // backtrack to find the factor
+                loop {
+                    ys = step(&ys, c);
+                    g = (&x - &ys).complete().gcd(n);
+                    if g != 1 {
+                        break;
+                    }
+                }
+            }
+        }
+
+        if g != 1 && g != *n {
+            return Some(g);
+        }
+    }
+
+    None
+}
+
+/// A point in projective `(X:Z)` coordinates on a Montgomery curve.
+type MontgomeryPoint = (MultiPrecisionInteger, MultiPrecisionInteger);
+
+/// Find a non-trivial factor of the odd composite `n` using Lenstra's elliptic curve
+/// method on Montgomery curves with Suyama's parametrization. The second stage uses
+/// a baby-step giant-step continuation with bound `B2 = 100*B1`. The bound `B1` is
+/// increased until it exceeds `max_b1`, after which `None` is returned.
+///
+/// The primes of the second stage are generated with a segmented sieve and stored as
+/// one bit per pair of giant and baby step, so that the memory use is `O(B2/log(B2))` bits.
+fn ecm(n: &MultiPrecisionInteger, max_b1: u64) -> Option<MultiPrecisionInteger> {
+    let mut rng = rand::thread_rng();
+
+    // check for perfect powers, which ECM cannot split
+    for k in 2..n.significant_bits() {
+        let r = n.clone().root(k);
+        if r < 2 {
+            break;
+        }
+        if r.clone().pow(k) == *n {
+            return Some(r);
+        }
+    }
+
+    const W: u64 = 210;
+    let baby_steps: Vec<_> = (1..W / 2)
+        .step_by(2)
+        .filter(|j| utils::gcd_unsigned(*j, W) == 1)
+        .collect();
+
+    let mut baby_index = [u32::MAX; W as usize / 2];
+    for (i, j) in baby_steps.iter().enumerate() {
+        baby_index[*j as usize] = i as u32;
+    }
+
+    let mut b1 = 2000;
+    while b1 <= max_b1 {
+        let b2 = b1.saturating_mul(100);
+        let primes = primes_up_to(b1);
+
+        // for every giant step m, mark the baby steps j for which m*W+j or m*W-j is prime
+        let m_start = b1 / W;
+        let m_end = b2 / W;
+        let mut stage2_pairs = vec![0u32; (m_end - m_start + 1) as usize];
+        let mut lo = m_start * W - W / 2;
+        while lo <= m_end * W + W / 2 {
+            let hi = (lo + SIEVE_SEGMENT).min(m_end * W + W / 2 + 1);
+            for (k, is_prime) in (lo..).zip(sieve_segment(lo, hi, &primes)) {
+                if !is_prime || k <= b1 {
+                    continue;
+                }
+
+                let m = (k + W / 2) / W;
+                let j = k.abs_diff(m * W);
+                if baby_index[j as usize] != u32::MAX {
+                    stage2_pairs[(m - m_start) as usize] |= 1 << baby_index[j as usize];
+                }
+            }
+            lo = hi;
+        }
+
+        for _ in 0..25 {
+            let sigma = MultiPrecisionInteger::from(rng.gen_range(6..u32::MAX));
+            let u = (sigma.square_ref().complete() - 5u32) % n;
+            let v = (sigma * 4u32) % n;
+
+            let x = u.clone().pow_mod(&3u32.into(), n).unwrap();
+            let z = v.clone().pow_mod(&3u32.into(), n).unwrap();
+
+            let num = ((&v - &u).complete().pow_mod(&3u32.into(), n).unwrap()
+                * (&u * 3u32 + &v).complete())
+                % n;
+            let den = (&x * &v).complete() * 16u32 % n;
+            let a24 = match den.clone().invert(n) {
+                Ok(inv) => num * inv % n,
+                Err(den) => {
+                    let g = den.gcd(n);
+                    if g != 1 && g != *n {
+                        return Some(g);
+                    }
+                    continue;
+                }
+            };
+
+            // stage 1: multiply by all prime powers up to b1
+            let mut q = (x, z);
+            for &p in &primes {
+                let mut k = p;
+                while k * p <= b1 {
+                    k *= p;
+                }
+                q = montgomery_ladder(k, &q, &a24, n);
+            }
+
+            let g = q.1.gcd_ref(n).complete();
+            if g != 1 {
+                if g != *n {
+                    return Some(g);
+                }
+                continue;
+            }
+
+            // stage 2: find primes m*W+-j in (b1, b2] for which (m*W)*q = +-j*q
+            let q2 = xdbl(&q, &a24, n);
+            let mut baby = vec![q.clone()];
+            let mut prev = q.clone();
+            let mut cur = xadd(&q2, &q, &q, n);
+            for j in (3..W / 2).step_by(2) {
+                if baby_steps.contains(&j) {
+                    baby.push(cur.clone());
+                }
+                let next = xadd(&cur, &q2, &prev, n);
+                prev = std::mem::replace(&mut cur, next);
+            }
+
+            let w_point = montgomery_ladder(W, &q, &a24, n);
+            let mut giant_prev = montgomery_ladder((m_start - 1) * W, &q, &a24, n);
+            let mut giant = montgomery_ladder(m_start * W, &q, &a24, n);
+
+            let mut acc = MultiPrecisionInteger::from(1);
+            for pairs in &stage2_pairs {
+                for (i, b) in baby.iter().enumerate() {
+                    if (pairs >> i) & 1 == 1 {
+                        acc =
+                            acc * ((&giant.0 * &b.1).complete() - (&b.0 * &giant.1).complete()) % n;
+                    }
+                }
+
+                let next = xadd(&giant, &w_point, &giant_prev, n);
+                giant_prev = std::mem::replace(&mut giant, next);
+            }
+
+            let g = acc.gcd(n);
+            if g != 1 && g != *n {
+                return Some(g);
+            }
+        }
+
+        b1 = b1.saturating_mul(5);
+    }
+
+    None
+}
+
+/// The size of the segments of the sieve of Eratosthenes used in the second stage of [ecm].
+const SIEVE_SEGMENT: u64 = 1 << 16;
+
+/// Get all primes up to and including `n` using the sieve of Eratosthenes.
+fn primes_up_to(n: u64) -> Vec<u64> {
+    let mut sieve = vec![true; n as usize + 1];
+    let mut primes = vec![];
+    for p in 2..=n as usize {
+        if sieve[p] {
+            primes.push(p as u64);
+            for m in (p * p..=n as usize).step_by(p) {
+                sieve[m] = false;
+            }
+        }
+    }
+    primes
+}
+
+/// Determine which integers in `[lo, hi)`, with `lo >= 2`, are prime, given all
+/// primes up to at least `sqrt(hi)` in `primes`.
+fn sieve_segment(lo: u64, hi: u64, primes: &[u64]) -> Vec<bool> {
+    let mut sieve = vec![true; (hi - lo) as usize];
+    for &p in primes.iter().take_while(|p| *p * *p < hi) {
+        let start = (p * p).max(lo.div_ceil(p) * p);
+        for m in (start..hi).step_by(p as usize) {
+            sieve[(m - lo) as usize] = false;
+        }
+    }
+    sieve
+}
+
+/// Double the point `p` on the Montgomery curve with `a24 = (A+2)/4` modulo `n`.
+fn xdbl(
+    (x, z): &MontgomeryPoint,
+    a24: &MultiPrecisionInteger,
+    n: &MultiPrecisionInteger,
+) -> MontgomeryPoint {
+    let t1 = (x + z).complete().square() % n;
+    let t2 = (x - z).complete().square() % n;
+    let t3 = (&t1 - &t2).complete();
+    let z2 = (&t3 * (t2.clone() + (a24 * &t3).complete())) % n;
+    ((t1 * t2) % n, z2)
+}
+
+/// Add the points `p` and `q` on a Montgomery curve modulo `n`, where `diff = p - q`.
+fn xadd(
+    (xp, zp): &MontgomeryPoint,
+    (xq, zq): &MontgomeryPoint,
+    diff: &MontgomeryPoint,
+    n: &MultiPrecisionInteger,
+) -> MontgomeryPoint {
+    let u = (xp - zp).complete() * (xq + zq).complete();
+    let v = (xp + zp).complete() * (xq - zq).complete();
+    let x = (&u + &v).complete().square() * &diff.1 % n;
+    let z = (u - v).square() * &diff.0 % n;
+    (x, z)
+}
+
+/// Compute `k*p` for a point `p` on the Montgomery curve with `a24 = (A+2)/4` modulo `n`.
+fn montgomery_ladder(
+    k: u64,
+    p: &MontgomeryPoint,
+    a24: &MultiPrecisionInteger,
+    n: &MultiPrecisionInteger,
+) -> MontgomeryPoint {
+    let mut r0 = p.clone();
+    let mut r1 = xdbl(p, a24, n);
+    for i in (0..63 - k.leading_zeros()).rev() {
+        if k & (1 << i) != 0 {
+            r0 = xadd(&r1, &r0, p, n);
+            r1 = xdbl(&r1, a24, n);
+        } else {
+            r1 = xadd(&r1, &r0, p, n);
+            r0 = xdbl(&r0, a24, n);
+        }
+    }
+
+    r0
+}
+
+/// The maximal bound `B1` of the first stage of [ecm] used in [Integer::factor]. It bounds
+/// the total work, as a bound `B1` uses `25` curves and `O(B1)` memory.
+pub const ECM_MAX_B1: u64 = 1_250_000;
+
+/// The maximal number of bits of a composite that is split using [siqs].
+const SIQS_MAX_BITS: u32 = 239;
+
+/// Parameters of the self-initializing quadratic sieve: the maximal number of decimal digits
+/// of `n`, the size of the factor base and the half-width of the sieve interval.
+const SIQS_PARAMETERS: [(u32, usize, i64); 9] = [
+    (24, 100, 16384),
+    (30, 200, 16384),
+    (36, 400, 32768),
+    (42, 600, 32768),
+    (48, 1000, 32768),
+    (54, 1500, 65536),
+    (60, 2200, 65536),
+    (66, 3500, 65536),
+    (72, 5000, 98304),
+];
+
+/// A relation `x^2 = large^2 * prod_i p_i (mod n)` found by the quadratic sieve,
+/// where the `p_i` are indices into the factor base and index 0 represents `-1`.
+struct SiqsRelation {
+    x: MultiPrecisionInteger,
+    exponents: Vec<usize>,
+    large: u64,
+}
+
+/// Compute `b^e mod m` for `m < 2^32`.
+fn pow_mod_u32(mut b: u64, mut e: u64, m: u64) -> u64 {
+    let mut r = 1 % m;
+    b %= m;
+    while e > 0 {
+        if e & 1 == 1 {
+            r = r * b % m;
+        }
+        b = b * b % m;
+        e >>= 1;
+    }
+    r
+}
+
+/// Compute a square root of the quadratic residue `a` modulo the odd prime `p < 2^32`
+/// using the Tonelli-Shanks algorithm.
+fn sqrt_mod_prime(a: u64, p: u64) -> u64 {
+    if a == 0 {
+        return 0;
+    }
+
+    let mut q = p - 1;
+    let mut s = 0;
+    while q % 2 == 0 {
+        q /= 2;
+        s += 1;
+    }
+
+    let mut z = 2;
+    while pow_mod_u32(z, (p - 1) / 2, p) != p - 1 {
+        z += 1;
+    }
+
+    let mut m = s;
+    let mut c = pow_mod_u32(z, q, p);
+    let mut t = pow_mod_u32(a, q, p);
+    let mut r = pow_mod_u32(a, q.div_ceil(2), p);
+    while t != 1 {
+        let mut i = 0;
+        let mut t2 = t;
+        while t2 != 1 {
+            t2 = t2 * t2 % p;
+            i += 1;
+        }
+
+        let b = pow_mod_u32(c, 1 << (m - i - 1), p);
+        m = i;
+        c = b * b % p;
+        t = t * c % p;
+        r = r * b % p;
+    }
+
+    r
+}
+
+/// Find a non-trivial factor of the odd composite `n`, which should not be a perfect power,
+/// using the self-initializing quadratic sieve with the single large prime variation.
+///
+/// The polynomials `Q(x) = ((A*x+B)^2-n)/A` are sieved over `[-M, M)`, where `A` is a product of
+/// factor base primes and the `2^(s-1)` values of `B` for every `A` are visited in Gray code
+/// order, so that the sieve roots can be updated with a single addition per prime.
+/// Returns `None` if `n` has more than 72 digits or if all dependencies are trivial.
+fn siqs(n: &MultiPrecisionInteger) -> Option<MultiPrecisionInteger> {
+    let digits = (n.significant_bits() as f64 * std::f64::consts::LOG10_2).ceil() as u32;
+    let &(_, fb_size, m) = SIQS_PARAMETERS.iter().find(|(d, _, _)| digits <= *d)?;
+
+    // the factor base consists of 2 and the odd primes for which n is a quadratic residue
+    let mut primes = vec![2u64];
+    let mut roots = vec![1u64];
+    let mut p = 3;
+    while primes.len() < fb_size {
+        if is_prime_u64(p) {
+            let r = n.mod_u(p as u32) as u64;
+            if r == 0 {
+                return Some(p.into());
+            }
+            if pow_mod_u32(r, (p - 1) / 2, p) == 1 {
+                primes.push(p);
+                roots.push(sqrt_mod_prime(r, p));
+            }
+        }
+        p += 2;
+    }
+
+    let logs: Vec<u8> = primes
+        .iter()
+        .map(|p| (*p as f64).log2().round() as u8)
+        .collect();
+    let p_max = *primes.last().unwrap();
+    let large_prime_bound = 64 * p_max;
+
+    // |Q(x)| is at most M*sqrt(n/2); allow for a large prime and for the unsieved powers of small primes
+    let threshold = ((m as f64).log2() + n.significant_bits() as f64 / 2.
+        - 0.5
+        - (large_prime_bound as f64).log2()
+        - 4.)
+        .max(0.) as u8;
+
+    // A should be close to sqrt(2n)/M and consist of s primes of roughly the same size
+    let ln_target = ((n * 2u32).complete().sqrt() / m).to_f64().ln();
+    let q_preferred = (p_max as f64 / 2.).min(2000.);
+    let s = ((ln_target / q_preferred.ln()).ceil() as usize).max(1);
+    let q_ideal = (ln_target / s as f64).exp();
+    let mut candidates: Vec<usize> = (1..primes.len())
+        .filter(|i| primes[*i] as f64 > q_ideal / 2. && (primes[*i] as f64) < 2. * q_ideal)
+        .collect();
+    if candidates.len() < s + 2 {
+        candidates = (primes.len() / 2..primes.len()).collect();
+    }
+
+    let mut rng = rand::thread_rng();
+    let mut used_a = HashSet::default();
+    let mut relations = vec![];
+    let mut partials: HashMap<u64, SiqsRelation> = HashMap::default();
+    let mut sieve = vec![0u8; 2 * m as usize];
+    let mut soln1 = vec![0u64; primes.len()];
+    let mut soln2 = vec![0u64; primes.len()];
+    let mut b_ainv = vec![vec![0u64; primes.len()]; s];
+
+    'collect: while relations.len() < primes.len() + 10 {
+        // select a new value of A
+        let mut a_factors;
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+            if attempts > 1000 {
+                return None;
+            }
+
+            a_factors = vec![];
+            let mut ln_a = 0.;
+            while a_factors.len() + 1 < s {
+                let i = candidates[rng.gen_range(0..candidates.len())];
+                if !a_factors.contains(&i) {
+                    a_factors.push(i);
+                    ln_a += (primes[i] as f64).ln();
+                }
+            }
+
+            // choose the last prime such that A is as close to the target as possible
+            let rest = ln_target - ln_a;
+            let last = (1..primes.len())
+                .filter(|i| !a_factors.contains(i))
+                .min_by(|i, j| {
+                    ((primes[*i] as f64).ln() - rest)
+                        .abs()
+                        .total_cmp(&((primes[*j] as f64).ln() - rest).abs())
+                })
+                .unwrap();
+            a_factors.push(last);
+            a_factors.sort_unstable();
+
+            if used_a.insert(a_factors.clone()) {
+                break;
+            }
+        }
+
+        let a = a_factors
+            .iter()
+            .fold(MultiPrecisionInteger::from(1), |acc, i| acc * primes[*i]);
+
+        // B = sum_l B_l with B_l^2 = n (mod q_l) and B_l = 0 (mod q_k) for k != l
+        let b_parts: Vec<_> = a_factors
+            .iter()
+            .map(|i| {
+                let q = primes[*i];
+                let a_q = (&a / q).complete();
+                let inv = pow_mod_u32(a_q.mod_u(q as u32) as u64, q - 2, q);
+                let mut gamma = roots[*i] * inv % q;
+                if gamma > q / 2 {
+                    gamma = q - gamma;
+                }
+                a_q * gamma
+            })
+            .collect();
+        let mut b: MultiPrecisionInteger = b_parts.iter().sum();
+        let mut signs = vec![true; s];
+
+        for j in 1..primes.len() {
+            let p = primes[j];
+            let a_mod = a.mod_u(p as u32) as u64;
+            if a_mod == 0 {
+                continue;
+            }
+
+            let a_inv = pow_mod_u32(a_mod, p - 2, p);
+            let b_mod = b.mod_u(p as u32) as u64;
+            soln1[j] = a_inv * ((roots[j] + p - b_mod) % p) % p;
+            soln2[j] = a_inv * ((2 * p - roots[j] - b_mod) % p) % p;
+            for (l, b_l) in b_parts.iter().enumerate() {
+                b_ainv[l][j] = 2 * b_l.mod_u(p as u32) as u64 % p * a_inv % p;
+            }
+        }
+
+        for poly in 0..1usize << (s - 1) {
+            if poly > 0 {
+                // flip the sign of B_v, which shifts the roots by -+2*B_v/A
+                let v = poly.trailing_zeros() as usize;
+                if signs[v] {
+                    b -= (&b_parts[v] * 2u32).complete();
+                } else {
+                    b += (&b_parts[v] * 2u32).complete();
+                }
+
+                for j in 1..primes.len() {
+                    let p = primes[j];
+                    let shift = if signs[v] {
+                        b_ainv[v][j]
+                    } else {
+                        p - b_ainv[v][j]
+                    };
+                    soln1[j] = (soln1[j] + shift) % p;
+                    soln2[j] = (soln2[j] + shift) % p;
+                }
+                signs[v] = !signs[v];
+            }
+
+            sieve.fill(0);
+            for j in 1..primes.len() {
+                if a_factors.binary_search(&j).is_ok() {
+                    continue;
+                }
+
+                let p = primes[j] as usize;
+                for r in [soln1[j], soln2[j]] {
+                    for e in sieve[(r as usize + m as usize) % p..].iter_mut().step_by(p) {
+                        *e = e.wrapping_add(logs[j]);
+                    }
+                }
+            }
+
+            for (index, _) in sieve.iter().enumerate().filter(|(_, e)| **e >= threshold) {
+                let x = index as i64 - m;
+                let ax_b = (&a * x).complete() + &b;
+                let mut q = (ax_b.square_ref().complete() - n).div_exact(&a);
+                if q == 0 {
+                    continue;
+                }
+
+                // factor A*Q(x) over the factor base
+                let mut exponents: Vec<_> = a_factors.iter().map(|i| i + 1).collect();
+                if q < 0 {
+                    exponents.push(0);
+                    q = -q;
+                }
+
+                let twos = q.find_one(0).unwrap();
+                exponents.extend(std::iter::repeat(1).take(twos as usize));
+                q >>= twos;
+
+                for j in 1..primes.len() {
+                    let p = primes[j];
+                    let x_mod = x.rem_euclid(p as i64) as u64;
+                    if x_mod == soln1[j] || x_mod == soln2[j] || a_factors.binary_search(&j).is_ok()
+                    {
+                        while q.is_divisible_u(p as u32) {
+                            q.div_exact_u_mut(p as u32);
+                            exponents.push(j + 1);
+                        }
+                    }
+                }
+
+                if q == 1 {
+                    relations.push(SiqsRelation {
+                        x: ax_b,
+                        exponents,
+                        large: 1,
+                    });
+                } else if let Some(large) = q.to_u64().filter(|l| *l < large_prime_bound) {
+                    // combine two relations with the same large prime
+                    if let Some(other) = partials.remove(&large) {
+                        exponents.extend(other.exponents);
+                        relations.push(SiqsRelation {
+                            x: ax_b * other.x % n,
+                            exponents,
+                            large,
+                        });
+                    } else {
+                        partials.insert(
+                            large,
+                            SiqsRelation {
+                                x: ax_b,
+                                exponents,
+                                large,
+                            },
+                        );
+                    }
+                }
+
+                if relations.len() >= primes.len() + 10 {
+                    break 'collect;
+                }
+            }
+        }
+    }
+
+    // find dependencies among the exponent vectors modulo 2 using Gaussian elimination,
+    // keeping track of the combination of relations in every row
+    let cols = primes.len() + 1;
+    let rows = relations.len();
+    let mut matrix: Vec<_> = relations
+        .iter()
+        .enumerate()
+        .map(|(i, r)| {
+            let mut v = vec![0u64; cols.div_ceil(64)];
+            for e in &r.exponents {
+                v[e / 64] ^= 1 << (e % 64);
+            }
+            let mut h = vec![0u64; rows.div_ceil(64)];
+            h[i / 64] |= 1 << (i % 64);
+            (v, h)
+        })
+        .collect();
+
+    let mut rank = 0;
+    for c in 0..cols {
+        let Some(pivot) = (rank..rows).find(|r| (matrix[*r].0[c / 64] >> (c % 64)) & 1 == 1) else {
+            continue;
+        };
+        matrix.swap(rank, pivot);
+
+        let (pivot_rows, rest) = matrix.split_at_mut(rank + 1);
+        let (pv, ph) = &pivot_rows[rank];
+        for (v, h) in rest {
+            if (v[c / 64] >> (c % 64)) & 1 == 1 {
+                v.iter_mut().zip(pv).for_each(|(a, b)| *a ^= b);
+                h.iter_mut().zip(ph).for_each(|(a, b)| *a ^= b);
+            }
+        }
+        rank += 1;
+    }
+
+    for (_, h) in &matrix[rank..] {
+        let mut x = MultiPrecisionInteger::from(1);
+        let mut y = MultiPrecisionInteger::from(1);
+        let mut counts = vec![0u32; cols];
+        for (i, r) in relations.iter().enumerate() {
+            if (h[i / 64] >> (i % 64)) & 1 == 1 {
+                x = x * &r.x % n;
+                y = y * r.large % n;
+                for e in &r.exponents {
+                    counts[*e] += 1;
+                }
+            }
+        }
+
+        for (p, c) in primes.iter().zip(&counts[1..]) {
+            let f = MultiPrecisionInteger::from(*p)
+                .pow_mod(&(c / 2).into(), n)
+                .unwrap();
+            y = y * f % n;
+        }
+
+        let g = (x - y).gcd(n);
+        if g != 1 && g != *n {
+            return Some(g);
+        }
+    }
+
+    None
+}
+
+/// Test if `n` is a prime using the Baillie-PSW test: trial division by small primes,
+/// a strong probable prime test to base 2 and a strong Lucas probable prime test.
+fn is_prime_bpsw(n: &MultiPrecisionInteger) -> bool {
+    if *n < 2 {
+        return false;
+    }
+
+    for p in SMALL_PRIMES {
+        if *n == p {
+            return true;
+        }
+        if n.is_divisible_u(p as u32) {
+            return false;
+        }
+    }
+
+    // strong probable prime test to base 2
+    let n_minus_one = (n - 1u32).complete();
+    let s = n_minus_one.find_one(0).unwrap();
+    let d = (&n_minus_one >> s).complete();
+    let mut x = MultiPrecisionInteger::from(2).pow_mod(&d, n).unwrap();
+    if x != 1 && x != n_minus_one {
+        let mut composite = true;
+        for _ in 1..s {
+            x = x.square() % n;
+            if x == n_minus_one {
+                composite = false;
+                break;
+            }
+        }
+
+        if composite {
+            return false;
+        }
+    }
+
+    // strong Lucas probable prime test with Selfridge's parameters
+    if n.is_perfect_square() {
+        return false;
+    }
+
+    let mut dd = 5i64;
+    loop {
+        let j = MultiPrecisionInteger::from(dd).jacobi(n);
+        if j == -1 {
+            break;
+        }
+        if j == 0 && *n != dd.unsigned_abs() {
+            return false;
+        }
+        dd = if dd > 0 { -(dd + 2) } else { -dd + 2 };
+    }
+
+    let q = (1 - dd) / 4;
+    let half = |x: MultiPrecisionInteger| -> MultiPrecisionInteger {
+        if x.is_odd() {
+            (x + n) >> 1
+        } else {
+            x >> 1
+        }
+    };
+
+    let n_plus_one = (n + 1u32).complete();
+    let s = n_plus_one.find_one(0).unwrap();
+    let d = (&n_plus_one >> s).complete();
+
+    let mut u = MultiPrecisionInteger::from(1);
+    let mut v = MultiPrecisionInteger::from(1);
+    let mut qk = MultiPrecisionInteger::from(q).rem_euc(n);
+    for i in (0..d.significant_bits() - 1).rev() {
+        u = (&u * &v).complete() % n;
+        v = (v.square() - (&qk * 2u32).complete()).rem_euc(n);
+        qk = qk.square() % n;
+
+        if d.get_bit(i) {
+            let u2 = half((&u + &v).complete()).rem_euc(n);
+            v = half((&u * dd).complete() + &v).rem_euc(n);
+            u = u2;
+            qk = (qk * q).rem_euc(n);
+        }
+    }
+
+    if u == 0 || v == 0 {
+        return true;
+    }
+
+    for _ in 1..s {
+        v = (v.square() - (&qk * 2u32).complete()).rem_euc(n);
+        if v == 0 {
+            return true;
+        }
+        qk = qk.square() % n;
+    }
+
+    false
 }
 
 impl Display for Integer {
@@ -1575,3 +2576,101 @@ impl EuclideanDomain for MultiPrecisionIntegerRing {
         a.clone().gcd(b)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use rug::Integer as MultiPrecisionInteger;
+
+    use super::{ecm, is_prime_bpsw, primes_up_to, sieve_segment, siqs, Integer};
+    use crate::domains::finite_field::is_prime_u64;
+
+    #[test]
+    fn is_prime() {
+        for n in 0..20000u64 {
+            assert_eq!(is_prime_bpsw(&n.into()), is_prime_u64(n), "{}", n);
+        }
+
+        // strong pseudoprime to bases 2, 3, 5 and 7
+        assert!(!is_prime_bpsw(&3215031751u64.into()));
+
+        let m127 = MultiPrecisionInteger::from(MultiPrecisionInteger::from(1) << 127) - 1u32;
+        assert!(Integer::from_large(m127.clone()).is_prime());
+        assert!(!Integer::from_large(m127.clone() * (m127 + 2u32)).is_prime());
+        assert!(!Integer::new(-7).is_prime());
+    }
+
+    #[test]
+    fn factor() {
+        let r = Integer::new(-360).factor().unwrap();
+        assert_eq!(
+            r,
+            vec![
+                (Integer::new(-1), 1),
+                (Integer::new(2), 3),
+                (Integer::new(3), 2),
+                (Integer::new(5), 1)
+            ]
+        );
+
+        let r = Integer::from((1u64 << 63) - 25).factor().unwrap();
+        assert!(r.iter().all(|(p, _)| p.is_prime()));
+        assert_eq!(
+            r.iter()
+                .fold(Integer::one(), |acc, (p, e)| &acc * &p.pow(*e as u64)),
+            Integer::from((1u64 << 63) - 25)
+        );
+
+        // 2^128+1
+        let n = Integer::from_large(
+            MultiPrecisionInteger::from(MultiPrecisionInteger::from(1) << 128) + 1u32,
+        );
+        assert_eq!(
+            n.factor().unwrap(),
+            vec![
+                (Integer::from(59649589127497217u64), 1),
+                (
+                    Integer::from_large("5704689200685129054721".parse().unwrap()),
+                    1
+                )
+            ]
+        );
+    }
+
+    #[test]
+    fn ecm_segmented_sieve() {
+        let primes = primes_up_to(1000);
+        assert_eq!(primes.len(), 168);
+
+        let segment = sieve_segment(100_000, 101_000, &primes);
+        for (k, p) in (100_000..101_000).zip(segment) {
+            assert_eq!(p, is_prime_u64(k), "{}", k);
+        }
+
+        let p = 10_000_000_019u64;
+        let n = MultiPrecisionInteger::from(p) * ((1u64 << 61) - 1);
+        let d = ecm(&n, 50_000).unwrap();
+        assert!(d == p || d == (1u64 << 61) - 1);
+    }
+
+    #[test]
+    fn siqs_balanced_semiprime() {
+        let next_prime = |mut n: u64| {
+            while !is_prime_u64(n) {
+                n += 1;
+            }
+            n
+        };
+
+        let p = next_prime(1_000_000_000_000_000);
+        let q = next_prime(3_000_000_000_000_000);
+        let n = MultiPrecisionInteger::from(p) * q;
+
+        let d = siqs(&n).unwrap();
+        assert!(d == p || d == q);
+
+        assert_eq!(
+            Integer::from_large(n).factor().unwrap(),
+            vec![(Integer::from(p), 1), (Integer::from(q), 1)]
+        );
+    }
+}
@@ -18,6 +18,7 @@ use crate::{
 };
 
 use super::{
+    algebraic_number::AlgebraicNumberRing,
     finite_field::{FiniteField, FiniteFieldCore, FiniteFieldWorkspace, ToFiniteField},
     integer::{IntegerRing, Z},
     rational::RationalField,
@@ -286,6 +287,51 @@ where
     }
 }
 
+impl<E: Exponent>
+    FromNumeratorAndDenominator<
+        AlgebraicNumberRing<RationalField>,
+        AlgebraicNumberRing<RationalField>,
+        E,
+    > for RationalPolynomial<AlgebraicNumberRing<RationalField>, E>
+{
+    fn from_num_den(
+        mut num: MultivariatePolynomial<AlgebraicNumberRing<RationalField>, E>,
+        mut den: MultivariatePolynomial<AlgebraicNumberRing<RationalField>, E>,
+        field: &AlgebraicNumberRing<RationalField>,
+        do_gcd: bool,
+    ) -> Self {
+        num.unify_variables(&mut den);
+
+        if den.is_one() {
+            RationalPolynomial {
+                numerator: num,
+                denominator: den,
+            }
+        } else {
+            if do_gcd {
+                let gcd = num.gcd(&den);
+
+                if !gcd.is_one() {
+                    num = num / &gcd;
+                    den = den / &gcd;
+                }
+            }
+
+            // normalize denominator to have leading coefficient of one
+            if !field.is_one(&den.lcoeff()) {
+                let c = field.inv(&den.lcoeff());
+                num = num.mul_coeff(c.clone());
+                den = den.mul_coeff(c);
+            }
+
+            RationalPolynomial {
+                numerator: num,
+                denominator: den,
+            }
+        }
+    }
+}
+
 impl<R: EuclideanDomain + PolynomialGCD<E>, E: Exponent> RationalPolynomial<R, E>
 where
     Self: FromNumeratorAndDenominator<R, R, E>,
@@ -1368,4 +1414,36 @@ mod test {
             ]
         );
     }
+
+    #[test]
+    fn apart_number_field() {
+        use crate::atom::Atom;
+        use crate::domains::algebraic_number::AlgebraicNumberRing;
+
+        let ring = Atom::parse("a^2-2")
+            .unwrap()
+            .to_polynomial::<_, u8>(&Q, None);
+        let ring = AlgebraicNumberRing::new(ring);
+
+        let p: RationalPolynomial<_, u8> = Atom::parse("1/(x^2-2)")
+            .unwrap()
+            .to_rational_polynomial::<_, _, u8>(&Q, &Z, None);
+        let p = RationalPolynomial {
+            numerator: p
+                .numerator
+                .map_coeff(|c| c.into(), Q)
+                .to_number_field(&ring),
+            denominator: p
+                .denominator
+                .map_coeff(|c| c.into(), Q)
+                .to_number_field(&ring),
+        };
+
+        let r = p.apart(0);
+        assert_eq!(r.len(), 2);
+
+        let field = RationalPolynomialField::new_from_poly(&p.numerator);
+        let sum = r.iter().fold(field.zero(), |acc, x| field.add(&acc, x));
+        assert_eq!(sum, p);
+    }
 }
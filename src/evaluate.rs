@@ -1,9 +1,13 @@
-use ahash::HashMap;
+use ahash::{HashMap, HashSet};
+use rug::{float::Round, Float as MultiPrecisionFloat};
 
 use crate::{
-    atom::{Atom, AtomView, Symbol},
+    atom::{Atom, AtomView, FunctionBuilder, Symbol},
     coefficient::CoefficientView,
-    domains::{float::Real, rational::Rational},
+    domains::{
+        float::{Interval, Real},
+        rational::Rational,
+    },
     state::State,
 };
 
@@ -29,6 +33,52 @@ impl<T> EvaluationFn<T> {
     }
 }
 
+/// Call `eval` with interval arithmetic at an increasing precision in bits,
+/// starting from the precision needed for `target_digits` decimal digits
+/// and doubling it until all returned intervals certify `target_digits` correct digits
+/// or until `max_prec` is exceeded. If `absolute_tolerance` is set, results that cancel to (nearly) zero
+/// are also accepted once their interval lies within `[-absolute_tolerance, absolute_tolerance]`,
+/// as such results never have certain significant digits.
+///
+/// Returns the midpoints of the intervals and the precision that was used.
+pub(crate) fn evaluate_with_increasing_precision<F: FnMut(u32) -> Result<Vec<Interval>, String>>(
+    target_digits: u32,
+    max_prec: u32,
+    absolute_tolerance: Option<f64>,
+    mut eval: F,
+) -> Result<(Vec<MultiPrecisionFloat>, u32), String> {
+    let mut prec = (target_digits as f64 * std::f64::consts::LOG2_10).ceil() as u32 + 32;
+
+    loop {
+        let prec_used = prec.min(max_prec);
+        let res = eval(prec_used)?;
+
+        if res.iter().all(|r| {
+            r.correct_digits() >= target_digits
+                || absolute_tolerance.is_some_and(|t| r.is_within(t))
+        }) {
+            return Ok((res.iter().map(|r| r.midpoint()).collect(), prec_used));
+        }
+
+        if prec_used == max_prec {
+            return Err(format!(
+                "Could not obtain {} correct digits with a precision of {} bits",
+                target_digits, max_prec
+            ));
+        }
+
+        prec = prec.saturating_mul(2);
+    }
+}
+
+/// Convert a float that is considered exact to the smallest interval with precision `prec` that contains it.
+pub(crate) fn float_to_interval(f: &MultiPrecisionFloat, prec: u32) -> Interval {
+    Interval::new(
+        MultiPrecisionFloat::with_val_round(prec, f, Round::Down).0,
+        MultiPrecisionFloat::with_val_round(prec, f, Round::Up).0,
+    )
+}
+
 impl Atom {
     /// Evaluate an expression using a constant map and a function map.
     /// The constant map can map any literal expression to a value, for example
@@ -45,9 +95,77 @@ impl Atom {
         self.as_view()
             .evaluate(coeff_map, const_map, function_map, cache)
     }
+
+    /// Replace all function calls, powers, products and sums that occur more than once
+    /// in the expression, as well as shared parts of sums and products, by new symbols
+    /// named `prefix0`, `prefix1`, etc. See [CommonSubexpressions::new].
+    pub fn common_subexpression_elimination(&self, prefix: &str) -> CommonSubexpressions {
+        CommonSubexpressions::new(&[self.as_view()], prefix)
+    }
+
+    /// Evaluate an expression to `target_digits` correct decimal digits, by evaluating it
+    /// with interval arithmetic at increasing precision until the error bound is small enough.
+    /// The values in the constant map are considered exact. A result that cancels to (nearly) zero
+    /// has no correct significant digits and is only accepted if it is within the `absolute_tolerance` of zero.
+    ///
+    /// Returns the value and the precision in bits that was used, or an error if
+    /// `max_prec` bits are not sufficient.
+    pub fn evaluate_with_precision(
+        &self,
+        const_map: &HashMap<AtomView<'_>, MultiPrecisionFloat>,
+        function_map: &HashMap<Symbol, EvaluationFn<Interval>>,
+        target_digits: u32,
+        max_prec: u32,
+        absolute_tolerance: Option<f64>,
+    ) -> Result<(MultiPrecisionFloat, u32), String> {
+        self.as_view().evaluate_with_precision(
+            const_map,
+            function_map,
+            target_digits,
+            max_prec,
+            absolute_tolerance,
+        )
+    }
 }
 
 impl<'a> AtomView<'a> {
+    /// Evaluate an expression to `target_digits` correct decimal digits, by evaluating it
+    /// with interval arithmetic at increasing precision until the error bound is small enough.
+    /// The values in the constant map are considered exact. A result that cancels to (nearly) zero
+    /// has no correct significant digits and is only accepted if it is within the `absolute_tolerance` of zero.
+    ///
+    /// Returns the value and the precision in bits that was used, or an error if
+    /// `max_prec` bits are not sufficient.
+    pub fn evaluate_with_precision(
+        &self,
+        const_map: &HashMap<AtomView<'_>, MultiPrecisionFloat>,
+        function_map: &HashMap<Symbol, EvaluationFn<Interval>>,
+        target_digits: u32,
+        max_prec: u32,
+        absolute_tolerance: Option<f64>,
+    ) -> Result<(MultiPrecisionFloat, u32), String> {
+        let (mut r, prec) = evaluate_with_increasing_precision(
+            target_digits,
+            max_prec,
+            absolute_tolerance,
+            |prec| {
+                let interval_map = const_map
+                    .iter()
+                    .map(|(k, v)| (*k, float_to_interval(v, prec)))
+                    .collect();
+
+                Ok(vec![self.evaluate(
+                    |r| r.to_multi_prec_interval(prec),
+                    &interval_map,
+                    function_map,
+                    &mut HashMap::default(),
+                )])
+            },
+        )?;
+
+        Ok((r.pop().unwrap(), prec))
+    }
+
     /// Evaluate an expression using a constant map and a function map.
     /// The constant map can map any literal expression to a value, for example
     /// a variable or a function with fixed arguments.
@@ -158,11 +276,526 @@ impl<'a> AtomView<'a> {
     }
 }
 
+/// The result of common subexpression elimination on a list of expressions.
+/// Every subexpression that occurs more than once is replaced by a fresh symbol,
+/// whose definition is stored in `definitions`. A definition only depends
+/// on the symbols of the definitions that precede it.
+#[derive(Clone, Debug)]
+pub struct CommonSubexpressions {
+    pub definitions: Vec<(Symbol, Atom)>,
+    pub expressions: Vec<Atom>,
+}
+
+impl CommonSubexpressions {
+    /// Find all function calls, powers, products and sums that occur more than once in `exprs`
+    /// and replace them by new symbols named `prefix0`, `prefix1`, etc. Afterwards, subsets
+    /// of at least two arguments that are shared between sums or between products are
+    /// replaced by new symbols as well, e.g. `x+y` in `x+y+z` and `x+y+w`.
+    ///
+    /// Names that already occur in `exprs` are skipped.
+    pub fn new(exprs: &[AtomView<'_>], prefix: &str) -> CommonSubexpressions {
+        let mut used = HashSet::default();
+        let mut counts = HashMap::default();
+        for e in exprs {
+            used.extend(e.get_all_symbols(true));
+            Self::count(*e, &mut counts);
+        }
+
+        let mut symbols = SymbolGenerator {
+            prefix,
+            used,
+            next: 0,
+        };
+
+        let mut names = HashMap::default();
+        let mut definitions = vec![];
+        let expressions = exprs
+            .iter()
+            .map(|e| Self::replace(*e, &counts, &mut names, &mut definitions, &mut symbols))
+            .collect();
+
+        let mut cse = CommonSubexpressions {
+            definitions,
+            expressions,
+        };
+        cse.replace_common_arguments(&mut symbols);
+        cse
+    }
+
+    /// Count the occurrences of subexpressions. Children of a subexpression
+    /// that was seen before are not visited again, as they will be part of its definition.
+    fn count<'a>(e: AtomView<'a>, counts: &mut HashMap<AtomView<'a>, usize>) {
+        if let AtomView::Num(_) | AtomView::Var(_) = e {
+            return;
+        }
+
+        let c = counts.entry(e).or_insert(0);
+        *c += 1;
+        if *c > 1 {
+            return;
+        }
+
+        match e {
+            AtomView::Fun(f) => {
+                for a in f.iter() {
+                    Self::count(a, counts);
+                }
+            }
+            AtomView::Pow(p) => {
+                let (b, e) = p.get_base_exp();
+                Self::count(b, counts);
+                Self::count(e, counts);
+            }
+            AtomView::Mul(m) => {
+                for a in m.iter() {
+                    Self::count(a, counts);
+                }
+            }
+            AtomView::Add(a) => {
+                for a in a.iter() {
+                    Self::count(a, counts);
+                }
+            }
+            AtomView::Num(_) | AtomView::Var(_) => unreachable!(),
+        }
+    }
+
+    fn replace<'a>(
+        e: AtomView<'a>,
+        counts: &HashMap<AtomView<'a>, usize>,
+        names: &mut HashMap<AtomView<'a>, Symbol>,
+        definitions: &mut Vec<(Symbol, Atom)>,
+        symbols: &mut SymbolGenerator,
+    ) -> Atom {
+        if let Some(s) = names.get(&e) {
+            return Atom::new_var(*s);
+        }
+
+        let r = map_children(e, |c| Self::replace(c, counts, names, definitions, symbols));
+
+        if counts.get(&e).map(|c| *c > 1).unwrap_or(false) {
+            let s = symbols.next();
+            definitions.push((s, r));
+            names.insert(e, s);
+            Atom::new_var(s)
+        } else {
+            r
+        }
+    }
+
+    /// Greedily replace subsets of arguments that are shared between pairs of sums
+    /// or pairs of products by new symbols. If the shared subset is the sum or product that
+    /// defines a symbol, this symbol is used instead.
+    ///
+    /// The sums and products are indexed by their arguments, such that only pairs that
+    /// share at least two arguments are compared. The subsets are substituted
+    /// into the expressions in a single pass at the end.
+    fn replace_common_arguments(&mut self, symbols: &mut SymbolGenerator) {
+        let mut nodes = vec![];
+        for (s, d) in &self.definitions {
+            Self::collect_arguments(d.as_view(), Some(*s), &mut nodes);
+        }
+        for e in &self.expressions {
+            Self::collect_arguments(e.as_view(), None, &mut nodes);
+        }
+
+        // the sums or products in which an argument appears
+        let mut index: HashMap<(bool, Atom), HashSet<usize>> = HashMap::default();
+        for (i, n) in nodes.iter().enumerate() {
+            for a in &n.args {
+                index.entry((n.is_add, a.clone())).or_default().insert(i);
+            }
+        }
+
+        let mut subsets = vec![];
+        for i in 0..nodes.len() {
+            let is_add = nodes[i].is_add;
+
+            let mut shared: HashMap<usize, usize> = HashMap::default();
+            for a in &nodes[i].args {
+                if let Some(js) = index.get(&(is_add, a.clone())) {
+                    for j in js {
+                        if *j > i {
+                            *shared.entry(*j).or_insert(0) += 1;
+                        }
+                    }
+                }
+            }
+
+            let mut candidates: Vec<_> = shared
+                .into_iter()
+                .filter(|(_, c)| *c > 1)
+                .map(|(j, _)| j)
+                .collect();
+            candidates.sort_unstable();
+
+            for j in candidates {
+                let common = multiset_intersection(&nodes[i].args, &nodes[j].args);
+                if common.len() < 2
+                    || (common.len() == nodes[i].args.len() && common.len() == nodes[j].args.len())
+                {
+                    continue;
+                }
+
+                let owner = [&nodes[i], &nodes[j]]
+                    .iter()
+                    .find(|n| n.args.len() == common.len())
+                    .and_then(|n| n.owner);
+
+                let s = owner.unwrap_or_else(|| {
+                    let s = symbols.next();
+                    let args: Vec<_> = common.iter().collect();
+                    let d = if is_add {
+                        Atom::add_many(&args)
+                    } else {
+                        Atom::mul_many(&args)
+                    };
+                    self.definitions.push((s, d));
+                    s
+                });
+
+                // only the nodes that contain the first argument of the subset can contain the subset
+                let mut containing: Vec<_> = index
+                    .get(&(is_add, common[0].clone()))
+                    .map(|ks| ks.iter().cloned().collect())
+                    .unwrap_or_default();
+                containing.sort_unstable();
+
+                let new_arg = Atom::new_var(s);
+                for k in containing {
+                    let n = &mut nodes[k];
+                    if n.owner == Some(s) {
+                        continue;
+                    }
+
+                    if let Some(mut rest) = multiset_difference(&n.args, &common) {
+                        for a in &common {
+                            if !rest.contains(a) {
+                                if let Some(ks) = index.get_mut(&(is_add, a.clone())) {
+                                    ks.remove(&k);
+                                }
+                            }
+                        }
+
+                        rest.push(new_arg.clone());
+                        index
+                            .entry((is_add, new_arg.clone()))
+                            .or_default()
+                            .insert(k);
+                        n.args = rest;
+                    }
+                }
+
+                subsets.push((is_add, common, s));
+            }
+        }
+
+        if subsets.is_empty() {
+            return;
+        }
+
+        for (s, d) in &mut self.definitions {
+            *d = Self::apply_subsets(d.as_view(), &subsets, Some(*s));
+        }
+        for e in &mut self.expressions {
+            *e = Self::apply_subsets(e.as_view(), &subsets, None);
+        }
+
+        self.sort_definitions();
+    }
+
+    /// Collect the arguments of all sums and products in `e`.
+    fn collect_arguments(e: AtomView<'_>, owner: Option<Symbol>, nodes: &mut Vec<ArgumentList>) {
+        match e {
+            AtomView::Num(_) | AtomView::Var(_) => {}
+            AtomView::Fun(f) => {
+                for a in f.iter() {
+                    Self::collect_arguments(a, None, nodes);
+                }
+            }
+            AtomView::Pow(p) => {
+                let (b, e) = p.get_base_exp();
+                Self::collect_arguments(b, None, nodes);
+                Self::collect_arguments(e, None, nodes);
+            }
+            AtomView::Mul(m) => {
+                nodes.push(ArgumentList {
+                    is_add: false,
+                    args: m.iter().map(|a| a.to_owned()).collect(),
+                    owner,
+                });
+                for a in m.iter() {
+                    Self::collect_arguments(a, None, nodes);
+                }
+            }
+            AtomView::Add(a) => {
+                nodes.push(ArgumentList {
+                    is_add: true,
+                    args: a.iter().map(|a| a.to_owned()).collect(),
+                    owner,
+                });
+                for a in a.iter() {
+                    Self::collect_arguments(a, None, nodes);
+                }
+            }
+        }
+    }
+
+    /// Replace the argument subsets in all sums and products in `e`, in the order in which they were found.
+    /// The subset of the symbol `skip` is not applied to the top level of `e`.
+    fn apply_subsets(
+        e: AtomView<'_>,
+        subsets: &[(bool, Vec<Atom>, Symbol)],
+        skip: Option<Symbol>,
+    ) -> Atom {
+        let is_add = match e {
+            AtomView::Add(_) => true,
+            AtomView::Mul(_) => false,
+            _ => return map_children(e, |c| Self::apply_subsets(c, subsets, None)),
+        };
+
+        let mut args: Vec<_> = match e {
+            AtomView::Add(a) => a.iter().map(|a| a.to_owned()).collect(),
+            AtomView::Mul(m) => m.iter().map(|a| a.to_owned()).collect(),
+            _ => unreachable!(),
+        };
+
+        for (add, common, s) in subsets {
+            if *add == is_add && Some(*s) != skip {
+                if let Some(mut rest) = multiset_difference(&args, common) {
+                    rest.push(Atom::new_var(*s));
+                    args = rest;
+                }
+            }
+        }
+
+        let args: Vec<_> = args
+            .iter()
+            .map(|a| Self::apply_subsets(a.as_view(), subsets, None))
+            .collect();
+        let args: Vec<_> = args.iter().collect();
+        if is_add {
+            Atom::add_many(&args)
+        } else {
+            Atom::mul_many(&args)
+        }
+    }
+
+    /// Sort the definitions such that every definition only depends on the
+    /// symbols of the definitions that precede it.
+    fn sort_definitions(&mut self) {
+        fn visit(
+            i: usize,
+            definitions: &[(Symbol, Atom)],
+            index: &HashMap<Symbol, usize>,
+            visited: &mut [bool],
+            order: &mut Vec<usize>,
+        ) {
+            if visited[i] {
+                return;
+            }
+            visited[i] = true;
+
+            let mut deps: Vec<_> = definitions[i]
+                .1
+                .get_all_symbols(true)
+                .iter()
+                .filter_map(|s| index.get(s).cloned())
+                .collect();
+            deps.sort_unstable();
+
+            for d in deps {
+                visit(d, definitions, index, visited, order);
+            }
+            order.push(i);
+        }
+
+        let index: HashMap<_, _> = self
+            .definitions
+            .iter()
+            .enumerate()
+            .map(|(i, (s, _))| (*s, i))
+            .collect();
+
+        let mut visited = vec![false; self.definitions.len()];
+        let mut order = Vec::with_capacity(self.definitions.len());
+        for i in 0..self.definitions.len() {
+            visit(i, &self.definitions, &index, &mut visited, &mut order);
+        }
+
+        let mut definitions: Vec<_> = self.definitions.drain(..).map(Some).collect();
+        self.definitions = order
+            .into_iter()
+            .map(|i| definitions[i].take().unwrap())
+            .collect();
+    }
+
+    /// Substitute all definitions back into the expressions.
+    pub fn to_expressions(&self) -> Vec<Atom> {
+        let mut defs: HashMap<Symbol, Atom> = HashMap::default();
+        for (s, d) in &self.definitions {
+            let d = substitute_symbols(d.as_view(), &defs);
+            defs.insert(*s, d);
+        }
+
+        self.expressions
+            .iter()
+            .map(|e| substitute_symbols(e.as_view(), &defs))
+            .collect()
+    }
+
+    /// Convert the definitions to levels suitable for
+    /// [`ExpressionEvaluator::new`](crate::poly::evaluate::ExpressionEvaluator::new).
+    /// Every definition `Z` becomes an array of length one that is referenced as `Z(0)`, and
+    /// is put in the first level after all the definitions it depends on.
+    /// The last level contains the expressions, stored in the array `output`.
+    pub fn to_levels(&self, output: Symbol) -> Vec<Vec<(Symbol, Vec<Atom>)>> {
+        let mut depth: HashMap<Symbol, usize> = HashMap::default();
+        let mut array_map: HashMap<Symbol, Atom> = HashMap::default();
+        let mut levels: Vec<Vec<(Symbol, Vec<Atom>)>> = vec![];
+
+        for (s, d) in &self.definitions {
+            let level = d
+                .get_all_symbols(false)
+                .iter()
+                .filter_map(|x| depth.get(x).map(|l| l + 1))
+                .max()
+                .unwrap_or(0);
+
+            if levels.len() <= level {
+                levels.resize(level + 1, vec![]);
+            }
+
+            levels[level].push((*s, vec![substitute_symbols(d.as_view(), &array_map)]));
+
+            depth.insert(*s, level);
+            array_map.insert(
+                *s,
+                FunctionBuilder::new(*s).add_arg(&Atom::new_num(0)).finish(),
+            );
+        }
+
+        levels.push(vec![(
+            output,
+            self.expressions
+                .iter()
+                .map(|e| substitute_symbols(e.as_view(), &array_map))
+                .collect(),
+        )]);
+
+        levels
+    }
+}
+
+impl std::fmt::Display for CommonSubexpressions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (s, d) in &self.definitions {
+            writeln!(f, "{} = {}", State::get_name(*s), d)?;
+        }
+        for (i, e) in self.expressions.iter().enumerate() {
+            writeln!(f, "OUT{} = {}", i, e)?;
+        }
+        Ok(())
+    }
+}
+
+/// Generates the symbols `prefix0`, `prefix1`, etc., skipping symbols that are in use.
+struct SymbolGenerator<'a> {
+    prefix: &'a str,
+    used: HashSet<Symbol>,
+    next: usize,
+}
+
+impl<'a> SymbolGenerator<'a> {
+    fn next(&mut self) -> Symbol {
+        loop {
+            let s = State::get_symbol(format!("{}{}", self.prefix, self.next));
+            self.next += 1;
+            if !self.used.contains(&s) {
+                return s;
+            }
+        }
+    }
+}
+
+/// The arguments of a sum or product, and the symbol it defines, if any.
+struct ArgumentList {
+    is_add: bool,
+    args: Vec<Atom>,
+    owner: Option<Symbol>,
+}
+
+/// Compute the intersection of two lists of atoms, respecting multiplicity.
+fn multiset_intersection(a: &[Atom], b: &[Atom]) -> Vec<Atom> {
+    let mut rest = b.to_vec();
+    let mut common = vec![];
+    for x in a {
+        if let Some(p) = rest.iter().position(|y| y == x) {
+            rest.swap_remove(p);
+            common.push(x.clone());
+        }
+    }
+    common
+}
+
+/// Remove the atoms of `subset` from `a`, respecting multiplicity. Returns `None` if
+/// `subset` is not contained in `a`.
+fn multiset_difference(a: &[Atom], subset: &[Atom]) -> Option<Vec<Atom>> {
+    let mut rest = a.to_vec();
+    for x in subset {
+        let p = rest.iter().position(|y| y == x)?;
+        rest.remove(p);
+    }
+    Some(rest)
+}
+
+/// Rebuild `e` where every child `c` is replaced by `map(c)`.
+fn map_children<'a, F: FnMut(AtomView<'a>) -> Atom>(e: AtomView<'a>, mut map: F) -> Atom {
+    match e {
+        AtomView::Num(_) | AtomView::Var(_) => e.to_owned(),
+        AtomView::Fun(f) => {
+            let mut fb = FunctionBuilder::new(f.get_symbol());
+            for a in f.iter() {
+                fb = fb.add_arg(&map(a));
+            }
+            fb.finish()
+        }
+        AtomView::Pow(p) => {
+            let (b, e) = p.get_base_exp();
+            map(b).pow(&map(e))
+        }
+        AtomView::Mul(m) => {
+            let args: Vec<_> = m.iter().map(map).collect();
+            Atom::mul_many(&args.iter().collect::<Vec<_>>())
+        }
+        AtomView::Add(a) => {
+            let args: Vec<_> = a.iter().map(map).collect();
+            Atom::add_many(&args.iter().collect::<Vec<_>>())
+        }
+    }
+}
+
+/// Replace the variables in `e` that are keys of `map` by their value.
+fn substitute_symbols(e: AtomView<'_>, map: &HashMap<Symbol, Atom>) -> Atom {
+    if let AtomView::Var(v) = e {
+        if let Some(r) = map.get(&v.get_symbol()) {
+            return r.clone();
+        }
+    }
+
+    map_children(e, |c| substitute_symbols(c, map))
+}
+
 #[cfg(test)]
 mod test {
     use ahash::HashMap;
 
-    use crate::{atom::Atom, evaluate::EvaluationFn, state::State};
+    use crate::{
+        atom::Atom,
+        domains::rational::Rational,
+        evaluate::{CommonSubexpressions, EvaluationFn},
+        state::State,
+    };
 
     #[test]
     fn evaluate() {
@@ -223,4 +856,114 @@ mod test {
             "6.0000000099840062521194578624390895167558285149387196915810785"
         );
     }
+
+    #[test]
+    fn interval() {
+        let x = State::get_symbol("v1");
+        let a = Atom::parse("(v1 + 10^30)^2 - 10^60 - 2*10^30*v1").unwrap();
+
+        let mut const_map = HashMap::default();
+
+        let v = Atom::new_var(x);
+        const_map.insert(
+            v.as_view(),
+            Rational::Natural(1, 3).to_multi_prec_interval(60),
+        );
+
+        let r = a.evaluate(
+            |r| r.to_multi_prec_interval(60),
+            &const_map,
+            &HashMap::default(),
+            &mut HashMap::default(),
+        );
+        assert!(r.contains(&(rug::Float::with_val(60, 1) / 9)));
+        assert_eq!(r.correct_digits(), 0);
+
+        const_map.insert(
+            v.as_view(),
+            Rational::Natural(1, 3).to_multi_prec_interval(300),
+        );
+        let r = a.evaluate(
+            |r| r.to_multi_prec_interval(300),
+            &const_map,
+            &HashMap::default(),
+            &mut HashMap::default(),
+        );
+        assert!(r.correct_digits() > 20);
+    }
+
+    #[test]
+    fn evaluate_with_precision() {
+        let x = State::get_symbol("v1");
+        let a = Atom::parse("(v1 + 10^30)^2 - 10^60 - 2*10^30*v1").unwrap();
+
+        let v = Atom::new_var(x);
+        let mut const_map = HashMap::default();
+        const_map.insert(v.as_view(), rug::Float::with_val(53, 0.5));
+
+        let (r, prec) = a
+            .evaluate_with_precision(&const_map, &HashMap::default(), 20, 1000, None)
+            .unwrap();
+
+        assert_eq!(r, 0.25);
+        assert!(prec > 200);
+
+        assert!(a
+            .evaluate_with_precision(&const_map, &HashMap::default(), 20, 100, None)
+            .is_err());
+
+        // the result cancels to zero, so it is only accepted with an absolute tolerance
+        let b = Atom::parse("sin(v1)^2 + cos(v1)^2 - 1").unwrap();
+        assert!(b
+            .evaluate_with_precision(&const_map, &HashMap::default(), 20, 1000, None)
+            .is_err());
+        let (r, _) = b
+            .evaluate_with_precision(&const_map, &HashMap::default(), 20, 1000, Some(1e-20))
+            .unwrap();
+        assert!(r.abs() <= 1e-20);
+    }
+
+    #[test]
+    fn common_subexpressions() {
+        let a = Atom::parse("f(x+y)^2*cos(f(x+y)) + (x+y)*g(f(x+y)^2) + sin(x)").unwrap();
+        let b = Atom::parse("f(x+y)^2 + sin(x)").unwrap();
+
+        let cse = CommonSubexpressions::new(&[a.as_view(), b.as_view()], "Z");
+
+        let res = [
+            ("Z0", "sin(x)"),
+            ("Z1", "x+y"),
+            ("Z2", "f(Z1)"),
+            ("Z3", "Z2^2"),
+        ]
+        .map(|(s, d)| (State::get_symbol(s), Atom::parse(d).unwrap()));
+        assert_eq!(cse.definitions, res);
+        assert_eq!(cse.to_expressions(), [a, b]);
+
+        let levels = cse.to_levels(State::get_symbol("out"));
+        assert_eq!(levels.len(), 4);
+        assert_eq!(levels[1][0].1[0], Atom::parse("f(Z1(0))").unwrap());
+    }
+
+    #[test]
+    fn common_arguments() {
+        let a = Atom::parse("(x+y+z)*f(x+y+w) + x*y*z - x*y*w").unwrap();
+        let cse = CommonSubexpressions::new(&[a.as_view()], "Z");
+
+        let res = [("Z0", "x+y"), ("Z1", "x*y")]
+            .map(|(s, d)| (State::get_symbol(s), Atom::parse(d).unwrap()));
+        assert_eq!(cse.definitions, res);
+        assert_eq!(
+            cse.expressions,
+            [Atom::parse("(Z0+z)*f(Z0+w) + Z1*z - Z1*w").unwrap()]
+        );
+        assert_eq!(cse.to_expressions(), [a]);
+
+        // existing symbols are not reused
+        let b = Atom::parse("f(Y0+x) + g(Y0+x)").unwrap();
+        let cse = CommonSubexpressions::new(&[b.as_view()], "Y");
+        let res = [(State::get_symbol("Y1"), Atom::parse("Y0+x").unwrap())];
+        assert_eq!(cse.definitions, res);
+        assert_eq!(cse.to_expressions(), [b]);
+    }
 }
@@ -5,7 +5,11 @@ use smallvec::SmallVec;
 use crate::{
     atom::{Atom, AtomView, Fun, Symbol},
     coefficient::{Coefficient, CoefficientView},
-    domains::{integer::Z, rational::Q},
+    domains::{
+        integer::{Integer, Z},
+        rational::Q,
+        Field,
+    },
     poly::Variable,
     state::{RecycledAtom, State, Workspace},
 };
@@ -1099,6 +1103,77 @@ impl<'a> AtomView<'a> {
                                 break 'pow_simplify;
                             }
 
+                            // extract perfect powers from the base, e.g. 12^(1/2) = 2*3^(1/2)
+                            if let (Coefficient::Rational(r), Coefficient::Rational(e)) =
+                                (&new_base_num, &new_exp_num)
+                            {
+                                if let Some(d) =
+                                    e.denominator().to_i64().filter(|d| *d <= u32::MAX as i64)
+                                {
+                                    let (num_out, mut num_in) =
+                                        r.numerator().split_perfect_power(d as u32);
+                                    let (den_out, mut den_in) =
+                                        r.denominator().split_perfect_power(d as u32);
+
+                                    // reduce the degree of the root if the remaining base is a perfect power,
+                                    // e.g. 4^(1/4) = 2^(1/2), which is only valid for positive bases
+                                    let mut new_d = d;
+                                    if !r.is_negative() {
+                                        let mut divisors = vec![1];
+                                        for (p, e) in Integer::from(d).factor().unwrap() {
+                                            let p = p.to_i64().unwrap();
+                                            let len = divisors.len();
+                                            let mut pk = 1;
+                                            for _ in 0..e {
+                                                pk *= p;
+                                                for i in 0..len {
+                                                    divisors.push(divisors[i] * pk);
+                                                }
+                                            }
+                                        }
+                                        divisors.sort_unstable_by(|a, b| b.cmp(a));
+
+                                        for k in divisors.into_iter().take_while(|k| *k > 1) {
+                                            let (nr, nc) = num_in.split_perfect_power(k as u32);
+                                            let (dr, dc) = den_in.split_perfect_power(k as u32);
+                                            if nc.is_one() && dc.is_one() {
+                                                num_in = nr;
+                                                den_in = dr;
+                                                new_d = d / k;
+                                                break;
+                                            }
+                                        }
+                                    }
+
+                                    if !num_out.is_one() || !den_out.is_one() || new_d != d {
+                                        if r.is_negative() {
+                                            num_in = -&num_in;
+                                        }
+
+                                        let mut num_h = workspace.new_atom();
+                                        num_h.to_num(
+                                            Q.div(&num_in.to_rational(), &den_in.to_rational())
+                                                .into(),
+                                        );
+                                        exp_handle.to_num((1, new_d).into());
+                                        let mut pow_h = workspace.new_atom();
+                                        pow_h.to_pow(num_h.as_view(), exp_handle.as_view());
+
+                                        num_h.to_num(
+                                            Q.div(&num_out.to_rational(), &den_out.to_rational())
+                                                .into(),
+                                        );
+
+                                        let mut mul_h = workspace.new_atom();
+                                        let mul = mul_h.to_mul();
+                                        mul.extend(num_h.as_view());
+                                        mul.extend(pow_h.as_view());
+                                        mul_h.as_view().normalize(workspace, out);
+                                        break 'pow_simplify;
+                                    }
+                                }
+                            }
+
                             base_handle.to_num(new_base_num);
                             exp_handle.to_num(new_exp_num);
                         } else if let AtomView::Var(v) = base_handle.as_view() {
@@ -1290,6 +1365,24 @@ mod test {
         );
         assert_eq!(Atom::parse("(v1^v2)^2"), Atom::parse("v1^(2*v2)"));
         assert_eq!(Atom::parse("(v1^(1/2))^2"), Atom::parse("v1"));
+        assert_eq!(Atom::parse("12^(1/2)"), Atom::parse("2*3^(1/2)"));
+        assert_eq!(Atom::parse("4^(1/2)"), Atom::parse("2"));
+        assert_eq!(Atom::parse("8^(2/3)"), Atom::parse("4"));
+        assert_eq!(Atom::parse("4^(1/4)"), Atom::parse("2^(1/2)"));
+        assert_eq!(Atom::parse("(8/27)^(1/6)"), Atom::parse("(2/3)^(1/2)"));
+        assert_eq!(
+            Atom::parse("(-24/25)^(3/2)"),
+            Atom::parse("48/125*(-6)^(1/2)")
+        );
+        assert_eq!(
+            format!("{}", Atom::parse("4^(1/4294967296)").unwrap()),
+            "4^(1/4294967296)"
+        );
+        assert_eq!(Atom::parse("64^(1/12)"), Atom::parse("2^(1/2)"));
+        assert_eq!(
+            format!("{}", Atom::parse("4^(1/4294967295)").unwrap()),
+            "4^(1/4294967295)"
+        );
     }
 
     #[test]
@@ -2,8 +2,12 @@ pub mod evaluate;
 pub mod factor;
 pub mod gcd;
 pub mod groebner;
+pub mod ideal;
+pub mod multivariate_series;
 pub mod polynomial;
+pub mod reconstruct;
 pub mod resultant;
+pub mod roots;
 pub mod series;
 pub mod univariate;
 
@@ -12,6 +16,7 @@ use std::cmp::Ordering::{self, Equal};
 use std::fmt::{Debug, Display};
 use std::hash::Hash;
 use std::iter::Sum;
+use std::marker::PhantomData;
 use std::ops::{Add as OpAdd, AddAssign, DerefMut, Div, Mul as OpMul, Neg, Rem, Sub};
 use std::sync::Arc;
 
@@ -339,6 +344,65 @@ impl MonomialOrder for LexOrder {
     }
 }
 
+/// Block ordering of monomials, where the first `N` variables are compared using the order `O1`
+/// and only if they are equal, the remaining variables are compared using the order `O2`.
+///
+/// The block order is an elimination order for the first `N` variables: the elements of a Groebner basis
+/// that do not depend on these variables form a Groebner basis of the elimination ideal.
+#[derive(Clone)]
+pub struct BlockOrder<
+    const N: usize,
+    O1: MonomialOrder = GrevLexOrder,
+    O2: MonomialOrder = GrevLexOrder,
+> {
+    _phantom: PhantomData<(O1, O2)>,
+}
+
+impl<const N: usize, O1: MonomialOrder, O2: MonomialOrder> MonomialOrder for BlockOrder<N, O1, O2> {
+    #[inline]
+    fn cmp<E: Exponent>(a: &[E], b: &[E]) -> Ordering {
+        let n = N.min(a.len());
+        match O1::cmp(&a[..n], &b[..n]) {
+            Equal => O2::cmp(&a[n..], &b[n..]),
+            x => x,
+        }
+    }
+}
+
+/// The weights of the variables in a weighted degree ordering.
+pub trait MonomialWeights: Clone {
+    /// The weight of every variable. Variables without a weight have weight 1.
+    fn weights() -> &'static [u32];
+}
+
+/// Weighted degree ordering of monomials, where ties are broken using the order `O`.
+#[derive(Clone)]
+pub struct WeightedOrder<W: MonomialWeights, O: MonomialOrder = GrevLexOrder> {
+    _phantom: PhantomData<(W, O)>,
+}
+
+impl<W: MonomialWeights, O: MonomialOrder> WeightedOrder<W, O> {
+    /// Compute the weighted degree of a monomial.
+    #[inline]
+    pub fn weighted_degree<E: Exponent>(a: &[E]) -> u64 {
+        let w = W::weights();
+        a.iter()
+            .enumerate()
+            .map(|(i, e)| e.to_u32() as u64 * w.get(i).cloned().unwrap_or(1) as u64)
+            .sum()
+    }
+}
+
+impl<W: MonomialWeights, O: MonomialOrder> MonomialOrder for WeightedOrder<W, O> {
+    #[inline]
+    fn cmp<E: Exponent>(a: &[E], b: &[E]) -> Ordering {
+        match Self::weighted_degree(a).cmp(&Self::weighted_degree(b)) {
+            Equal => O::cmp(a, b),
+            x => x,
+        }
+    }
+}
+
 /// A polynomial variable. It is either a (global) symbol
 /// a temporary variable (for internal use), an array entry,
 /// a function or any other non-polynomial part.
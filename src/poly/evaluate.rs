@@ -5,12 +5,14 @@ use std::{
 
 use ahash::{AHasher, HashMap, HashSet, HashSetExt};
 use rand::{thread_rng, Rng};
+use rayon::prelude::*;
+use rug::Float as MultiPrecisionFloat;
 
 use crate::{
     atom::Symbol,
     coefficient::CoefficientView,
     domains::{
-        float::NumericalFloatLike,
+        float::{Dual, NumericalFloatLike},
         rational::{Rational, RationalField, Q},
         EuclideanDomain,
     },
@@ -19,7 +21,8 @@ use crate::{
 use crate::{
     atom::{Atom, AtomView},
     domains::{float::Real, Ring},
-    evaluate::EvaluationFn,
+    evaluate::{evaluate_with_increasing_precision, float_to_interval, EvaluationFn},
+    numerical_integration::MonteCarloRng,
     state::State,
 };
 
@@ -523,6 +526,27 @@ impl<E: Exponent> MultivariatePolynomial<RationalField, E> {
         (hs.pop().unwrap(), op_count, scheme)
     }
 
+    /// Find the Horner scheme that minimizes the number of operations,
+    /// taking common subexpressions into account, by running all strategies in `settings`
+    /// in parallel. Returns the best scheme, its operation count, the variable order and
+    /// a report of the operation count reached by every run.
+    pub fn optimize_horner_scheme_with_settings(
+        &self,
+        settings: &HornerOptimizationSettings,
+    ) -> (
+        HornerScheme<RationalField>,
+        usize,
+        Vec<usize>,
+        Vec<HornerStrategyReport>,
+    )
+    where
+        E: Send + Sync,
+    {
+        let (mut hs, op_count, scheme, reports) =
+            HornerScheme::optimize_multiple_with_settings(std::slice::from_ref(&self), settings);
+        (hs.pop().unwrap(), op_count, scheme, reports)
+    }
+
     /// Optimize an expression for evaluation, given `num_iter` tries.
     pub fn optimize(&self, num_iter: usize) -> InstructionListOutput<Rational> {
         let (h, _ops, _scheme) = self.optimize_horner_scheme(num_iter);
@@ -536,6 +560,128 @@ impl<E: Exponent> MultivariatePolynomial<RationalField, E> {
     }
 }
 
+/// A strategy for finding a Horner scheme with few operations.
+#[derive(Clone, Debug, PartialEq)]
+pub enum HornerStrategy {
+    /// Order the variables by descending number of occurrences.
+    Occurrence,
+    /// Simulated annealing over variable orders, starting from the occurrence order.
+    /// A random swap of two variables that increases the operation count by a relative amount `Δ`
+    /// is accepted with probability `exp(-Δ/T)`, where the temperature `T` decreases
+    /// geometrically from `start_temperature` to `end_temperature` over `iterations` steps.
+    SimulatedAnnealing {
+        iterations: usize,
+        start_temperature: f64,
+        end_temperature: f64,
+    },
+    /// Monte Carlo tree search over variable orders, as used in HEPGAME. Every iteration
+    /// selects a prefix of the order using UCT with exploration constant `exploration`
+    /// and completes it randomly.
+    MonteCarloTreeSearch { iterations: usize, exploration: f64 },
+}
+
+/// Settings for the search of an optimal Horner scheme.
+#[derive(Clone, Debug)]
+pub struct HornerOptimizationSettings {
+    /// The strategies to try.
+    pub strategies: Vec<HornerStrategy>,
+    /// The number of independent runs per randomized strategy. All runs
+    /// are performed in parallel on the rayon thread pool.
+    pub runs: usize,
+    /// The seed of the random number generators, which makes the search reproducible.
+    pub seed: u64,
+}
+
+impl Default for HornerOptimizationSettings {
+    fn default() -> Self {
+        HornerOptimizationSettings {
+            strategies: vec![
+                HornerStrategy::Occurrence,
+                HornerStrategy::SimulatedAnnealing {
+                    iterations: 1000,
+                    start_temperature: 0.1,
+                    end_temperature: 0.001,
+                },
+            ],
+            runs: 1,
+            seed: 0,
+        }
+    }
+}
+
+/// The result of a single run of a Horner scheme search strategy.
+#[derive(Clone, Debug)]
+pub struct HornerStrategyReport {
+    pub strategy: HornerStrategy,
+    pub run: usize,
+    pub op_count: usize,
+    pub scheme: Vec<usize>,
+}
+
+type HornerBox = Box<(
+    Option<HornerScheme<RationalField>>,
+    Option<HornerScheme<RationalField>>,
+)>;
+
+/// Computes the number of operations of the Horner schemes of a list of polynomials for a given variable order,
+/// reusing its buffers between calls.
+struct HornerCost<'a, E: Exponent> {
+    polys: &'a [&'a MultivariatePolynomial<RationalField, E>],
+    indices: Vec<usize>,
+    power_sub: Vec<E>,
+    boxes: Vec<HornerBox>,
+}
+
+impl<'a, E: Exponent> HornerCost<'a, E> {
+    fn new(polys: &'a [&'a MultivariatePolynomial<RationalField, E>]) -> Self {
+        HornerCost {
+            polys,
+            indices: vec![],
+            power_sub: vec![E::zero(); polys[0].nvars()],
+            boxes: vec![],
+        }
+    }
+
+    fn op_count(&mut self, scheme: &[usize]) -> usize {
+        let mut hs = Vec::with_capacity(self.polys.len());
+        for x in self.polys {
+            self.indices.clear();
+            self.indices.extend(0..x.nterms());
+
+            hs.push(x.to_horner_scheme_impl(
+                scheme,
+                &mut self.indices,
+                0,
+                &mut self.power_sub,
+                &mut self.boxes,
+            ));
+        }
+
+        // use the same hash set for all polynomials
+        let mut hash_set = HashSet::default();
+        let mut op_count = 0;
+        for x in &hs {
+            op_count += BorrowedHornerScheme::from(x).op_count_cse_impl(&mut hash_set);
+        }
+        drop(hash_set);
+
+        for mut x in hs {
+            x.cleanup(&mut self.boxes);
+        }
+
+        op_count
+    }
+}
+
+/// A node in the Monte Carlo search tree over variable orders.
+struct MctsNode {
+    visits: usize,
+    reward: f64,
+    children: Vec<usize>,
+    var: usize,
+    untried: Vec<usize>,
+}
+
 impl HornerScheme<RationalField> {
     pub fn optimize_multiple<E: Exponent>(
         polys: &[&MultivariatePolynomial<RationalField, E>],
@@ -553,17 +699,7 @@ impl HornerScheme<RationalField> {
         );
 
         // the starting scheme is the descending order of occurrence of variables
-        let mut occurrence: Vec<_> = (0..polys[0].nvars()).map(|x| (x, 0)).collect();
-        for es in polys[0].exponents.chunks(polys[0].nvars()) {
-            for ((_, o), e) in occurrence.iter_mut().zip(es) {
-                if *e > E::zero() {
-                    *o += 1;
-                }
-            }
-        }
-        occurrence.sort_by_key(|e| Reverse(e.1));
-
-        let mut scheme: Vec<_> = occurrence.into_iter().map(|(v, _)| v).collect();
+        let mut scheme = Self::occurrence_order(polys);
 
         let mut indices: Vec<_> = vec![];
         let mut power_sub = vec![E::zero(); polys[0].nvars()];
@@ -648,6 +784,260 @@ impl HornerScheme<RationalField> {
         (best, best_score, best_scheme)
     }
 
+    /// Get the variables of the polynomials ordered by descending number of occurrences in the first polynomial.
+    fn occurrence_order<E: Exponent>(
+        polys: &[&MultivariatePolynomial<RationalField, E>],
+    ) -> Vec<usize> {
+        let mut occurrence: Vec<_> = (0..polys[0].nvars()).map(|x| (x, 0)).collect();
+        for es in polys[0].exponents.chunks(polys[0].nvars()) {
+            for ((_, o), e) in occurrence.iter_mut().zip(es) {
+                if *e > E::zero() {
+                    *o += 1;
+                }
+            }
+        }
+        occurrence.sort_by_key(|e| Reverse(e.1));
+
+        occurrence.into_iter().map(|(v, _)| v).collect()
+    }
+
+    /// Find the Horner schemes of `polys` that minimize the number of operations,
+    /// taking common subexpressions into account, by running all strategies in `settings`
+    /// in parallel. Returns the best schemes, their operation count, the variable order and
+    /// a report of the operation count reached by every run.
+    pub fn optimize_multiple_with_settings<E: Exponent + Send + Sync>(
+        polys: &[&MultivariatePolynomial<RationalField, E>],
+        settings: &HornerOptimizationSettings,
+    ) -> (
+        Vec<HornerScheme<RationalField>>,
+        usize,
+        Vec<usize>,
+        Vec<HornerStrategyReport>,
+    ) {
+        if polys.is_empty() {
+            return (vec![], 0, vec![], vec![]);
+        }
+
+        assert!(
+            polys
+                .windows(2)
+                .all(|r| r[0].variables == r[1].variables && r[0].nvars() == r[1].nvars()),
+            "Variable maps of all polynomials must be the same"
+        );
+
+        let start = Self::occurrence_order(polys);
+
+        let mut jobs = vec![];
+        for s in &settings.strategies {
+            let runs = if let HornerStrategy::Occurrence = s {
+                1
+            } else {
+                settings.runs
+            };
+            for run in 0..runs {
+                jobs.push((s, run));
+            }
+        }
+
+        let reports: Vec<_> = jobs
+            .into_par_iter()
+            .enumerate()
+            .map(|(stream_id, (strategy, run))| {
+                let mut rng = MonteCarloRng::new(settings.seed, stream_id);
+                let mut cost = HornerCost::new(polys);
+
+                let (op_count, scheme) = match strategy {
+                    HornerStrategy::Occurrence => (cost.op_count(&start), start.clone()),
+                    HornerStrategy::SimulatedAnnealing {
+                        iterations,
+                        start_temperature,
+                        end_temperature,
+                    } => Self::simulated_annealing(
+                        &mut cost,
+                        start.clone(),
+                        *iterations,
+                        *start_temperature,
+                        *end_temperature,
+                        &mut rng,
+                    ),
+                    HornerStrategy::MonteCarloTreeSearch {
+                        iterations,
+                        exploration,
+                    } => Self::monte_carlo_tree_search(
+                        &mut cost,
+                        &start,
+                        *iterations,
+                        *exploration,
+                        &mut rng,
+                    ),
+                };
+
+                HornerStrategyReport {
+                    strategy: strategy.clone(),
+                    run,
+                    op_count,
+                    scheme,
+                }
+            })
+            .collect();
+
+        let best = reports.iter().min_by_key(|r| r.op_count).unwrap();
+        let best_scheme = best.scheme.clone();
+        let best_score = best.op_count;
+
+        let mut indices = vec![];
+        let mut power_sub = vec![E::zero(); polys[0].nvars()];
+        let mut horner_boxes = vec![];
+        let hs = polys
+            .iter()
+            .map(|x| {
+                indices.clear();
+                indices.extend(0..x.nterms());
+                x.to_horner_scheme_impl(
+                    &best_scheme,
+                    &mut indices,
+                    0,
+                    &mut power_sub,
+                    &mut horner_boxes,
+                )
+            })
+            .collect();
+
+        (hs, best_score, best_scheme, reports)
+    }
+
+    fn simulated_annealing<E: Exponent>(
+        cost: &mut HornerCost<E>,
+        mut scheme: Vec<usize>,
+        iterations: usize,
+        start_temperature: f64,
+        end_temperature: f64,
+        rng: &mut MonteCarloRng,
+    ) -> (usize, Vec<usize>) {
+        let mut cur = cost.op_count(&scheme);
+        let mut best = (cur, scheme.clone());
+
+        if scheme.len() < 2 {
+            return best;
+        }
+
+        for i in 0..iterations {
+            let t = start_temperature
+                * (end_temperature / start_temperature).powf(i as f64 / iterations as f64);
+
+            let a = rng.gen_range(0..scheme.len());
+            let b = rng.gen_range(0..scheme.len());
+            scheme.swap(a, b);
+
+            let new = cost.op_count(&scheme);
+            if new <= cur
+                || rng.gen::<f64>() < (-((new - cur) as f64) / (cur.max(1) as f64 * t)).exp()
+            {
+                cur = new;
+                if cur < best.0 {
+                    best = (cur, scheme.clone());
+                }
+            } else {
+                scheme.swap(a, b);
+            }
+        }
+
+        best
+    }
+
+    fn monte_carlo_tree_search<E: Exponent>(
+        cost: &mut HornerCost<E>,
+        start: &[usize],
+        iterations: usize,
+        exploration: f64,
+        rng: &mut MonteCarloRng,
+    ) -> (usize, Vec<usize>) {
+        let start_ops = cost.op_count(start);
+        let mut best = (start_ops, start.to_vec());
+
+        let mut tree = vec![MctsNode {
+            visits: 0,
+            reward: 0.,
+            children: vec![],
+            var: usize::MAX,
+            untried: start.to_vec(),
+        }];
+
+        let mut path = vec![];
+        let mut order = vec![];
+        for _ in 0..iterations {
+            path.clear();
+            order.clear();
+
+            // select a child using UCT until a node with untried moves is found
+            let mut node = 0;
+            path.push(node);
+            while tree[node].untried.is_empty() && !tree[node].children.is_empty() {
+                let log_visits = (tree[node].visits as f64).ln();
+                node = *tree[node]
+                    .children
+                    .iter()
+                    .max_by(|&&a, &&b| {
+                        let uct = |c: usize| {
+                            tree[c].reward / tree[c].visits as f64
+                                + exploration * (log_visits / tree[c].visits as f64).sqrt()
+                        };
+                        uct(a).partial_cmp(&uct(b)).unwrap()
+                    })
+                    .unwrap();
+                path.push(node);
+                order.push(tree[node].var);
+            }
+
+            // expand
+            if !tree[node].untried.is_empty() {
+                let i = rng.gen_range(0..tree[node].untried.len());
+                let var = tree[node].untried.swap_remove(i);
+                order.push(var);
+
+                let untried = start
+                    .iter()
+                    .filter(|v| !order.contains(v))
+                    .cloned()
+                    .collect();
+                tree.push(MctsNode {
+                    visits: 0,
+                    reward: 0.,
+                    children: vec![],
+                    var,
+                    untried,
+                });
+                let child = tree.len() - 1;
+                tree[node].children.push(child);
+                path.push(child);
+            }
+
+            // complete the order randomly
+            let mut rest: Vec<_> = start
+                .iter()
+                .filter(|v| !order.contains(v))
+                .cloned()
+                .collect();
+            while !rest.is_empty() {
+                let i = rng.gen_range(0..rest.len());
+                order.push(rest.swap_remove(i));
+            }
+
+            let ops = cost.op_count(&order);
+            if ops < best.0 {
+                best = (ops, order.clone());
+            }
+
+            let reward = start_ops as f64 / ops.max(1) as f64;
+            for n in &path {
+                tree[*n].visits += 1;
+                tree[*n].reward += reward;
+            }
+        }
+
+        best
+    }
+
     fn get_constants(&self, constants: &mut HashMap<Rational, usize>, shift: usize) {
         match self {
             HornerScheme::Node(n) => {
@@ -1470,6 +1860,28 @@ impl<N: Real + for<'b> From<&'b Rational>> InstructionEvaluator<N> {
     }
 }
 
+impl<N: Real> InstructionEvaluator<Dual<N>> {
+    /// Evaluate the converted polynomials at a given sample point and compute
+    /// the Jacobian with respect to the inputs with indices `wrt`, using forward-mode
+    /// automatic differentiation over the same instruction list.
+    ///
+    /// The real part of every output is its value and `dual[j]` is the derivative
+    /// with respect to `samples[wrt[j]]`.
+    pub fn evaluate_with_jacobian(&mut self, samples: &[N], wrt: &[usize]) -> &[Dual<N>] {
+        for (i, (e, s)) in self.eval.iter_mut().zip(samples).enumerate() {
+            let mut dual = vec![s.zero(); wrt.len()];
+            for (d, w) in dual.iter_mut().zip(wrt) {
+                if *w == i {
+                    *d = s.one();
+                }
+            }
+            *e = Dual::new(s.clone(), dual);
+        }
+
+        self.evaluate_impl()
+    }
+}
+
 impl<N: NumericalFloatLike> InstructionListOutput<N> {
     /// Convert all numbers in the instruction list from the field `N` to the field `NO`.
     pub fn convert<'a, NO: NumericalFloatLike + for<'b> From<&'b N>>(
@@ -1865,6 +2277,184 @@ impl ExpressionEvaluator {
     pub fn get_input(&self) -> &[super::Variable] {
         &self.input
     }
+
+    /// Convert the computational graph to a numerical evaluator, where the rational numbers
+    /// in the expressions are converted using `coeff_map`. The evaluator can be
+    /// called repeatedly with different input, see [NumericalExpressionEvaluator::evaluate].
+    ///
+    /// Returns an error if the graph contains inputs that cannot be evaluated numerically.
+    pub fn evaluator<T: Real, F: Fn(&Rational) -> T + Copy>(
+        &self,
+        coeff_map: F,
+    ) -> Result<NumericalExpressionEvaluator<T>, String> {
+        let mut levels = Vec::with_capacity(self.operations.len());
+        for (id, _, o, _) in &self.operations {
+            let mut sources = Vec::with_capacity(o.input_map.len());
+            for x in &o.input_map {
+                let source = match x {
+                    super::Variable::Symbol(s) => match *s {
+                        State::PI => InputSource::Constant(coeff_map(&(-1).into()).acos()),
+                        State::E => InputSource::Constant(coeff_map(&1.into()).exp()),
+                        State::I => Err(
+                            "The imaginary unit is not supported in numerical evaluation"
+                                .to_owned(),
+                        )?,
+                        _ => InputSource::Array(*s, 0),
+                    },
+                    super::Variable::Function(name, f) => {
+                        let AtomView::Fun(f) = f.as_view() else {
+                            unreachable!()
+                        };
+
+                        let index = match f.iter().next() {
+                            Some(AtomView::Num(n)) if f.get_nargs() == 1 => {
+                                match n.get_coeff_view() {
+                                    CoefficientView::Natural(n, 1) if n >= 0 => n as usize,
+                                    _ => Err(format!(
+                                        "Array index of {} is not a natural number",
+                                        f.as_view()
+                                    ))?,
+                                }
+                            }
+                            _ => Err(format!("{} is not an array", f.as_view()))?,
+                        };
+
+                        InputSource::Array(*name, index)
+                    }
+                    _ => Err(format!("Unsupported input {}", x))?,
+                };
+                sources.push(source);
+            }
+
+            let super::Variable::Symbol(id) = id else {
+                unreachable!()
+            };
+
+            levels.push((*id, sources, o.convert_with_map(coeff_map).evaluator()));
+        }
+
+        Ok(NumericalExpressionEvaluator {
+            input: self
+                .input
+                .iter()
+                .map(|x| match x {
+                    super::Variable::Symbol(s) => *s,
+                    _ => unreachable!("Input variables are symbols"),
+                })
+                .collect(),
+            levels,
+            samples: vec![],
+        })
+    }
+
+    /// Evaluate the computational graph numerically. The arrays in `input` must be
+    /// provided in the order of [`get_input`](Self::get_input), where a symbol is
+    /// represented by an array of length one. The rational numbers in the expressions
+    /// are converted using `coeff_map`.
+    ///
+    /// Returns the output array of the last level. To evaluate the graph repeatedly,
+    /// convert it once using [`evaluator`](Self::evaluator) instead.
+    pub fn evaluate<T: Real, F: Fn(&Rational) -> T + Copy>(
+        &self,
+        coeff_map: F,
+        input: &[&[T]],
+    ) -> Result<Vec<T>, String> {
+        self.evaluator(coeff_map)?.evaluate(input)
+    }
+
+    /// Evaluate the computational graph to `target_digits` correct decimal digits for every output,
+    /// by evaluating it with interval arithmetic at increasing precision until the error bounds are small enough.
+    /// The input values are considered exact and must be provided in the order of [`get_input`](Self::get_input).
+    /// Outputs that cancel to (nearly) zero have no correct significant digits and are only accepted if they are
+    /// within the `absolute_tolerance` of zero.
+    ///
+    /// Returns the output array of the last level and the precision in bits that was used,
+    /// or an error if `max_prec` bits are not sufficient.
+    pub fn evaluate_with_precision(
+        &self,
+        input: &[&[MultiPrecisionFloat]],
+        target_digits: u32,
+        max_prec: u32,
+        absolute_tolerance: Option<f64>,
+    ) -> Result<(Vec<MultiPrecisionFloat>, u32), String> {
+        evaluate_with_increasing_precision(target_digits, max_prec, absolute_tolerance, |prec| {
+            let intervals: Vec<Vec<_>> = input
+                .iter()
+                .map(|x| x.iter().map(|f| float_to_interval(f, prec)).collect())
+                .collect();
+            let interval_refs: Vec<_> = intervals.iter().map(|x| x.as_slice()).collect();
+
+            self.evaluator(|r| r.to_multi_prec_interval(prec))?
+                .evaluate(&interval_refs)
+        })
+    }
+}
+
+/// The origin of an input value of a level of an [ExpressionEvaluator].
+#[derive(Clone)]
+enum InputSource<T> {
+    Constant(T),
+    Array(Symbol, usize),
+}
+
+/// A numerical evaluator for the computational graph of an [ExpressionEvaluator],
+/// created using [ExpressionEvaluator::evaluator].
+#[derive(Clone)]
+pub struct NumericalExpressionEvaluator<T: Real> {
+    input: Vec<Symbol>,
+    levels: Vec<(Symbol, Vec<InputSource<T>>, InstructionEvaluator<T>)>,
+    samples: Vec<T>,
+}
+
+impl<T: Real> NumericalExpressionEvaluator<T> {
+    /// Evaluate the computational graph. The arrays in `input` must be
+    /// provided in the order of [`ExpressionEvaluator::get_input`], where a symbol is
+    /// represented by an array of length one.
+    ///
+    /// Returns the output array of the last level, or an error if an array is
+    /// missing or is too short.
+    pub fn evaluate(&mut self, input: &[&[T]]) -> Result<Vec<T>, String> {
+        if input.len() != self.input.len() {
+            Err(format!(
+                "Expected {} input arrays, but got {}",
+                self.input.len(),
+                input.len()
+            ))?;
+        }
+
+        let mut arrays: HashMap<Symbol, Vec<T>> = HashMap::default();
+        for (s, values) in self.input.iter().zip(input) {
+            arrays.insert(*s, values.to_vec());
+        }
+
+        let mut res = vec![];
+        for (id, sources, eval) in &mut self.levels {
+            self.samples.clear();
+            for s in sources.iter() {
+                match s {
+                    InputSource::Constant(c) => self.samples.push(c.clone()),
+                    InputSource::Array(name, index) => {
+                        let Some(a) = arrays.get(name) else {
+                            Err(format!("Array {} not in input", State::get_name(*name)))?
+                        };
+                        let Some(v) = a.get(*index) else {
+                            Err(format!(
+                                "Index {} is out of bounds for array {}",
+                                index,
+                                State::get_name(*name)
+                            ))?
+                        };
+                        self.samples.push(v.clone());
+                    }
+                }
+            }
+
+            res = eval.evaluate_with_input(&self.samples).to_vec();
+            arrays.insert(*id, res.clone());
+        }
+
+        Ok(res)
+    }
 }
 
 impl std::fmt::Display for ExpressionEvaluator {
@@ -1936,11 +2526,18 @@ auto 𝑖 = 1i;\n",
 mod test {
     use crate::{
         atom::Atom,
-        domains::{float::Complex, rational::Q},
+        domains::{
+            float::{Complex, Dual},
+            rational::Q,
+        },
         poly::{
-            evaluate::{BorrowedHornerScheme, InstructionSetPrinter},
+            evaluate::{
+                BorrowedHornerScheme, ExpressionEvaluator, HornerOptimizationSettings,
+                HornerStrategy, InstructionSetPrinter,
+            },
             polynomial::MultivariatePolynomial,
         },
+        state::State,
     };
 
     use wide::f64x4;
@@ -2065,4 +2662,124 @@ a0^2*a2*b2^2*b3^3-2*a0^2*a2*b1*b3^4-a0^2*a1*b2*b3^4+a0^3*b3^5";
             (res.re - 3230756.634848104).abs() < 1e-6 && (res.im - 2522437.0904901037).abs() < 1e-6
         );
     }
+
+    #[test]
+    fn jacobian() {
+        let poly: MultivariatePolynomial<_, u8> = Atom::parse("x^2*y+3*y^3+x*y*z+5")
+            .unwrap()
+            .to_polynomial(&Q, None);
+
+        let (h, _ops, _scheme) = poly.optimize_horner_scheme(10);
+        let mut i = h.to_instr(poly.nvars());
+        i.fuse_operations();
+
+        for _ in 0..100 {
+            if !i.common_pair_elimination() {
+                break;
+            }
+            i.fuse_operations();
+        }
+
+        let o = i.to_output(poly.variables.as_ref().to_vec(), true);
+        let mut evaluator = o.convert::<Dual<f64>>().evaluator();
+
+        let x = poly
+            .get_vars_ref()
+            .iter()
+            .position(|v| v.to_string() == "x")
+            .unwrap();
+        let y = poly
+            .get_vars_ref()
+            .iter()
+            .position(|v| v.to_string() == "y")
+            .unwrap();
+        let z = poly
+            .get_vars_ref()
+            .iter()
+            .position(|v| v.to_string() == "z")
+            .unwrap();
+
+        let mut samples = vec![0.; 3];
+        samples[x] = 2.;
+        samples[y] = 3.;
+        samples[z] = 5.;
+
+        let res = &evaluator.evaluate_with_jacobian(&samples, &[x, y])[0];
+        assert_eq!(res.real, 128.);
+        assert_eq!(res.dual, vec![27., 95.]);
+    }
+
+    #[test]
+    fn expression_evaluator_precision() {
+        let levels = vec![
+            vec![(
+                State::get_symbol("x0"),
+                vec![
+                    Atom::parse("p1 + 10^30").unwrap(),
+                    Atom::parse("p2(0)*p2(1)").unwrap(),
+                ],
+            )],
+            vec![(
+                State::get_symbol("x1"),
+                vec![Atom::parse("x0(0)^2 - 10^60 - 2*10^30*p1 + x0(1)").unwrap()],
+            )],
+        ];
+
+        let e = ExpressionEvaluator::new(levels, 10);
+        let input: Vec<_> = e.get_input().iter().map(|x| x.to_string()).collect();
+        assert_eq!(input, ["p1", "p2"]);
+
+        let p1 = [rug::Float::with_val(53, 0.5)];
+        let p2 = [rug::Float::with_val(53, 3), rug::Float::with_val(53, 0.25)];
+
+        let r = e.evaluate(|r| r.into(), &[&[0.5], &[3., 0.25]]).unwrap();
+        assert_ne!(r[0], 1.);
+
+        // arrays that are too short are reported
+        let mut eval = e.evaluator(|r| r.into()).unwrap();
+        assert!(eval.evaluate(&[&[0.5], &[3.]]).is_err());
+        assert_eq!(eval.evaluate(&[&[0.5], &[3., 0.25]]).unwrap(), r);
+
+        let (r, _prec) = e
+            .evaluate_with_precision(&[&p1, &p2], 20, 1000, None)
+            .unwrap();
+        assert_eq!(r, [1.]);
+    }
+
+    #[test]
+    fn horner_strategies() {
+        let poly: MultivariatePolynomial<_, u8> =
+            Atom::parse(RES_53).unwrap().to_polynomial(&Q, None);
+
+        let settings = HornerOptimizationSettings {
+            strategies: vec![
+                HornerStrategy::Occurrence,
+                HornerStrategy::SimulatedAnnealing {
+                    iterations: 50,
+                    start_temperature: 0.1,
+                    end_temperature: 0.001,
+                },
+                HornerStrategy::MonteCarloTreeSearch {
+                    iterations: 50,
+                    exploration: 0.5,
+                },
+            ],
+            runs: 2,
+            seed: 3,
+        };
+
+        let (h, ops, scheme, reports) = poly.optimize_horner_scheme_with_settings(&settings);
+        assert_eq!(reports.len(), 5);
+        assert!(reports.iter().all(|r| r.op_count >= ops));
+        assert!(ops <= reports[0].op_count);
+        assert_eq!(BorrowedHornerScheme::from(&h).op_count_cse(), ops);
+
+        let (_, ops2, scheme2, reports2) = poly.optimize_horner_scheme_with_settings(&settings);
+        assert_eq!(ops, ops2);
+        assert_eq!(scheme, scheme2);
+        assert_eq!(
+            reports.iter().map(|r| r.op_count).collect::<Vec<_>>(),
+            reports2.iter().map(|r| r.op_count).collect::<Vec<_>>()
+        );
+    }
 }
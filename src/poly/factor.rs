@@ -7,9 +7,10 @@ use tracing::debug;
 use crate::{
     combinatorics::CombinationIterator,
     domains::{
+        algebraic_number::AlgebraicNumberRing,
         finite_field::{
-            FiniteField, FiniteFieldCore, FiniteFieldWorkspace, PrimeIteratorU64, ToFiniteField,
-            Zp, Zp64,
+            FiniteField, FiniteFieldCore, FiniteFieldLike, FiniteFieldWorkspace, PrimeIteratorU64,
+            ToFiniteField, Zp, Zp64,
         },
         integer::{Integer, IntegerRing, Z},
         rational::{RationalField, Q},
@@ -342,10 +343,159 @@ impl<E: Exponent> Factorize for MultivariatePolynomial<RationalField, E, LexOrde
     }
 }
 
-impl<UField: FiniteFieldWorkspace, E: Exponent> Factorize
-    for MultivariatePolynomial<FiniteField<UField>, E, LexOrder>
-where
-    FiniteField<UField>: Field + PolynomialGCD<E> + FiniteFieldCore<UField>,
+impl<E: Exponent> Factorize
+    for MultivariatePolynomial<AlgebraicNumberRing<RationalField>, E, LexOrder>
+{
+    fn square_free_factorization(&self) -> Vec<(Self, usize)> {
+        if self.is_zero() {
+            return vec![];
+        }
+
+        let c = self.lcoeff();
+        let stripped = self.clone().make_monic();
+
+        let mut factors = vec![];
+        let fs = stripped.factor_separable();
+
+        for f in fs {
+            let mut nf = f.square_free_factorization_0_char();
+            factors.append(&mut nf);
+        }
+
+        if factors.is_empty() || !self.field.is_one(&c) {
+            factors.push((self.constant(c), 1))
+        }
+
+        factors
+    }
+
+    fn factor(&self) -> Vec<(Self, usize)> {
+        let sf = self.square_free_factorization();
+
+        let mut factors = vec![];
+        for (f, p) in sf {
+            debug!("SFF {} {}", f, p);
+
+            if f.is_constant() {
+                factors.push((f, p));
+                continue;
+            }
+
+            factors.extend(f.trager_factorization().into_iter().map(|ff| (ff, p)));
+        }
+
+        factors
+    }
+}
+
+impl<E: Exponent> MultivariatePolynomial<AlgebraicNumberRing<RationalField>, E, LexOrder> {
+    /// Compute the norm of the polynomial, which is the resultant of the minimal polynomial
+    /// of the number field and the polynomial, in the variable of the number field.
+    /// The result is a polynomial over the integers, defined up to a constant factor.
+    ///
+    /// The variable of the number field is appended to the variable map if it is not present.
+    pub fn norm(&self) -> MultivariatePolynomial<IntegerRing, E> {
+        let poly = self.from_number_field();
+        let var = &self.field.poly().get_vars_ref()[0];
+        let var_index = poly.get_vars_ref().iter().position(|x| x == var).unwrap();
+
+        let mut min_poly = poly.zero();
+        let mut exp = vec![E::zero(); poly.nvars()];
+        for t in self.field.poly() {
+            exp[var_index] = E::from_u32(t.exponents[0] as u32);
+            min_poly.append_monomial(t.coefficient.clone(), &exp);
+        }
+
+        let to_integer = |p: &MultivariatePolynomial<RationalField, E>| {
+            let c = p.content();
+            p.map_coeff(|coeff| (coeff / &c).numerator(), Z)
+        };
+
+        let poly = to_integer(&poly);
+        let min_poly = to_integer(&min_poly);
+
+        if poly.degree(var_index) == E::zero() {
+            return poly.pow(min_poly.degree(var_index).to_u32() as usize);
+        }
+
+        min_poly
+            .to_univariate(var_index)
+            .resultant_prs(&poly.to_univariate(var_index))
+    }
+
+    /// Find the smallest `k` such that the norm of `self(var - k*a)`, where `a` is the generator
+    /// of the number field, is square free. Returns `k` and the norm.
+    ///
+    /// The polynomial must be square free and all of its factors must depend on `var`.
+    pub fn square_free_norm(&self, var: usize) -> (u64, MultivariatePolynomial<IntegerRing, E>) {
+        let field = &self.field;
+        let alpha = field.to_element(field.poly().monomial(Q.one(), vec![1]));
+
+        for k in 0u64.. {
+            let shifted = if k == 0 {
+                self.clone()
+            } else {
+                self.shift_var(var, &field.neg(&field.mul(&field.nth(k), &alpha)))
+            };
+
+            let norm = shifted.norm();
+            if norm.degree(var) > E::zero() && norm.gcd(&norm.derivative(var)).is_constant() {
+                return (k, norm);
+            }
+        }
+
+        unreachable!()
+    }
+
+    /// Factor a square-free polynomial over an algebraic number field using Trager's algorithm.
+    /// The polynomial is shifted until its norm is square free, after which the factors
+    /// of the norm over the rationals are mapped back using gcds.
+    fn trager_factorization(&self) -> Vec<Self> {
+        let field = &self.field;
+        let var = (0..self.nvars())
+            .find(|v| self.degree(*v) > E::zero())
+            .unwrap();
+
+        let (k, norm) = self.square_free_norm(var);
+
+        let norm_factors: Vec<_> = norm
+            .factor()
+            .into_iter()
+            .filter(|(f, _)| !f.is_constant())
+            .collect();
+
+        debug!(
+            "Norm factors of {} with shift {}: {:?}",
+            self, k, norm_factors
+        );
+
+        if norm_factors.len() == 1 {
+            return vec![self.clone()];
+        }
+
+        let alpha = field.to_element(field.poly().monomial(Q.one(), vec![1]));
+        let shift = field.mul(&field.nth(k), &alpha);
+
+        let mut factors = Vec::with_capacity(norm_factors.len());
+        for (f, _) in norm_factors {
+            let mut f_k = self.zero();
+            for t in &f {
+                f_k.append_monomial(
+                    field.to_element(field.poly().constant(t.coefficient.into())),
+                    &t.exponents[..self.nvars()],
+                );
+            }
+
+            let f_k = f_k.shift_var(var, &shift);
+            factors.push(self.gcd(&f_k).make_monic());
+        }
+
+        factors
+    }
+}
+
+impl<F: FiniteFieldLike + PolynomialGCD<E>, E: Exponent> Factorize
+    for MultivariatePolynomial<F, E, LexOrder>
 {
     fn square_free_factorization(&self) -> Vec<(Self, usize)> {
         let c = self.lcoeff();
@@ -434,11 +584,7 @@ where
     }
 }
 
-impl<UField: FiniteFieldWorkspace, E: Exponent>
-    MultivariatePolynomial<FiniteField<UField>, E, LexOrder>
-where
-    FiniteField<UField>: Field + PolynomialGCD<E> + FiniteFieldCore<UField>,
-{
+impl<F: FiniteFieldLike + PolynomialGCD<E>, E: Exponent> MultivariatePolynomial<F, E, LexOrder> {
     /// Bernardin's algorithm for square free factorization.
     fn square_free_factorization_bernardin(&self) -> Vec<(Self, usize)> {
         if self.is_constant() {
@@ -468,10 +614,13 @@ where
         }
 
         // take the pth root
-        // the coefficients remain unchanged, since x^1/p = x
+        // the coefficients of a prime field remain unchanged, since x^1/p = x
         // since the derivative in every var is 0, all powers are divisible by p
-        let p = self.field.get_prime().to_u64() as usize;
+        let p = self.field.characteristic().to_i64().unwrap_or(i64::MAX) as usize;
         let mut b = f.clone();
+        for c in &mut b.coefficients {
+            *c = self.field.pth_root(c);
+        }
         for es in b.exponents_iter_mut() {
             for e in es {
                 if e.is_zero() {
@@ -527,8 +676,9 @@ where
 
         let mut factors = vec![];
 
+        let p = self.field.characteristic().to_i64().unwrap_or(i64::MAX) as usize;
         let mut i = 1;
-        while !w.is_constant() && i < self.field.get_prime().to_u64() as usize {
+        while !w.is_constant() && i < p {
             let z = v - w.derivative(var);
             let g = w.gcd(&z);
             w = w / &g;
@@ -546,7 +696,6 @@ where
 
     /// Perform distinct degree factorization on a monic, univariate and square-free polynomial.
     pub fn distinct_degree_factorization(&self) -> Vec<(usize, Self)> {
-        assert!(self.field.get_prime().to_u64() != 2);
         let Some(var) = self.last_exponents().iter().position(|x| *x > E::zero()) else {
             return vec![(0, self.clone())]; // constant polynomial
         };
@@ -562,7 +711,7 @@ where
         while !f.is_one() {
             i += 1;
 
-            h = h.exp_mod_univariate(self.field.get_prime().to_u64().into(), &mut f);
+            h = h.exp_mod_univariate(self.field.size(), &mut f);
 
             let mut g = f.gcd(&(&h - &x));
 
@@ -586,7 +735,6 @@ where
     /// Perform Cantor-Zassenhaus's probabilistic algorithm for
     /// finding irreducible factors of degree `d`.
     pub fn equal_degree_factorization(&self, d: usize) -> Vec<Self> {
-        assert!(self.field.get_prime().to_u64() != 2);
         let mut s = self.clone().make_monic();
 
         let Some(var) = self.last_exponents().iter().position(|x| *x > E::zero()) else {
@@ -615,16 +763,14 @@ where
 
             if d == 1 {
                 exp[var] = E::zero();
-                random_poly.append_monomial(self.field.nth(try_counter), &exp);
+                random_poly.append_monomial(self.field.nth_element(try_counter), &exp);
                 exp[var] = E::one();
                 random_poly.append_monomial(self.field.one(), &exp);
                 try_counter += 1;
             } else {
                 for i in 0..2 * d {
-                    let r = self
-                        .field
-                        .nth(rng.gen_range(0..self.field.get_prime().to_u64()));
-                    if !FiniteField::<UField>::is_zero(&r) {
+                    let r = self.field.sample(&mut rng, (0, i64::MAX));
+                    if !F::is_zero(&r) {
                         exp[var] = E::from_u32(i as u32);
                         random_poly.append_monomial(r, &exp);
                     }
@@ -641,11 +787,23 @@ where
                 break g;
             }
 
-            // TODO: use Frobenius map and modular composition to prevent computing large exponent poly^(p^d)
-            let p: Integer = self.field.get_prime().to_u64().into();
-            let b = random_poly
-                .exp_mod_univariate(&(&p.pow(d as u64) - &1i64.into()) / &2i64.into(), &mut s)
-                - self.one();
+            // TODO: use Frobenius map and modular composition to prevent computing large exponent poly^(q^d)
+            let q = self.field.size();
+            let b = if self.field.characteristic() == Integer::Natural(2) {
+                // use the trace map r + r^2 + ... + r^(2^(kd-1)) for q = 2^k
+                let k = q.to_i64().unwrap().trailing_zeros() as usize;
+                let mut t = random_poly.clone();
+                let mut b = random_poly.clone();
+                for _ in 1..k * d {
+                    t = t.exp_mod_univariate(Integer::Natural(2), &mut s);
+                    b = &b + &t;
+                }
+                b
+            } else {
+                random_poly
+                    .exp_mod_univariate(&(&q.pow(d as u64) - &1i64.into()) / &2i64.into(), &mut s)
+                    - self.one()
+            };
 
             let g = b.gcd(&s);
 
@@ -797,14 +955,19 @@ where
                 break;
             }
 
-            sample_point = self.field.nth(i);
+            if Integer::from(i) >= self.field.size() {
+                // the field is too small to contain a suitable sample point
+                return self.factor_in_extension();
+            }
+
+            sample_point = self.field.nth_element(i);
             uni_f = self.replace(interpolation_var, &sample_point);
             i += 1;
         }
 
         let mut d = self.degree(interpolation_var).to_u32();
 
-        let shifted_poly = if !FiniteField::<UField>::is_zero(&sample_point) {
+        let shifted_poly = if !F::is_zero(&sample_point) {
             self.shift_var_cached(interpolation_var, &sample_point)
         } else {
             self.clone()
@@ -873,7 +1036,7 @@ where
 
         rec_factors.push(rest);
 
-        if !FiniteField::<UField>::is_zero(&sample_point) {
+        if !F::is_zero(&sample_point) {
             for x in &mut rec_factors {
                 // shift the polynomial to y - sample
                 *x = x.shift_var_cached(interpolation_var, &self.field.neg(&sample_point));
@@ -883,6 +1046,56 @@ where
         rec_factors
     }
 
+    /// Factor a square-free polynomial in an extension field, for when the field is
+    /// too small to contain suitable sample points. The factors over the extension
+    /// are multiplied with their conjugates to yield the factors over the original field.
+    fn factor_in_extension(&self) -> Vec<Self> {
+        let total_degree: u64 = (0..self.nvars())
+            .map(|v| self.degree(v).to_u32() as u64)
+            .sum();
+        let (ext, root) = self.field.field_extension(100 * total_degree);
+        let ext_poly = self.to_galois_field_extension(&ext, &root);
+        debug!("Factor {} in extension{}", self, ext_poly.field);
+
+        let mut ext_factors: Vec<_> = ext_poly
+            .factor()
+            .into_iter()
+            .filter(|(f, _)| !f.is_constant())
+            .map(|(f, _)| f.make_monic())
+            .collect();
+
+        let q = self.field.size().to_i64().unwrap() as u64;
+        let mut factors = vec![];
+        while let Some(f) = ext_factors.pop() {
+            // multiply all distinct conjugates under the Frobenius map a -> a^q
+            let mut conj = f.clone();
+            let mut norm = f.clone();
+            loop {
+                for c in &mut conj.coefficients {
+                    *c = f.field.pow(c, q);
+                }
+
+                if conj == f {
+                    break;
+                }
+
+                if let Some(pos) = ext_factors.iter().position(|g| *g == conj) {
+                    ext_factors.swap_remove(pos);
+                }
+                norm = &norm * &conj;
+            }
+
+            factors.push(self.from_galois_field_extension(&norm, &root));
+        }
+
+        let lcoeff = self.lcoeff();
+        if !self.field.is_one(&lcoeff) {
+            factors[0] = factors[0].clone().mul_coeff(lcoeff);
+        }
+
+        factors
+    }
+
     /// Reconstruct the leading coefficient using a Pade approximation with numerator degree `deg_n` and
     /// denominator degree `deg_d`. The resulting denominator should be a factor of the leading coefficient.
     fn lcoeff_reconstruct(coeffs: &[Self], deg_n: u32, deg_d: u32) -> Self {
@@ -902,8 +1115,8 @@ where
     fn canonical_sort(
         biv_polys: &[Self],
         replace_var: usize,
-        sample_points: &[(usize, <FiniteField<UField> as Ring>::Element)],
-    ) -> Vec<(Self, <FiniteField<UField> as Ring>::Element, Self)> {
+        sample_points: &[(usize, F::Element)],
+    ) -> Vec<(Self, F::Element, Self)> {
         let mut univariate_factors = biv_polys
             .iter()
             .map(|f| {
@@ -931,7 +1144,7 @@ where
     fn lcoeff_precomputation(
         &self,
         bivariate_factors: &[Self],
-        sample_points: &[(usize, <FiniteField<UField> as Ring>::Element)],
+        sample_points: &[(usize, F::Element)],
         order: &[usize],
     ) -> Result<(Vec<Self>, Vec<Self>), usize> {
         let lcoeff = self.univariate_lcoeff(order[0]);
@@ -1098,7 +1311,7 @@ where
     fn multivariate_hensel_lift_with_auto_lcoeff_fixing(
         &self,
         factors: &[Self],
-        sample_points: &[(usize, <FiniteField<UField> as Ring>::Element)],
+        sample_points: &[(usize, F::Element)],
         order: &[usize],
     ) -> Vec<Self> {
         let lcoeff = self.univariate_lcoeff(order[0]);
@@ -1162,7 +1375,7 @@ where
     fn univariate_diophantine_field(
         factors: &[Self],
         order: &[usize],
-        sample_points: &[(usize, <FiniteField<UField> as Ring>::Element)],
+        sample_points: &[(usize, F::Element)],
     ) -> (Vec<Self>, Vec<Self>) {
         // produce univariate factors and univariate delta
         let mut univariate_factors = factors.to_vec();
@@ -1230,10 +1443,24 @@ where
 
         let uni_lcoeff = self.univariate_lcoeff(order[0]);
 
+        // the number of failed samples after which the field is deemed too small
+        let max_samples = self
+            .field
+            .size()
+            .to_i64()
+            .map(|q| (q as u64).saturating_mul(2))
+            .unwrap_or(u64::MAX);
+
         let mut content_fail_count = 0;
         'new_sample: loop {
+            if coefficient_upper_bound > max_samples {
+                return self.factor_in_extension();
+            }
+
             for s in &mut sample_points {
-                s.1 = self.field.nth(rng.gen_range(0..=coefficient_upper_bound));
+                s.1 = self
+                    .field
+                    .nth_element(rng.gen_range(0..=coefficient_upper_bound));
             }
 
             biv_f = self.clone();
@@ -1293,7 +1520,7 @@ where
         }
 
         for (v, s) in &sample_points {
-            debug!("Sample point {}={}", v, self.field.from_element(s));
+            debug!("Sample point {}={}", v, self.field.printer(s));
         }
 
         let bivariate_factors = biv_f.bivariate_factorization(order[0], order[1]);
@@ -3099,7 +3326,9 @@ impl<E: Exponent> MultivariatePolynomial<FiniteField<Integer>, E, LexOrder> {
 mod test {
     use crate::{
         atom::Atom,
-        domains::{finite_field::Zp, integer::Z},
+        domains::{
+            algebraic_number::AlgebraicNumberRing, finite_field::Zp, integer::Z, rational::Q,
+        },
         poly::factor::Factorize,
     };
 
@@ -3325,4 +3554,63 @@ mod test {
         r.sort_by(|a, b| a.partial_cmp(&b).unwrap());
         assert_eq!(r, res);
     }
+
+    #[test]
+    fn factor_number_field() {
+        let ring = Atom::parse("a^2-2")
+            .unwrap()
+            .to_polynomial::<_, u8>(&Q, None);
+        let ring = AlgebraicNumberRing::new(ring);
+
+        let poly = Atom::parse("x^2-2")
+            .unwrap()
+            .to_polynomial::<_, u8>(&Q, None)
+            .to_number_field(&ring);
+
+        let mut r: Vec<_> = poly
+            .factor()
+            .into_iter()
+            .map(|(f, p)| (f.from_number_field(), p))
+            .collect();
+        r.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+        let vars = r[0].0.variables.clone();
+        let mut res: Vec<_> = ["x-a", "x+a"]
+            .iter()
+            .map(|f| {
+                (
+                    Atom::parse(f)
+                        .unwrap()
+                        .to_polynomial(&Q, vars.clone().into()),
+                    1,
+                )
+            })
+            .collect();
+        res.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+        assert_eq!(r, res);
+    }
+
+    #[test]
+    fn factor_number_field_multivariate() {
+        let ring = Atom::parse("a^3-2")
+            .unwrap()
+            .to_polynomial::<_, u8>(&Q, None);
+        let ring = AlgebraicNumberRing::new(ring);
+
+        let poly = Atom::parse("(x^3-2*y^3)*(x+y+a)^2*(x^2+a*y+1)")
+            .unwrap()
+            .expand()
+            .to_polynomial::<_, u8>(&Q, None)
+            .to_number_field(&ring);
+
+        let factors = poly.factor();
+        assert_eq!(factors.iter().filter(|(f, _)| !f.is_constant()).count(), 4);
+
+        let mut r = poly.one();
+        for (f, p) in &factors {
+            r = r * &f.pow(*p);
+        }
+        assert_eq!(r, poly);
+    }
 }
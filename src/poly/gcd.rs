@@ -9,8 +9,9 @@ use tracing::{debug, instrument};
 
 use crate::domains::algebraic_number::AlgebraicNumberRing;
 use crate::domains::finite_field::{
-    FiniteField, FiniteFieldCore, FiniteFieldWorkspace, ToFiniteField, Zp,
+    FiniteField, FiniteFieldCore, FiniteFieldLike, FiniteFieldWorkspace, ToFiniteField, Zp,
 };
+use crate::domains::galois_field::GaloisField;
 use crate::domains::integer::{FromFiniteField, Integer, IntegerRing, SMALL_PRIMES, Z};
 use crate::domains::rational::{Rational, RationalField, Q};
 use crate::domains::{EuclideanDomain, Field, Ring};
@@ -1016,7 +1017,8 @@ impl<F: Field, E: Exponent> MultivariatePolynomial<F, E> {
                             MatrixError::NotSquare
                             | MatrixError::ShapeMismatch
                             | MatrixError::RightHandSideIsNotVector
-                            | MatrixError::Singular,
+                            | MatrixError::Singular
+                            | MatrixError::DegreeTooLarge,
                         ) => {
                             unreachable!()
                         }
@@ -1328,6 +1330,75 @@ impl<F: Field + PolynomialGCD<E>, E: Exponent> MultivariatePolynomial<F, E> {
     }
 }
 
+impl<F: FiniteFieldLike + PolynomialGCD<E>, E: Exponent> MultivariatePolynomial<F, E> {
+    /// Compute the gcd of two polynomials over a finite field. If the field is too small
+    /// to contain enough sample points, the gcd is computed in an extension field.
+    fn gcd_finite_field(
+        a: &Self,
+        b: &Self,
+        vars: &[usize],
+        bounds: &mut [E],
+        tight_bounds: &mut [E],
+    ) -> Self {
+        assert!(!a.is_zero() || !b.is_zero());
+
+        if let Some(size) = Self::gcd_extension_size(a, b, vars) {
+            let (ext, root) = a.field.field_extension(size);
+            let g = MultivariatePolynomial::gcd_shape_modular(
+                &a.to_galois_field_extension(&ext, &root),
+                &b.to_galois_field_extension(&ext, &root),
+                vars,
+                bounds,
+                tight_bounds,
+            )
+            .unwrap();
+            return a.from_galois_field_extension(&g, &root);
+        }
+
+        MultivariatePolynomial::gcd_shape_modular(a, b, vars, bounds, tight_bounds).unwrap()
+    }
+
+    /// Compute tight degree bounds for the gcd of two polynomials over a finite field.
+    /// If the field is too small to contain enough sample points, the loose bounds are returned.
+    fn get_gcd_var_bounds_finite_field(
+        a: &Self,
+        b: &Self,
+        vars: &[usize],
+        loose_bounds: &[E],
+    ) -> SmallVec<[E; INLINED_EXPONENTS]> {
+        let mut tight_bounds: SmallVec<[_; INLINED_EXPONENTS]> = loose_bounds.into();
+        if Self::gcd_extension_size(a, b, vars).is_some() {
+            return tight_bounds;
+        }
+
+        for var in vars {
+            let vvars: SmallVec<[usize; INLINED_EXPONENTS]> =
+                vars.iter().filter(|i| *i != var).cloned().collect();
+            tight_bounds[*var] = MultivariatePolynomial::get_gcd_var_bound(a, b, &vvars, *var);
+        }
+        tight_bounds
+    }
+
+    /// Get the size of the extension field in which the gcd of `a` and `b` should be computed,
+    /// if the field is too small to contain enough sample points.
+    fn gcd_extension_size(a: &Self, b: &Self, vars: &[usize]) -> Option<u64> {
+        if vars.len() < 2 {
+            return None;
+        }
+
+        let d: u64 = vars
+            .iter()
+            .map(|v| max(a.degree(*v), b.degree(*v)).to_u32() as u64)
+            .sum();
+
+        if a.field.size() <= Integer::from(2 * d + 2) {
+            Some(100 * (d + 1))
+        } else {
+            None
+        }
+    }
+}
+
 impl<R: EuclideanDomain + PolynomialGCD<E>, E: Exponent> MultivariatePolynomial<R, E> {
     /// Get the content of a multivariate polynomial viewed as a
     /// univariate polynomial in `x`.
@@ -2578,8 +2649,7 @@ where
         bounds: &mut [E],
         tight_bounds: &mut [E],
     ) -> MultivariatePolynomial<Self, E> {
-        assert!(!a.is_zero() || !b.is_zero());
-        MultivariatePolynomial::gcd_shape_modular(a, b, vars, bounds, tight_bounds).unwrap()
+        MultivariatePolynomial::gcd_finite_field(a, b, vars, bounds, tight_bounds)
     }
 
     fn get_gcd_var_bounds(
@@ -2588,13 +2658,47 @@ where
         vars: &[usize],
         loose_bounds: &[E],
     ) -> SmallVec<[E; INLINED_EXPONENTS]> {
-        let mut tight_bounds: SmallVec<[_; INLINED_EXPONENTS]> = loose_bounds.into();
-        for var in vars {
-            let vvars: SmallVec<[usize; INLINED_EXPONENTS]> =
-                vars.iter().filter(|i| *i != var).cloned().collect();
-            tight_bounds[*var] = MultivariatePolynomial::get_gcd_var_bound(a, b, &vvars, *var);
-        }
-        tight_bounds
+        MultivariatePolynomial::get_gcd_var_bounds_finite_field(a, b, vars, loose_bounds)
+    }
+
+    fn gcd_multiple(f: Vec<MultivariatePolynomial<Self, E>>) -> MultivariatePolynomial<Self, E> {
+        MultivariatePolynomial::repeated_gcd(f)
+    }
+
+    fn normalize(a: MultivariatePolynomial<Self, E>) -> MultivariatePolynomial<Self, E> {
+        a.make_monic()
+    }
+}
+
+impl<E: Exponent> PolynomialGCD<E> for GaloisField {
+    fn heuristic_gcd(
+        _a: &MultivariatePolynomial<Self, E>,
+        _b: &MultivariatePolynomial<Self, E>,
+    ) -> Option<(
+        MultivariatePolynomial<Self, E>,
+        MultivariatePolynomial<Self, E>,
+        MultivariatePolynomial<Self, E>,
+    )> {
+        None
+    }
+
+    fn gcd(
+        a: &MultivariatePolynomial<Self, E>,
+        b: &MultivariatePolynomial<Self, E>,
+        vars: &[usize],
+        bounds: &mut [E],
+        tight_bounds: &mut [E],
+    ) -> MultivariatePolynomial<Self, E> {
+        MultivariatePolynomial::gcd_finite_field(a, b, vars, bounds, tight_bounds)
+    }
+
+    fn get_gcd_var_bounds(
+        a: &MultivariatePolynomial<Self, E>,
+        b: &MultivariatePolynomial<Self, E>,
+        vars: &[usize],
+        loose_bounds: &[E],
+    ) -> SmallVec<[E; INLINED_EXPONENTS]> {
+        MultivariatePolynomial::get_gcd_var_bounds_finite_field(a, b, vars, loose_bounds)
     }
 
     fn gcd_multiple(f: Vec<MultivariatePolynomial<Self, E>>) -> MultivariatePolynomial<Self, E> {
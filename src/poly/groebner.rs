@@ -1,14 +1,19 @@
-use std::{cmp::Ordering, rc::Rc};
+use std::{cmp::Ordering, marker::PhantomData, rc::Rc, sync::Arc};
 
-use ahash::HashMap;
+use ahash::{HashMap, HashSet};
+use rayon::prelude::*;
 
 use crate::domains::{
-    finite_field::{FiniteField, FiniteFieldCore, Mersenne64, Zp, Zp64},
-    rational::RationalField,
+    finite_field::{FiniteField, FiniteFieldCore, Mersenne64, ToFiniteField, Zp, Zp64},
+    integer::{FromFiniteField, Integer, IntegerRing, Z},
+    rational::{Rational, RationalField, Q},
     Field, Ring,
 };
 
-use super::{polynomial::MultivariatePolynomial, Exponent, MonomialOrder};
+use super::{
+    gcd::LARGE_U64_PRIMES, polynomial::MultivariatePolynomial, BlockOrder, Exponent, MonomialOrder,
+    Variable,
+};
 
 #[derive(Debug)]
 pub struct CriticalPair<R: Field, E: Exponent, O: MonomialOrder> {
@@ -69,6 +74,7 @@ pub struct MonomialData {
     column: usize,
 }
 
+#[derive(Clone)]
 pub struct GroebnerBasis<R: Field, E: Exponent, O: MonomialOrder> {
     pub system: Vec<MultivariatePolynomial<R, E, O>>,
     pub print_stats: bool,
@@ -139,7 +145,9 @@ impl<R: Field + Echelonize, E: Exponent, O: MonomialOrder> GroebnerBasis<R, E, O
         let mut sorted_monomial_indices = vec![];
         let mut exp = vec![E::zero(); nvars];
         let mut new_polys = vec![];
+        let mut new_origins = vec![];
         let mut selected_polys = vec![];
+        let mut selected_origins = vec![];
 
         let mut buffer = vec![];
         let mut pivots: Vec<Option<usize>> = vec![];
@@ -161,9 +169,9 @@ impl<R: Field + Echelonize, E: Exponent, O: MonomialOrder> GroebnerBasis<R, E, O
             iter_count += 1;
 
             selected_polys.clear();
+            selected_origins.clear();
             let mut i = critical_pairs.len() - 1;
 
-            let mut l_tmp = vec![];
             loop {
                 if critical_pairs[i].degree == lowest_lcm_deg {
                     let pair = critical_pairs.swap_remove(i);
@@ -173,11 +181,11 @@ impl<R: Field + Echelonize, E: Exponent, O: MonomialOrder> GroebnerBasis<R, E, O
                         (pair.index_sec, pair.lcm_diff_sec),
                     ];
                     for poly_info in e {
-                        if !l_tmp.contains(&poly_info) {
+                        if !selected_origins.contains(&poly_info) {
                             let new_f1 =
                                 Self::simplify(&mut simplifications[poly_info.0], &poly_info.1);
                             selected_polys.push(new_f1);
-                            l_tmp.push(poly_info);
+                            selected_origins.push(poly_info);
                         }
                     }
                 }
@@ -211,6 +219,7 @@ impl<R: Field + Echelonize, E: Exponent, O: MonomialOrder> GroebnerBasis<R, E, O
             }
 
             new_polys.clear();
+            new_origins.clear();
             let mut i = 0;
             while i < selected_polys.len() {
                 for monom in selected_polys[i].exponents_iter() {
@@ -241,12 +250,14 @@ impl<R: Field + Echelonize, E: Exponent, O: MonomialOrder> GroebnerBasis<R, E, O
 
                         let pp = Self::simplify(&mut simplifications[*index], &exp);
                         new_polys.push(pp);
+                        new_origins.push((*index, exp.clone()));
                     }
                 }
 
                 i += 1;
 
                 selected_polys.append(&mut new_polys);
+                selected_origins.append(&mut new_origins);
             }
 
             // construct a matrix that is sparse in the columns
@@ -327,33 +338,22 @@ impl<R: Field + Echelonize, E: Exponent, O: MonomialOrder> GroebnerBasis<R, E, O
 
                     Self::update(&mut basis, &mut critical_pairs, poly, new_index);
                 } else {
-                    // update entries in the tab with simpler polynomials
-                    let mut diff = vec![E::zero(); nvars];
-                    'bf: for (g_ind, g) in &basis {
-                        if poly
-                            .last_exponents()
-                            .iter()
-                            .zip(g.last_exponents())
-                            .all(|(pi, gi)| *pi >= *gi)
-                        {
-                            for ((d, pi), gi) in diff
-                                .iter_mut()
-                                .zip(poly.last_exponents())
-                                .zip(g.last_exponents())
-                            {
-                                *d = *pi - *gi;
-                            }
+                    // update entries in the tab with simpler polynomials, only for
+                    // the basis polynomials from which a row with the same head was constructed
+                    'bf: for (p, (g_ind, diff)) in selected_polys.iter().zip(&selected_origins) {
+                        if p.max_exp() != lm {
+                            continue;
+                        }
 
-                            for (diff_e, p) in &mut simplifications[*g_ind] {
-                                if diff == *diff_e {
-                                    *p = poly.clone();
-                                    continue 'bf;
-                                }
+                        for (diff_e, p) in &mut simplifications[*g_ind] {
+                            if diff == diff_e {
+                                *p = poly.clone();
+                                continue 'bf;
                             }
-
-                            // new polynomial
-                            simplifications[*g_ind].push((diff.clone(), poly.clone()));
                         }
+
+                        // new polynomial
+                        simplifications[*g_ind].push((diff.clone(), poly.clone()));
                     }
                 }
             }
@@ -559,6 +559,473 @@ impl<R: Field, E: Exponent, O: MonomialOrder> GroebnerBasis<R, E, O> {
         }
         true
     }
+
+    /// Check if the ideal is zero-dimensional, i.e., if the polynomial system has finitely
+    /// many solutions. This is the case when for every variable, a pure power of the variable is
+    /// the leading monomial of an element of the basis.
+    pub fn is_zero_dimensional(&self) -> bool {
+        let Some(first) = self.system.first() else {
+            return false;
+        };
+
+        if self.system.iter().any(|p| p.is_constant()) {
+            return true;
+        }
+
+        (0..first.nvars()).all(|v| {
+            self.system.iter().any(|p| {
+                p.max_exp()
+                    .iter()
+                    .enumerate()
+                    .all(|(i, e)| if i == v { !e.is_zero() } else { e.is_zero() })
+            })
+        })
+    }
+
+    /// Compute the monic polynomial of lowest degree in the variable `var` that is an
+    /// element of the zero-dimensional ideal, by finding a linear dependence between
+    /// the normal forms of the powers of `var`.
+    ///
+    /// Returns `None` if the ideal is not zero-dimensional.
+    pub fn univariate_polynomial(&self, var: usize) -> Option<MultivariatePolynomial<R, E, O>> {
+        if !self.is_zero_dimensional() {
+            return None;
+        }
+
+        let first = &self.system[0];
+        let mut unit = vec![E::zero(); first.nvars()];
+        unit[var] = E::one();
+
+        let mut echelon = NormalFormEchelon { rows: vec![] };
+        let mut monomial = first.one();
+        let mut nf = Self::reduce(&monomial, &self.system);
+        loop {
+            if let Some(p) = echelon.reduce_or_insert(nf.clone(), monomial.clone()) {
+                return Some(p);
+            }
+
+            monomial = monomial.mul_exp(&unit);
+            nf = Self::reduce(&nf.mul_exp(&unit), &self.system);
+        }
+    }
+
+    /// Convert the Groebner basis of a zero-dimensional ideal to the reduced Groebner basis
+    /// in the monomial order `O2`, using linear algebra on the normal forms of the monomials.
+    ///
+    /// Returns `None` if the ideal is not zero-dimensional.
+    ///
+    /// Adapted from [Efficient computation of zero-dimensional Gröbner bases by change of ordering](https://doi.org/10.1006/jsco.1993.1051)
+    /// by Faugère, Gianni, Lazard and Mora.
+    pub fn fglm<O2: MonomialOrder>(&self) -> Option<GroebnerBasis<R, E, O2>> {
+        if !self.is_zero_dimensional() {
+            return None;
+        }
+
+        let first = &self.system[0];
+        let nvars = first.nvars();
+        let target: MultivariatePolynomial<R, E, O2> =
+            MultivariatePolynomial::new(&first.field, None, first.variables.clone());
+
+        let mut echelon = NormalFormEchelon { rows: vec![] };
+        let mut basis: Vec<MultivariatePolynomial<R, E, O2>> = vec![];
+        let mut processed: HashSet<Vec<E>> = HashSet::default();
+
+        // the monomials that are a multiple of a variable and a monomial of the staircase,
+        // together with their normal form
+        let mut candidates = vec![(
+            vec![E::zero(); nvars],
+            Self::reduce(&first.one(), &self.system),
+        )];
+
+        while let Some(i) = (0..candidates.len())
+            .min_by(|a, b| O2::cmp(candidates[*a].0.as_slice(), candidates[*b].0.as_slice()))
+        {
+            let (m, nf) = candidates.swap_remove(i);
+
+            if !processed.insert(m.clone())
+                || basis
+                    .iter()
+                    .any(|g| m.iter().zip(g.max_exp()).all(|(e1, e2)| *e1 >= *e2))
+            {
+                continue;
+            }
+
+            let mut monomial = target.zero();
+            monomial.append_monomial(first.field.one(), &m);
+
+            if let Some(g) = echelon.reduce_or_insert(nf.clone(), monomial) {
+                basis.push(g);
+                continue;
+            }
+
+            // the monomial is part of the staircase
+            let mut unit = vec![E::zero(); nvars];
+            for v in 0..nvars {
+                let mut next = m.clone();
+                next[v] += E::one();
+
+                if candidates.iter().all(|(c, _)| *c != next) {
+                    unit[v] = E::one();
+                    candidates.push((next, Self::reduce(&nf.clone().mul_exp(&unit), &self.system)));
+                    unit[v] = E::zero();
+                }
+            }
+        }
+
+        Some(
+            GroebnerBasis {
+                system: basis,
+                print_stats: self.print_stats,
+            }
+            .reduce_basis(),
+        )
+    }
+}
+
+/// A row of a [NormalFormEchelon]: a normal form in the order `O` and
+/// the polynomial in the order `O2` that it is the normal form of.
+type NormalFormRow<R, E, O, O2> = (
+    MultivariatePolynomial<R, E, O>,
+    MultivariatePolynomial<R, E, O2>,
+);
+
+/// Normal forms modulo a Groebner basis in row echelon form, where every row
+/// also stores the polynomial in the order `O2` that it is the normal form of.
+struct NormalFormEchelon<R: Field, E: Exponent, O: MonomialOrder, O2: MonomialOrder> {
+    rows: Vec<NormalFormRow<R, E, O, O2>>,
+}
+
+impl<R: Field, E: Exponent, O: MonomialOrder, O2: MonomialOrder> NormalFormEchelon<R, E, O, O2> {
+    /// Reduce the normal form `nf` of the polynomial `p` using the rows, which are sorted
+    /// by descending leading monomial. If the normal form vanishes, the reduced `p`
+    /// is an element of the ideal and it is returned. Otherwise, a new row is added.
+    fn reduce_or_insert(
+        &mut self,
+        mut nf: MultivariatePolynomial<R, E, O>,
+        mut p: MultivariatePolynomial<R, E, O2>,
+    ) -> Option<MultivariatePolynomial<R, E, O2>> {
+        for (row_nf, row_p) in &self.rows {
+            let lm = row_nf.max_exp();
+            let Some(i) = nf.exponents_iter().position(|e| e == lm) else {
+                continue;
+            };
+            let c = nf.coefficients[i].clone();
+
+            nf = nf - row_nf.clone().mul_coeff(c.clone());
+            p = p - row_p.clone().mul_coeff(c);
+        }
+
+        if nf.is_zero() {
+            return Some(p);
+        }
+
+        let inv = nf.field.inv(nf.max_coeff());
+        let nf = nf.mul_coeff(inv.clone());
+        let p = p.mul_coeff(inv);
+
+        let pos = self
+            .rows
+            .iter()
+            .position(|(r, _)| O::cmp(r.max_exp(), nf.max_exp()) == Ordering::Less)
+            .unwrap_or(self.rows.len());
+        self.rows.insert(pos, (nf, p));
+        None
+    }
+}
+
+impl<R: Field, E: Exponent, const N: usize, O1: MonomialOrder, O2: MonomialOrder>
+    GroebnerBasis<R, E, BlockOrder<N, O1, O2>>
+{
+    /// Get the elements of the basis that do not depend on the first `N` variables.
+    /// They form a Groebner basis of the elimination ideal.
+    pub fn elimination_ideal(&self) -> Vec<MultivariatePolynomial<R, E, BlockOrder<N, O1, O2>>> {
+        self.system
+            .iter()
+            .filter(|p| {
+                p.exponents
+                    .chunks(p.nvars())
+                    .all(|e| e.iter().take(N).all(|x| x.is_zero()))
+            })
+            .cloned()
+            .collect()
+    }
+}
+
+/// The order of an ideal that is homogenized with a new last variable:
+/// monomials are compared by their total degree and then by `O` on the other variables.
+/// The dehomogenization of a Groebner basis with respect to this order is a Groebner basis
+/// with respect to `O`.
+#[derive(Clone)]
+struct HomogenizedOrder<O: MonomialOrder> {
+    _phantom: PhantomData<O>,
+}
+
+impl<O: MonomialOrder> MonomialOrder for HomogenizedOrder<O> {
+    #[inline]
+    fn cmp<E: Exponent>(a: &[E], b: &[E]) -> Ordering {
+        let deg: E = a.iter().cloned().sum();
+        let deg2: E = b.iter().cloned().sum();
+
+        match deg.cmp(&deg2) {
+            Ordering::Equal => {
+                let n = a.len().saturating_sub(1);
+                O::cmp(&a[..n], &b[..n])
+            }
+            x => x,
+        }
+    }
+}
+
+/// The images of a Groebner basis modulo several primes that share the same
+/// leading monomials, combined using the Chinese remainder theorem.
+struct ModularImage<E: Exponent, O: MonomialOrder> {
+    leading_monomials: Vec<Vec<E>>,
+    basis: Vec<MultivariatePolynomial<IntegerRing, E, O>>,
+    modulus: Integer,
+    primes: usize,
+    last_guess: Option<Vec<MultivariatePolynomial<RationalField, E, O>>>,
+}
+
+impl<E: Exponent, O: MonomialOrder> ModularImage<E, O> {
+    /// Merge the basis `image` modulo the prime of `field` using Garner's algorithm.
+    fn merge(&mut self, field: &Zp64, image: Vec<MultivariatePolynomial<Zp64, E, O>>) {
+        let m_inv = field.inv(&self.modulus.to_finite_field(field));
+        for (gm, r) in self.basis.iter_mut().zip(image) {
+            let diff = r - gm.map_coeff(|c| c.to_finite_field(field), field.clone());
+            if diff.is_zero() {
+                continue;
+            }
+
+            let t = diff
+                .mul_coeff(m_inv)
+                .map_coeff(|c| field.to_symmetric_integer(c), Z);
+            *gm = &*gm + &t.mul_coeff(self.modulus.clone());
+        }
+
+        self.modulus = &self.modulus * &Integer::from_prime(field);
+        self.primes += 1;
+    }
+
+    /// Reconstruct the rational coefficients of the basis, if possible.
+    fn reconstruct(&self) -> Option<Vec<MultivariatePolynomial<RationalField, E, O>>> {
+        let mut basis = Vec::with_capacity(self.basis.len());
+        for g in &self.basis {
+            let mut coefficients = Vec::with_capacity(g.nterms());
+            for c in &g.coefficients {
+                coefficients
+                    .push(Rational::maximal_quotient_reconstruction(c, &self.modulus, None).ok()?);
+            }
+
+            let mut p = MultivariatePolynomial::new(&Q, None, g.variables.clone());
+            p.coefficients = coefficients;
+            p.exponents = g.exponents.clone();
+            basis.push(p);
+        }
+
+        Some(basis)
+    }
+}
+
+impl<E: Exponent + Send + Sync, O: MonomialOrder + Send + Sync> GroebnerBasis<RationalField, E, O> {
+    /// Construct a Groebner basis for a polynomial ideal over the rationals using
+    /// a multi-modular approach, which avoids the coefficient growth of F4 over Q.
+    ///
+    /// Reduced Groebner bases are computed in parallel modulo several 64-bit primes.
+    /// Primes that divide a leading coefficient of the input are skipped and
+    /// images with different leading monomials are separated, where the
+    /// majority is assumed to be lucky. The images are combined using the Chinese
+    /// remainder theorem, after which the coefficients are rationally reconstructed.
+    /// Once the reconstruction is stable, the result is verified to be a Groebner basis
+    /// that contains the input ideal. For homogeneous ideals, this implies that the
+    /// ideals are equal, since the reconstructed basis has the same leading monomials as the
+    /// modular images and the dimension of every homogeneous component can only decrease
+    /// modulo a prime.
+    ///
+    /// Ideals that are not homogeneous are homogenized with a new variable, using an order
+    /// that compares the total degree first and then the monomials in the original variables.
+    /// The basis of the homogenized ideal is dehomogenized and inter-reduced.
+    /// Ideals whose coefficients are too large for the available primes
+    /// are computed using [GroebnerBasis::new].
+    ///
+    /// Adapted from [Modular algorithms for computing Gröbner bases](https://doi.org/10.1016/S0747-7171(02)00140-2) by Elizabeth A. Arnold.
+    pub fn new_modular(
+        ideal: &[MultivariatePolynomial<RationalField, E, O>],
+        print_stats: bool,
+    ) -> GroebnerBasis<RationalField, E, O> {
+        let mut ideal = ideal.to_vec();
+        MultivariatePolynomial::unify_variables_list(&mut ideal);
+        ideal.retain(|p| !p.is_zero());
+
+        if ideal.is_empty() {
+            return GroebnerBasis {
+                system: ideal,
+                print_stats,
+            };
+        }
+
+        if ideal.iter().all(|p| p.is_homogeneous()) {
+            return Self::new_modular_homogeneous(&ideal, print_stats);
+        }
+
+        let n = ideal[0].nvars();
+        let mut vars = (*ideal[0].variables).clone();
+        let h = (0..)
+            .map(Variable::Temporary)
+            .find(|v| !vars.contains(v))
+            .unwrap();
+        vars.push(h);
+        let vars = Arc::new(vars);
+
+        let mut homogenized: Vec<MultivariatePolynomial<_, E, HomogenizedOrder<O>>> =
+            Vec::with_capacity(ideal.len());
+        let mut exp = vec![E::zero(); n + 1];
+        for p in &ideal {
+            let degree = |e: &[E]| e.iter().map(|x| x.to_u32()).sum::<u32>();
+            let d = p.exponents_iter().map(degree).max().unwrap_or(0);
+
+            let mut hp = MultivariatePolynomial::new(&Q, Some(p.nterms()), vars.clone());
+            for t in p {
+                exp[..n].copy_from_slice(t.exponents);
+                exp[n] = E::from_u32(d - degree(t.exponents));
+                hp.append_monomial(t.coefficient.clone(), &exp);
+            }
+            homogenized.push(hp);
+        }
+
+        let gb = GroebnerBasis::new_modular_homogeneous(&homogenized, print_stats);
+
+        let mut system = Vec::with_capacity(gb.system.len());
+        for g in &gb.system {
+            let mut p =
+                MultivariatePolynomial::new(&Q, Some(g.nterms()), ideal[0].variables.clone());
+            for t in g {
+                p.append_monomial(t.coefficient.clone(), &t.exponents[..n]);
+            }
+            system.push(p);
+        }
+
+        GroebnerBasis {
+            system,
+            print_stats,
+        }
+        .reduce_basis()
+    }
+
+    /// Construct a Groebner basis for a homogeneous ideal over the rationals
+    /// using the multi-modular approach of [GroebnerBasis::new_modular].
+    fn new_modular_homogeneous(
+        ideal: &[MultivariatePolynomial<RationalField, E, O>],
+        print_stats: bool,
+    ) -> GroebnerBasis<RationalField, E, O> {
+        // clear the denominators, which does not change the ideal
+        let integer_ideal: Vec<_> = ideal
+            .iter()
+            .map(|p| {
+                let mut lcm = Integer::one();
+                for c in &p.coefficients {
+                    lcm = lcm.lcm(&c.denominator());
+                }
+
+                p.map_coeff(|c| &c.numerator() * &(&lcm / &c.denominator()), Z)
+            })
+            .collect();
+
+        let mut images: Vec<ModularImage<E, O>> = vec![];
+        let batch_size = rayon::current_num_threads().max(1);
+
+        for primes in LARGE_U64_PRIMES.chunks(batch_size) {
+            let batch: Vec<_> = primes
+                .par_iter()
+                .filter_map(|p| {
+                    let field = Zp64::new(*p);
+
+                    let ideal_p: Vec<_> = integer_ideal
+                        .iter()
+                        .map(|f| f.map_coeff(|c| c.to_finite_field(&field), field.clone()))
+                        .collect();
+
+                    // skip primes that divide a leading coefficient
+                    if ideal_p
+                        .iter()
+                        .zip(&integer_ideal)
+                        .any(|(fp, f)| fp.is_zero() || fp.max_exp() != f.max_exp())
+                    {
+                        return None;
+                    }
+
+                    Some((field, GroebnerBasis::new(&ideal_p, false).system))
+                })
+                .collect();
+
+            for (field, image) in batch {
+                let leading_monomials: Vec<_> =
+                    image.iter().map(|p| p.max_exp().to_vec()).collect();
+
+                let Some(index) = images
+                    .iter()
+                    .position(|i| i.leading_monomials == leading_monomials)
+                else {
+                    images.push(ModularImage {
+                        leading_monomials,
+                        basis: image
+                            .iter()
+                            .map(|p| p.map_coeff(|c| field.to_symmetric_integer(c), Z))
+                            .collect(),
+                        modulus: Integer::from_prime(&field),
+                        primes: 1,
+                        last_guess: None,
+                    });
+                    continue;
+                };
+
+                images[index].merge(&field, image);
+
+                // only attempt the reconstruction for the majority of the primes
+                if images.iter().any(|i| i.primes > images[index].primes) {
+                    continue;
+                }
+
+                let im = &mut images[index];
+                let Some(guess) = im.reconstruct() else {
+                    continue;
+                };
+
+                if print_stats {
+                    println!(
+                        "Reconstructed basis with {} polynomials using {} primes",
+                        guess.len(),
+                        im.primes
+                    );
+                }
+
+                if im.last_guess.as_ref() != Some(&guess) {
+                    im.last_guess = Some(guess);
+                    continue;
+                }
+
+                if ideal.iter().all(|p| Self::reduce(p, &guess).is_zero())
+                    && Self::is_groebner_basis(&guess)
+                {
+                    return GroebnerBasis {
+                        system: guess,
+                        print_stats,
+                    };
+                }
+
+                if print_stats {
+                    println!("Verification failed, adding more primes");
+                }
+
+                im.last_guess = None;
+            }
+        }
+
+        if print_stats {
+            println!("Ran out of primes, computing the basis over the rationals");
+        }
+
+        GroebnerBasis::new(ideal, print_stats)
+    }
 }
 
 /// Echelonize a matrix with entries in the field.
@@ -844,7 +1311,7 @@ macro_rules! echelonize_impl {
                             pivots[*col] = Some(r);
                             pc += 1;
 
-                            if field.is_one(coeff) {
+                            if !field.is_one(coeff) {
                                 let inv_pivot = field.inv(coeff);
 
                                 for (coeff, _) in &mut matrix[r] {
@@ -928,10 +1395,19 @@ echelonize_impl!(RationalField);
 
 #[cfg(test)]
 mod test {
+    use std::sync::Arc;
+
     use crate::{
         atom::Atom,
-        domains::finite_field::Zp,
-        poly::{groebner::GroebnerBasis, polynomial::MultivariatePolynomial, GrevLexOrder},
+        domains::{
+            finite_field::{FiniteFieldCore, Zp, Zp64},
+            rational::Q,
+        },
+        poly::{
+            groebner::GroebnerBasis, polynomial::MultivariatePolynomial, BlockOrder, GrevLexOrder,
+            LexOrder, MonomialOrder, MonomialWeights, Variable, WeightedOrder,
+        },
+        state::State,
     };
 
     #[test]
@@ -998,4 +1474,204 @@ mod test {
 
         assert_eq!(gb.system, res);
     }
+
+    #[test]
+    fn elimination() {
+        // eliminate t from x = t^2, y = t^3
+        let vars: Arc<Vec<Variable>> = Arc::new(
+            ["t", "x", "y"]
+                .iter()
+                .map(|v| State::get_symbol(v).into())
+                .collect(),
+        );
+
+        let ideal: Vec<MultivariatePolynomial<_, u16, BlockOrder<1>>> = ["x-t^2", "y-t^3"]
+            .iter()
+            .map(|x| {
+                Atom::parse(x)
+                    .unwrap()
+                    .to_polynomial(&Zp::new(101), Some(vars.clone()))
+                    .reorder()
+            })
+            .collect();
+
+        let gb = GroebnerBasis::new(&ideal, false);
+        assert!(GroebnerBasis::is_groebner_basis(&gb.system));
+
+        let res = Atom::parse("x^3-y^2")
+            .unwrap()
+            .to_polynomial(&Zp::new(101), Some(vars.clone()))
+            .reorder();
+        assert_eq!(gb.elimination_ideal(), vec![res]);
+    }
+
+    #[test]
+    fn weighted_order() {
+        #[derive(Clone)]
+        struct Weights;
+
+        impl MonomialWeights for Weights {
+            fn weights() -> &'static [u32] {
+                &[3, 2]
+            }
+        }
+
+        type Order = WeightedOrder<Weights>;
+        assert!(Order::cmp(&[1u16, 0, 0], &[0, 1, 0]).is_gt());
+        assert!(Order::cmp(&[2u16, 0, 0], &[0, 3, 0]).is_lt());
+        assert!(Order::cmp(&[0u16, 0, 2], &[1, 0, 0]).is_lt());
+
+        let ideal: Vec<MultivariatePolynomial<_, u16, Order>> =
+            ["v1^2*v2-v3", "v1*v2^2+v3^2-1", "v2^3-v1"]
+                .iter()
+                .map(|x| {
+                    Atom::parse(x)
+                        .unwrap()
+                        .to_polynomial::<_, u16>(&Zp::new(13), None)
+                        .reorder()
+                })
+                .collect();
+
+        let gb = GroebnerBasis::new(&ideal, false);
+        assert!(GroebnerBasis::is_groebner_basis(&gb.system));
+
+        for mut p in ideal {
+            gb.system[0].clone().unify_variables(&mut p);
+            assert!(GroebnerBasis::reduce(&p, &gb.system).is_zero());
+        }
+    }
+
+    #[test]
+    fn non_monic_pivots() {
+        // the rows of the reduced matrix have non-unit pivots, which used to be kept
+        // unnormalized and were subsequently used as simplifications
+        let polys = [
+            "3/2 v1^2 - 5 v2 v3 + 1/7",
+            "v1 v2 - 2/3 v3 + 11",
+            "v1 + v2^2 - 9/5 v3",
+        ];
+
+        let vars: Arc<Vec<Variable>> = Arc::new(
+            ["v1", "v2", "v3"]
+                .iter()
+                .map(|v| State::get_symbol(v).into())
+                .collect(),
+        );
+
+        let ideal: Vec<MultivariatePolynomial<_, u16>> = polys
+            .iter()
+            .map(|x| {
+                Atom::parse(x)
+                    .unwrap()
+                    .expand()
+                    .to_polynomial(&Q, Some(vars.clone()))
+            })
+            .collect();
+
+        let gb = GroebnerBasis::new(&ideal, false);
+        assert!(GroebnerBasis::is_groebner_basis(&gb.system));
+        for p in &ideal {
+            assert!(GroebnerBasis::reduce(p, &gb.system).is_zero());
+        }
+
+        let field = Zp64::new(18446744073709551557);
+        let ideal: Vec<MultivariatePolynomial<_, u16>> = polys
+            .iter()
+            .map(|x| {
+                Atom::parse(x)
+                    .unwrap()
+                    .expand()
+                    .to_polynomial(&field, Some(vars.clone()))
+            })
+            .collect();
+
+        let gb = GroebnerBasis::new(&ideal, false);
+        assert!(GroebnerBasis::is_groebner_basis(&gb.system));
+        for p in &ideal {
+            assert!(GroebnerBasis::reduce(p, &gb.system).is_zero());
+        }
+    }
+
+    #[test]
+    fn modular_rational() {
+        let homogeneous = [
+            "3/2 v1^2 v2 - 5 v2 v3^2 + 1/7 v3^3",
+            "v1 v2^2 - 2/3 v3^3 + 11 v1^3",
+            "v1^2 + v2^2 - 9/5 v3^2",
+        ];
+        let inhomogeneous = [
+            "3/2 v1^2 v2 - 5 v2 v3 + 1/7",
+            "v1 v2^2 - 2/3 v3 + 11 v1",
+            "v1 + v2^2 - 9/5 v3^2",
+        ];
+
+        let vars: Arc<Vec<Variable>> = Arc::new(
+            ["v1", "v2", "v3"]
+                .iter()
+                .map(|v| State::get_symbol(v).into())
+                .collect(),
+        );
+
+        for polys in [homogeneous, inhomogeneous] {
+            let ideal: Vec<MultivariatePolynomial<_, u16, GrevLexOrder>> = polys
+                .iter()
+                .map(|x| {
+                    Atom::parse(x)
+                        .unwrap()
+                        .expand()
+                        .to_polynomial::<_, u16>(&Q, Some(vars.clone()))
+                        .reorder()
+                })
+                .collect();
+
+            let gb = GroebnerBasis::new_modular(&ideal, false);
+            assert!(GroebnerBasis::is_groebner_basis(&gb.system));
+            for p in &ideal {
+                assert!(GroebnerBasis::reduce(p, &gb.system).is_zero());
+            }
+
+            let gb_direct = GroebnerBasis::new(&ideal, false);
+            assert_eq!(gb.system, gb_direct.system);
+        }
+
+        // the lexicographical basis of the inhomogeneous ideal has large coefficients
+        let ideal: Vec<MultivariatePolynomial<_, u16>> = inhomogeneous
+            .iter()
+            .map(|x| {
+                Atom::parse(x)
+                    .unwrap()
+                    .expand()
+                    .to_polynomial(&Q, Some(vars.clone()))
+            })
+            .collect();
+
+        let gb = GroebnerBasis::new_modular(&ideal, false);
+        assert_eq!(gb.system.len(), 3);
+        assert!(GroebnerBasis::is_groebner_basis(&gb.system));
+        for p in &ideal {
+            assert!(GroebnerBasis::reduce(p, &gb.system).is_zero());
+        }
+    }
+
+    #[test]
+    fn fglm() {
+        let polys = ["v1^2 + v2 v3 - 2", "v2^2 - v1 v3 + 1", "v3^2 - v1 - v2"];
+
+        let ideal: Vec<MultivariatePolynomial<_, u16>> = polys
+            .iter()
+            .map(|x| Atom::parse(x).unwrap().expand().to_polynomial(&Q, None))
+            .collect();
+
+        let lex = GroebnerBasis::new(&ideal, false);
+
+        let grevlex_ideal: Vec<_> = ideal.iter().map(|p| p.reorder::<GrevLexOrder>()).collect();
+        let grevlex = GroebnerBasis::new(&grevlex_ideal, false);
+        assert!(grevlex.is_zero_dimensional());
+
+        let converted = grevlex.fglm::<LexOrder>().unwrap();
+        assert_eq!(converted.system, lex.system);
+
+        let u = grevlex.univariate_polynomial(2).unwrap();
+        assert_eq!(&u.reorder::<LexOrder>(), lex.system.last().unwrap());
+    }
 }
@@ -0,0 +1,523 @@
+use std::sync::Arc;
+
+use crate::domains::Field;
+
+use super::{
+    groebner::{Echelonize, GroebnerBasis},
+    polynomial::MultivariatePolynomial,
+    BlockOrder, Exponent, LexOrder, MonomialOrder, Variable,
+};
+
+/// A list of polynomials, such as the generators of an ideal.
+type PolynomialList<R, E, O> = Vec<MultivariatePolynomial<R, E, O>>;
+
+/// A polynomial ideal, represented by its generators and their reduced Groebner basis.
+#[derive(Clone)]
+pub struct Ideal<R: Field + Echelonize, E: Exponent, O: MonomialOrder> {
+    generators: Vec<MultivariatePolynomial<R, E, O>>,
+    basis: GroebnerBasis<R, E, O>,
+}
+
+impl<R: Field + Echelonize, E: Exponent, O: MonomialOrder> Ideal<R, E, O> {
+    /// Create the ideal generated by `generators`. At least one generator,
+    /// which may be zero, must be provided to fix the variables of the ideal.
+    pub fn new(generators: &[MultivariatePolynomial<R, E, O>]) -> Ideal<R, E, O> {
+        assert!(
+            !generators.is_empty(),
+            "An ideal needs at least one generator"
+        );
+
+        let mut generators = generators.to_vec();
+        MultivariatePolynomial::unify_variables_list(&mut generators);
+
+        let non_zero: Vec<_> = generators
+            .iter()
+            .filter(|p| !p.is_zero())
+            .cloned()
+            .collect();
+        let basis = if non_zero.is_empty() {
+            GroebnerBasis {
+                system: vec![],
+                print_stats: false,
+            }
+        } else {
+            GroebnerBasis::new(&non_zero, false)
+        };
+
+        Ideal { generators, basis }
+    }
+
+    /// Get the generators of the ideal.
+    pub fn generators(&self) -> &[MultivariatePolynomial<R, E, O>] {
+        &self.generators
+    }
+
+    /// Get the reduced Groebner basis of the ideal.
+    pub fn groebner_basis(&self) -> &GroebnerBasis<R, E, O> {
+        &self.basis
+    }
+
+    /// Compute the normal form of `p`, which is zero if and only if `p` is in the ideal.
+    /// The polynomial `p` must have the same variables as the ideal.
+    pub fn normal_form(
+        &self,
+        p: &MultivariatePolynomial<R, E, O>,
+    ) -> MultivariatePolynomial<R, E, O> {
+        debug_assert!(p.variables == self.generators[0].variables);
+        GroebnerBasis::reduce(p, &self.basis.system)
+    }
+
+    /// Check if `p` is an element of the ideal.
+    pub fn contains(&self, p: &MultivariatePolynomial<R, E, O>) -> bool {
+        self.normal_form(p).is_zero()
+    }
+
+    /// Check if the ideal is a subset of `other`.
+    pub fn is_subset(&self, other: &Self) -> bool {
+        let (a, b) = Self::unify(&self.generators, &other.generators);
+        let other = Ideal::new(&b);
+        a.iter().all(|p| other.contains(p))
+    }
+
+    /// Check if the ideal is the whole polynomial ring.
+    pub fn is_one(&self) -> bool {
+        self.basis.system.iter().any(|p| p.is_constant())
+    }
+
+    /// Compute the sum `I + J` of the ideals.
+    pub fn sum(&self, other: &Self) -> Self {
+        let (mut a, b) = Self::unify(&self.generators, &other.generators);
+        a.extend(b);
+        Ideal::new(&a)
+    }
+
+    /// Compute the product `I * J` of the ideals, which is generated by the
+    /// products of the generators.
+    pub fn product(&self, other: &Self) -> Self {
+        let (a, b) = Self::unify(&self.generators, &other.generators);
+
+        let b: Vec<_> = b.iter().map(|g| g.reorder::<LexOrder>()).collect();
+        let mut products = Vec::with_capacity(a.len() * b.len());
+        for f in &a {
+            let f = f.reorder::<LexOrder>();
+            for g in &b {
+                products.push((&f * g).reorder());
+            }
+        }
+
+        Ideal::new(&products)
+    }
+
+    /// Compute the intersection of the ideals as `(t*I + (1-t)*J) ∩ k[x]`,
+    /// by eliminating the new variable `t`.
+    pub fn intersection(&self, other: &Self) -> Self {
+        let (a, b) = Self::unify(&self.generators, &other.generators);
+        let vars = a[0].variables.clone();
+        let ext_vars = Self::extend_variables(&vars, 1);
+
+        let mut polys = Vec::with_capacity(a.len() + b.len());
+        for f in &a {
+            polys.push(Self::embed::<BlockOrder<1, LexOrder, O>>(
+                f,
+                &ext_vars,
+                &[E::one()],
+            ));
+        }
+        for g in &b {
+            polys.push(
+                Self::embed::<BlockOrder<1, LexOrder, O>>(g, &ext_vars, &[E::zero()])
+                    - Self::embed(g, &ext_vars, &[E::one()]),
+            );
+        }
+
+        Ideal::new(&Self::eliminate_first(polys, &vars))
+    }
+
+    /// Compute the ideal quotient `I : J = { f | f*J ⊆ I }`, as the intersection
+    /// of `I : g = (I ∩ <g>) / g` for every generator `g` of `J`.
+    pub fn quotient(&self, other: &Self) -> Self {
+        let (a, b) = Self::unify(&self.generators, &other.generators);
+        let ideal = Ideal::new(&a);
+
+        let mut res: Option<Self> = None;
+        for g in b.iter().filter(|g| !g.is_zero()) {
+            let i = ideal.intersection(&Ideal::new(std::slice::from_ref(g)));
+            let q: Vec<_> = i
+                .generators
+                .iter()
+                .map(|h| Self::divide_exact(h, g))
+                .collect();
+
+            let q = Ideal::new(&q);
+            res = Some(match res {
+                Some(r) => r.intersection(&q),
+                None => q,
+            });
+        }
+
+        res.unwrap_or_else(|| Ideal::new(&[a[0].one()]))
+    }
+
+    /// Compute the saturation `I : J^∞ = { f | f*J^n ⊆ I for some n }`, as the intersection
+    /// of `I : g^∞ = (I + <1 - t*g>) ∩ k[x]` for every generator `g` of `J`.
+    pub fn saturation(&self, other: &Self) -> Self {
+        let (a, b) = Self::unify(&self.generators, &other.generators);
+        let vars = a[0].variables.clone();
+        let ext_vars = Self::extend_variables(&vars, 1);
+
+        let mut res: Option<Self> = None;
+        for g in b.iter().filter(|g| !g.is_zero()) {
+            let mut polys: Vec<_> = a
+                .iter()
+                .map(|f| Self::embed::<BlockOrder<1, LexOrder, O>>(f, &ext_vars, &[E::zero()]))
+                .collect();
+            polys.push(
+                Self::embed(&g.one(), &ext_vars, &[E::zero()])
+                    - Self::embed(g, &ext_vars, &[E::one()]),
+            );
+
+            let s = Ideal::new(&Self::eliminate_first(polys, &vars));
+            res = Some(match res {
+                Some(r) => r.intersection(&s),
+                None => s,
+            });
+        }
+
+        res.unwrap_or_else(|| Ideal::new(&[a[0].one()]))
+    }
+
+    /// Compute the Hilbert dimension of the ideal, which is the dimension of its variety.
+    /// It is the size of the largest set of variables such that no leading monomial of the Groebner
+    /// basis is a monomial in only these variables. Returns `None` for the ideal `<1>`,
+    /// whose variety is empty.
+    pub fn dimension(&self) -> Option<usize> {
+        if self.is_one() {
+            return None;
+        }
+
+        // the variables that occur in the leading monomials of the basis
+        let supports: Vec<Vec<usize>> = self
+            .basis
+            .system
+            .iter()
+            .map(|p| {
+                p.max_exp()
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, e)| !e.is_zero())
+                    .map(|(i, _)| i)
+                    .collect()
+            })
+            .collect();
+
+        let mut set = vec![false; self.generators[0].nvars()];
+        let mut dim = 0;
+        Self::max_independent_set(&supports, &mut set, 0, 0, &mut dim);
+        Some(dim)
+    }
+
+    /// Find the size of the largest independent set of variables that extends `set` with
+    /// variables from `start` onward, where a set is independent if every leading monomial,
+    /// given by its `supports`, contains a variable outside of the set.
+    fn max_independent_set(
+        supports: &[Vec<usize>],
+        set: &mut [bool],
+        start: usize,
+        size: usize,
+        best: &mut usize,
+    ) {
+        *best = (*best).max(size);
+
+        for v in start..set.len() {
+            // the set cannot grow large enough anymore
+            if size + set.len() - v <= *best {
+                return;
+            }
+
+            set[v] = true;
+            if supports.iter().all(|s| s.iter().any(|x| !set[*x])) {
+                Self::max_independent_set(supports, set, v + 1, size + 1, best);
+            }
+            set[v] = false;
+        }
+    }
+
+    /// Express `p` in terms of the generators `g_i` of the ideal, by returning
+    /// cofactors `c_i` such that `p = sum_i c_i g_i`. Returns `None` if `p` is
+    /// not an element of the ideal.
+    pub fn lift(
+        &self,
+        p: &MultivariatePolynomial<R, E, O>,
+    ) -> Option<Vec<MultivariatePolynomial<R, E, O>>> {
+        debug_assert!(p.variables == self.generators[0].variables);
+
+        let (module, ext_vars) = self.module_basis();
+
+        let mut exp = vec![E::zero(); self.generators.len() + 1];
+        exp[0] = E::one();
+        let r = GroebnerBasis::reduce(&Self::embed::<LexOrder>(p, &ext_vars, &exp), &module.system);
+
+        // the remainder is e_0*(p - sum_i c_i g_i) - sum_i c_i e_i
+        let (rest, cofactors) = self.split_tags(&r);
+        if !rest.is_zero() {
+            return None;
+        }
+
+        Some(cofactors.into_iter().map(|c| -c).collect())
+    }
+
+    /// Compute generators of the syzygy module of the generators `g_i` of the ideal,
+    /// i.e., of the cofactors `c_i` that satisfy `sum_i c_i g_i = 0`.
+    pub fn syzygies(&self) -> Vec<Vec<MultivariatePolynomial<R, E, O>>> {
+        let (module, _) = self.module_basis();
+        let ntags = self.generators.len() + 1;
+
+        // the elements without e_0 that are linear in the tags are the syzygies
+        module
+            .system
+            .iter()
+            .filter(|p| {
+                let lm = p.max_exp();
+                lm[0].is_zero() && lm[..ntags].iter().cloned().sum::<E>() == E::one()
+            })
+            .map(|p| self.split_tags(p).1)
+            .collect()
+    }
+
+    /// Compute the Groebner basis of the submodule generated by `g_i e_0 + e_i`, represented
+    /// as polynomials in the tag variables `e_i` with a position-over-term order,
+    /// where all products of tag variables are set to zero.
+    fn module_basis(&self) -> (GroebnerBasis<R, E, LexOrder>, Arc<Vec<Variable>>) {
+        let ntags = self.generators.len() + 1;
+        let ext_vars = Self::extend_variables(&self.generators[0].variables, ntags);
+
+        let mut exp = vec![E::zero(); ntags];
+        let mut polys = vec![];
+        for (i, g) in self.generators.iter().enumerate() {
+            exp[0] = E::one();
+            let mut v = Self::embed::<LexOrder>(g, &ext_vars, &exp);
+            exp[0] = E::zero();
+
+            exp[i + 1] = E::one();
+            v = v + Self::embed(&g.one(), &ext_vars, &exp);
+            exp[i + 1] = E::zero();
+
+            polys.push(v);
+        }
+
+        let one = self.generators[0].one();
+        for i in 0..ntags {
+            for j in i..ntags {
+                exp[i] += E::one();
+                exp[j] += E::one();
+                polys.push(Self::embed(&one, &ext_vars, &exp));
+                exp[i] = E::zero();
+                exp[j] = E::zero();
+            }
+        }
+
+        (GroebnerBasis::new(&polys, false), ext_vars)
+    }
+
+    /// Split a polynomial that is linear in the tag variables into the coefficient of `e_0`
+    /// and the coefficients of the `e_i` that correspond to the generators.
+    fn split_tags(
+        &self,
+        p: &MultivariatePolynomial<R, E, LexOrder>,
+    ) -> (MultivariatePolynomial<R, E, O>, PolynomialList<R, E, O>) {
+        let ntags = self.generators.len() + 1;
+        let zero = self.generators[0].zero();
+        let mut parts = vec![zero; ntags];
+
+        for t in p {
+            let tag = t.exponents[..ntags]
+                .iter()
+                .position(|e| !e.is_zero())
+                .unwrap();
+            parts[tag].append_monomial(t.coefficient.clone(), &t.exponents[ntags..]);
+        }
+
+        let rest = parts.remove(0);
+        (rest, parts)
+    }
+
+    /// Divide `p` by `g`, where `g` is known to divide `p`.
+    fn divide_exact(
+        p: &MultivariatePolynomial<R, E, O>,
+        g: &MultivariatePolynomial<R, E, O>,
+    ) -> MultivariatePolynomial<R, E, O> {
+        p.reorder::<LexOrder>()
+            .divides(&g.reorder())
+            .unwrap_or_else(|| panic!("{} does not divide {}", g, p))
+            .reorder()
+    }
+
+    /// Unify the variables of two lists of polynomials.
+    fn unify(
+        a: &[MultivariatePolynomial<R, E, O>],
+        b: &[MultivariatePolynomial<R, E, O>],
+    ) -> (PolynomialList<R, E, O>, PolynomialList<R, E, O>) {
+        let mut all: Vec<_> = a.iter().chain(b).cloned().collect();
+        MultivariatePolynomial::unify_variables_list(&mut all);
+        let b = all.split_off(a.len());
+        (all, b)
+    }
+
+    /// Prepend `n` new temporary variables to the variable list `vars`.
+    fn extend_variables(vars: &Arc<Vec<Variable>>, n: usize) -> Arc<Vec<Variable>> {
+        let start = vars
+            .iter()
+            .filter_map(|v| match v {
+                Variable::Temporary(i) => Some(*i + 1),
+                _ => None,
+            })
+            .max()
+            .unwrap_or(0);
+
+        let mut ext: Vec<_> = (start..start + n).map(Variable::Temporary).collect();
+        ext.extend(vars.iter().cloned());
+        Arc::new(ext)
+    }
+
+    /// Embed the polynomial in the ring with the additional variables `ext_vars`,
+    /// multiplied by the monomial `prefix` in the new variables.
+    fn embed<O2: MonomialOrder>(
+        p: &MultivariatePolynomial<R, E, O>,
+        ext_vars: &Arc<Vec<Variable>>,
+        prefix: &[E],
+    ) -> MultivariatePolynomial<R, E, O2> {
+        let mut r = MultivariatePolynomial::new(&p.field, Some(p.nterms()), ext_vars.clone());
+        let mut exp = prefix.to_vec();
+        for t in p {
+            exp.truncate(prefix.len());
+            exp.extend_from_slice(t.exponents);
+            r.append_monomial(t.coefficient.clone(), &exp);
+        }
+        r
+    }
+
+    /// Compute the Groebner basis of `polys` using an elimination order for the first variable,
+    /// and return the elements that do not depend on it as polynomials in `vars`.
+    fn eliminate_first(
+        mut polys: Vec<MultivariatePolynomial<R, E, BlockOrder<1, LexOrder, O>>>,
+        vars: &Arc<Vec<Variable>>,
+    ) -> Vec<MultivariatePolynomial<R, E, O>> {
+        let field = polys[0].field.clone();
+        polys.retain(|p| !p.is_zero());
+
+        let mut res = vec![];
+        if !polys.is_empty() {
+            for p in GroebnerBasis::new(&polys, false).elimination_ideal() {
+                let mut r = MultivariatePolynomial::new(&field, Some(p.nterms()), vars.clone());
+                for t in &p {
+                    r.append_monomial(t.coefficient.clone(), &t.exponents[1..]);
+                }
+                res.push(r);
+            }
+        }
+
+        if res.is_empty() {
+            res.push(MultivariatePolynomial::new(&field, None, vars.clone()));
+        }
+
+        res
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use crate::{
+        atom::Atom,
+        domains::rational::{RationalField, Q},
+        poly::{ideal::Ideal, polynomial::MultivariatePolynomial, Variable},
+        state::State,
+    };
+
+    fn parse(polys: &[&str]) -> Vec<MultivariatePolynomial<RationalField, u16>> {
+        let vars = Arc::new(
+            ["v1", "v2", "v3"]
+                .iter()
+                .map(|v| Variable::Symbol(State::get_symbol(v)))
+                .collect::<Vec<_>>(),
+        );
+
+        polys
+            .iter()
+            .map(|x| {
+                Atom::parse(x)
+                    .unwrap()
+                    .expand()
+                    .to_polynomial(&Q, Some(vars.clone()))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn membership_and_lift() {
+        let gens = parse(&["v1^2 - v2", "v1*v2 - 1"]);
+        let ideal = Ideal::new(&gens);
+
+        let p = parse(&["(v1+v3)*(v1^2-v2) + v1*v3*(v1*v2-1)"]).remove(0);
+        assert!(ideal.contains(&p));
+        assert!(!ideal.contains(&parse(&["v1 + v3"])[0]));
+
+        let cofactors = ideal.lift(&p).unwrap();
+        let mut r = p.zero();
+        for (c, g) in cofactors.iter().zip(&gens) {
+            r = r + c * g;
+        }
+        assert_eq!(r, p);
+
+        assert!(ideal.lift(&parse(&["v3"])[0]).is_none());
+    }
+
+    #[test]
+    fn syzygies() {
+        let gens = parse(&["v1*v2", "v1*v3", "v2^2"]);
+        let ideal = Ideal::new(&gens);
+
+        let syz = ideal.syzygies();
+        assert!(!syz.is_empty());
+        for s in &syz {
+            let mut r = gens[0].zero();
+            for (c, g) in s.iter().zip(&gens) {
+                r = r + c * g;
+            }
+            assert!(r.is_zero());
+        }
+    }
+
+    #[test]
+    fn operations() {
+        let x = Ideal::new(&parse(&["v1"]));
+        let y = Ideal::new(&parse(&["v2"]));
+        let xy = Ideal::new(&parse(&["v1*v2"]));
+
+        assert_eq!(
+            x.intersection(&y).groebner_basis().system,
+            xy.groebner_basis().system
+        );
+        assert_eq!(
+            x.product(&y).groebner_basis().system,
+            xy.groebner_basis().system
+        );
+        assert_eq!(
+            xy.quotient(&x).groebner_basis().system,
+            y.groebner_basis().system
+        );
+
+        let s = Ideal::new(&parse(&["v1^2*v2", "v1*v2*v3"])).saturation(&x);
+        assert_eq!(s.groebner_basis().system, y.groebner_basis().system);
+        assert!(x.saturation(&Ideal::new(&parse(&["0"]))).is_one());
+
+        let sum = x.sum(&y);
+        assert!(x.is_subset(&sum) && y.is_subset(&sum) && !sum.is_subset(&x));
+
+        assert_eq!(xy.dimension(), Some(2));
+        assert_eq!(sum.dimension(), Some(1));
+        assert_eq!(Ideal::new(&parse(&["0"])).dimension(), Some(3));
+        assert_eq!(Ideal::new(&parse(&["v1", "v1 - 1"])).dimension(), None);
+    }
+}
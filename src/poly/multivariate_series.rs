@@ -0,0 +1,719 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Display,
+    ops::{Add, Mul, Neg, Sub},
+    sync::Arc,
+};
+
+use crate::{
+    atom::{Atom, FunctionBuilder},
+    domains::{atom::AtomField, integer::Integer, rational::Rational, Field, Ring},
+    state::State,
+};
+
+use super::{polynomial::MultivariatePolynomial, Exponent, Variable};
+
+/// The truncation of a [MultivariateSeries].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum SeriesTruncation {
+    /// Keep all terms with a total degree of at most the given order.
+    TotalDegree(u32),
+    /// Keep all terms whose degree in each variable is at most the order of that variable.
+    PerVariable(Vec<u32>),
+    /// Keep all terms with a total degree of at most the first order and whose degree in each
+    /// variable is at most the order of that variable. This truncation arises when
+    /// series with a different kind of truncation are combined.
+    TotalAndPerVariable(u32, Vec<u32>),
+}
+
+impl SeriesTruncation {
+    /// Check if the monomial with exponents `exponents` is kept.
+    pub fn keeps<E: Exponent>(&self, exponents: &[E]) -> bool {
+        match self {
+            SeriesTruncation::TotalDegree(d) => {
+                exponents.iter().map(|e| e.to_u32()).sum::<u32>() <= *d
+            }
+            SeriesTruncation::PerVariable(o) => {
+                exponents.iter().zip(o).all(|(e, o)| e.to_u32() <= *o)
+            }
+            SeriesTruncation::TotalAndPerVariable(d, o) => {
+                exponents.iter().map(|e| e.to_u32()).sum::<u32>() <= *d
+                    && exponents.iter().zip(o).all(|(e, o)| e.to_u32() <= *o)
+            }
+        }
+    }
+
+    /// Get the highest total degree of a kept monomial.
+    pub fn max_degree(&self) -> u32 {
+        match self {
+            SeriesTruncation::TotalDegree(d) => *d,
+            SeriesTruncation::PerVariable(o) => o.iter().sum(),
+            SeriesTruncation::TotalAndPerVariable(d, o) => o.iter().sum::<u32>().min(*d),
+        }
+    }
+
+    /// Get the bound on the total degree and the bounds on the degree per variable.
+    fn bounds(&self) -> (Option<u32>, Option<&[u32]>) {
+        match self {
+            SeriesTruncation::TotalDegree(d) => (Some(*d), None),
+            SeriesTruncation::PerVariable(o) => (None, Some(o)),
+            SeriesTruncation::TotalAndPerVariable(d, o) => (Some(*d), Some(o)),
+        }
+    }
+
+    /// Get the truncation that keeps the monomials that are kept by both `self` and `other`.
+    /// Bounds that are implied by the other bounds are dropped.
+    fn min(&self, other: &Self) -> Self {
+        let (d1, o1) = self.bounds();
+        let (d2, o2) = other.bounds();
+
+        let d = match (d1, d2) {
+            (Some(d1), Some(d2)) => Some(d1.min(d2)),
+            (d1, d2) => d1.or(d2),
+        };
+        let o = match (o1, o2) {
+            (Some(o1), Some(o2)) => Some(o1.iter().zip(o2).map(|(a, b)| *a.min(b)).collect()),
+            (o1, o2) => o1.or(o2).map(|o| o.to_vec()),
+        };
+
+        match (d, o) {
+            (Some(d), None) => SeriesTruncation::TotalDegree(d),
+            (None, Some(o)) => SeriesTruncation::PerVariable(o),
+            (Some(d), Some(o)) => {
+                if o.iter().sum::<u32>() <= d {
+                    SeriesTruncation::PerVariable(o)
+                } else if o.iter().all(|o| *o >= d) {
+                    SeriesTruncation::TotalDegree(d)
+                } else {
+                    SeriesTruncation::TotalAndPerVariable(d, o)
+                }
+            }
+            (None, None) => unreachable!(),
+        }
+    }
+}
+
+/// A multivariate Taylor series in the variables `x_i - a_i`, where `a` is the expansion point.
+/// The series is truncated in the total degree or in the degree of every variable separately,
+/// which makes it suitable for expansions in several small parameters at once.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct MultivariateSeries<F: Ring, E: Exponent> {
+    poly: MultivariatePolynomial<F, E>,
+    expansion_point: Vec<F::Element>,
+    truncation: SeriesTruncation,
+}
+
+impl<F: Ring + Display, E: Exponent> Display for MultivariateSeries<F, E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.poly)?;
+
+        let vars = self.poly.get_vars_ref();
+        let (d, o) = self.truncation.bounds();
+        if let Some(d) = d {
+            write!(f, "+O({{")?;
+            for (i, v) in vars.iter().enumerate() {
+                if i > 0 {
+                    write!(f, ",")?;
+                }
+                write!(f, "{}", v)?;
+            }
+            write!(f, "}}^{})", d + 1)?;
+        }
+
+        if let Some(o) = o {
+            write!(f, "+O(")?;
+            for (i, (v, o)) in vars.iter().zip(o).enumerate() {
+                if i > 0 {
+                    write!(f, ",")?;
+                }
+                write!(f, "{}^{}", v, o + 1)?;
+            }
+            write!(f, ")")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<F: Ring, E: Exponent> MultivariateSeries<F, E> {
+    /// Constructs a zero series in `variables` around `expansion_point`. Instead of using
+    /// this constructor, prefer to create new series from existing ones, so that the
+    /// variables, expansion point and truncation are inherited.
+    pub fn new(
+        field: &F,
+        variables: Arc<Vec<Variable>>,
+        expansion_point: Vec<F::Element>,
+        truncation: SeriesTruncation,
+    ) -> Self {
+        assert_eq!(
+            variables.len(),
+            expansion_point.len(),
+            "The expansion point does not match the number of variables"
+        );
+        if let (_, Some(o)) = truncation.bounds() {
+            assert_eq!(
+                variables.len(),
+                o.len(),
+                "The truncation does not match the number of variables"
+            );
+        }
+
+        Self {
+            poly: MultivariatePolynomial::new(field, None, variables),
+            expansion_point,
+            truncation,
+        }
+    }
+
+    /// Constructs a series from a polynomial in the shifted variables `x_i - a_i`, where `a`
+    /// is the expansion point. Terms beyond the truncation are removed.
+    pub fn from_polynomial(
+        poly: MultivariatePolynomial<F, E>,
+        expansion_point: Vec<F::Element>,
+        truncation: SeriesTruncation,
+    ) -> Self {
+        let mut s = Self::new(&poly.field, poly.get_vars(), expansion_point, truncation);
+        s.poly = poly;
+        s.truncate();
+        s
+    }
+
+    /// Constructs a zero series, inheriting the field, variables and truncation from `self`.
+    pub fn zero(&self) -> Self {
+        Self {
+            poly: self.poly.zero(),
+            expansion_point: self.expansion_point.clone(),
+            truncation: self.truncation.clone(),
+        }
+    }
+
+    /// Constructs a constant series, inheriting the field, variables and truncation from `self`.
+    pub fn constant(&self, coeff: F::Element) -> Self {
+        Self {
+            poly: self.poly.constant(coeff),
+            expansion_point: self.expansion_point.clone(),
+            truncation: self.truncation.clone(),
+        }
+    }
+
+    /// Constructs a series that is one, inheriting the field, variables and truncation from `self`.
+    pub fn one(&self) -> Self {
+        Self {
+            poly: self.poly.one(),
+            expansion_point: self.expansion_point.clone(),
+            truncation: self.truncation.clone(),
+        }
+    }
+
+    /// Constructs the series of the `var`th variable `x`, which is `a + (x - a)`
+    /// for the expansion point `a`.
+    pub fn variable(&self, var: usize) -> Self {
+        let mut exp = vec![E::zero(); self.poly.nvars()];
+        exp[var] = E::one();
+
+        let mut r = self.constant(self.expansion_point[var].clone());
+        r.poly.append_monomial(self.poly.field.one(), &exp);
+        r.truncate();
+        r
+    }
+
+    /// Get the polynomial in the shifted variables `x_i - a_i`, where `a` is the expansion point.
+    pub fn poly(&self) -> &MultivariatePolynomial<F, E> {
+        &self.poly
+    }
+
+    /// Get the expansion point.
+    pub fn expansion_point(&self) -> &[F::Element] {
+        &self.expansion_point
+    }
+
+    /// Get the truncation.
+    pub fn truncation(&self) -> &SeriesTruncation {
+        &self.truncation
+    }
+
+    /// Get the variables of the series.
+    pub fn get_vars_ref(&self) -> &[Variable] {
+        self.poly.get_vars_ref()
+    }
+
+    /// Get the field of the coefficients.
+    pub fn field(&self) -> &F {
+        &self.poly.field
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.poly.is_zero()
+    }
+
+    pub fn is_constant(&self) -> bool {
+        self.poly.is_constant()
+    }
+
+    /// Get the constant term, i.e., the value of the series at the expansion point.
+    pub fn get_constant(&self) -> F::Element {
+        self.poly.get_constant()
+    }
+
+    /// Remove all terms beyond the truncation.
+    fn truncate(&mut self) {
+        if (&self.poly)
+            .into_iter()
+            .all(|t| self.truncation.keeps(t.exponents))
+        {
+            return;
+        }
+
+        let mut p = self.poly.zero_with_capacity(self.poly.nterms());
+        for t in &self.poly {
+            if self.truncation.keeps(t.exponents) {
+                p.append_monomial(t.coefficient.clone(), t.exponents);
+            }
+        }
+        self.poly = p;
+    }
+
+    /// Get the truncation of the result of a binary operation.
+    fn joint_truncation(&self, other: &Self) -> SeriesTruncation {
+        assert!(
+            self.expansion_point == other.expansion_point,
+            "Cannot combine series with a different expansion point"
+        );
+        self.truncation.min(&other.truncation)
+    }
+
+    /// Multiply the polynomials of `self` and `other`, skipping all products of terms
+    /// that are beyond `truncation`.
+    fn mul_truncated(
+        &self,
+        other: &Self,
+        truncation: &SeriesTruncation,
+    ) -> MultivariatePolynomial<F, E> {
+        let field = &self.poly.field;
+        let mut terms: BTreeMap<Vec<E>, F::Element> = BTreeMap::new();
+        let mut exp = vec![E::zero(); self.poly.nvars()];
+        for t1 in &self.poly {
+            if !truncation.keeps(t1.exponents) {
+                continue;
+            }
+
+            for t2 in &other.poly {
+                for ((e, e1), e2) in exp.iter_mut().zip(t1.exponents).zip(t2.exponents) {
+                    *e = *e1 + *e2;
+                }
+
+                if !truncation.keeps(&exp) {
+                    continue;
+                }
+
+                if let Some(c) = terms.get_mut(&exp) {
+                    field.add_mul_assign(c, t1.coefficient, t2.coefficient);
+                } else {
+                    terms.insert(exp.clone(), field.mul(t1.coefficient, t2.coefficient));
+                }
+            }
+        }
+
+        let mut res = self.poly.zero_with_capacity(terms.len());
+        for (e, c) in terms {
+            res.append_monomial_back(c, &e);
+        }
+        res
+    }
+
+    /// Compute `self^pow`.
+    pub fn npow(&self, mut pow: usize) -> Self {
+        if pow == 0 {
+            return self.one();
+        }
+
+        let mut x = self.clone();
+        let mut y = self.one();
+        while pow != 1 {
+            if pow % 2 == 1 {
+                y = &y * &x;
+                pow -= 1;
+            }
+
+            x = &x * &x;
+            pow /= 2;
+        }
+
+        x * &y
+    }
+
+    pub fn mul_coeff(mut self, coeff: &F::Element) -> Self {
+        self.poly = self.poly.mul_coeff(coeff.clone());
+        self
+    }
+
+    /// Compute `sum_k coefficients[k] * (self - c)^k`, where `c` is the constant term of `self`.
+    /// This evaluates a function `f` with Taylor coefficients `coefficients` around `c` on the series.
+    /// Coefficients beyond the highest degree of the truncation are ignored.
+    pub fn compose_univariate(&self, coefficients: &[F::Element]) -> Self {
+        let p = self - &self.constant(self.get_constant());
+
+        let mut res = self.zero();
+        let depth = self.truncation.max_degree() as usize + 1;
+        for c in coefficients.iter().take(depth).rev() {
+            res = &res * &p;
+            res.poly = res.poly.add_constant(c.clone());
+        }
+
+        res
+    }
+
+    /// Substitute the `i`th variable of the series by `args[i]`, which are series in other variables.
+    /// The constant term of every argument must be the expansion point of the variable it replaces.
+    ///
+    /// The result is only exact up to the truncation of the arguments if the highest degree
+    /// of the truncation of `self` is at least as large.
+    pub fn compose(&self, args: &[Self]) -> Result<Self, &'static str> {
+        if args.len() != self.poly.nvars() {
+            return Err("The number of arguments does not match the number of variables");
+        }
+
+        let Some(first) = args.first() else {
+            return Ok(self.clone());
+        };
+
+        let mut shifted = Vec::with_capacity(args.len());
+        for (a, p) in args.iter().zip(&self.expansion_point) {
+            if a.get_constant() != *p {
+                return Err("The constant term of an argument does not match the expansion point");
+            }
+
+            shifted.push(a - &a.constant(p.clone()));
+        }
+
+        let mut powers: Vec<_> = shifted.iter().map(|s| vec![s.one()]).collect();
+
+        let mut res = first.zero();
+        for t in &self.poly {
+            let mut term = first.constant(t.coefficient.clone());
+            for (i, e) in t.exponents.iter().enumerate() {
+                let e = e.to_u32() as usize;
+                if e == 0 {
+                    continue;
+                }
+
+                while powers[i].len() <= e {
+                    let next = powers[i].last().unwrap() * &shifted[i];
+                    powers[i].push(next);
+                }
+
+                term = term * &powers[i][e];
+            }
+
+            res = res + term;
+        }
+
+        Ok(res)
+    }
+}
+
+impl<F: Field, E: Exponent> MultivariateSeries<F, E> {
+    /// Compute the inverse of a series with a non-zero constant term.
+    pub fn inv(&self) -> Result<Self, &'static str> {
+        let c = self.get_constant();
+        if F::is_zero(&c) {
+            return Err("Cannot invert a series without a constant term");
+        }
+
+        // 1/(c+p) = sum_k (-1)^k c^(-k-1) p^k
+        let field = self.field();
+        let c_inv = field.inv(&c);
+        let mut coeffs = Vec::with_capacity(self.truncation.max_degree() as usize + 1);
+        let mut x = c_inv.clone();
+        for _ in 0..=self.truncation.max_degree() {
+            coeffs.push(x.clone());
+            x = field.neg(&field.mul(&x, &c_inv));
+        }
+
+        Ok(self.compose_univariate(&coeffs))
+    }
+}
+
+impl<F: Ring, E: Exponent> Add for MultivariateSeries<F, E> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self::Output {
+        &self + &other
+    }
+}
+
+impl<'a, F: Ring, E: Exponent> Add<&'a MultivariateSeries<F, E>> for &MultivariateSeries<F, E> {
+    type Output = MultivariateSeries<F, E>;
+
+    fn add(self, other: &'a MultivariateSeries<F, E>) -> Self::Output {
+        let mut res = MultivariateSeries {
+            poly: &self.poly + &other.poly,
+            expansion_point: self.expansion_point.clone(),
+            truncation: self.joint_truncation(other),
+        };
+        res.truncate();
+        res
+    }
+}
+
+impl<F: Ring, E: Exponent> Sub for MultivariateSeries<F, E> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self::Output {
+        &self - &other
+    }
+}
+
+impl<'a, F: Ring, E: Exponent> Sub<&'a MultivariateSeries<F, E>> for &MultivariateSeries<F, E> {
+    type Output = MultivariateSeries<F, E>;
+
+    fn sub(self, other: &'a MultivariateSeries<F, E>) -> Self::Output {
+        let mut res = MultivariateSeries {
+            poly: &self.poly - &other.poly,
+            expansion_point: self.expansion_point.clone(),
+            truncation: self.joint_truncation(other),
+        };
+        res.truncate();
+        res
+    }
+}
+
+impl<F: Ring, E: Exponent> Neg for MultivariateSeries<F, E> {
+    type Output = Self;
+
+    fn neg(mut self) -> Self::Output {
+        self.poly = -self.poly;
+        self
+    }
+}
+
+impl<'a, F: Ring, E: Exponent> Mul<&'a MultivariateSeries<F, E>> for &MultivariateSeries<F, E> {
+    type Output = MultivariateSeries<F, E>;
+
+    fn mul(self, other: &'a MultivariateSeries<F, E>) -> Self::Output {
+        let truncation = self.joint_truncation(other);
+        MultivariateSeries {
+            poly: self.mul_truncated(other, &truncation),
+            expansion_point: self.expansion_point.clone(),
+            truncation,
+        }
+    }
+}
+
+impl<'a, F: Ring, E: Exponent> Mul<&'a MultivariateSeries<F, E>> for MultivariateSeries<F, E> {
+    type Output = MultivariateSeries<F, E>;
+
+    fn mul(self, other: &'a MultivariateSeries<F, E>) -> Self::Output {
+        (&self) * other
+    }
+}
+
+impl<E: Exponent> MultivariateSeries<AtomField, E> {
+    /// Get the Taylor coefficients `f^(k)(c)/k!` around the constant term `c`,
+    /// given the derivatives `f^(k)(c)`.
+    fn taylor_coefficients(&self, derivative: impl Fn(usize) -> Atom) -> Vec<Atom> {
+        let mut res = vec![];
+        let mut fac = Rational::one();
+        for k in 0..=self.truncation.max_degree() as usize {
+            if k > 0 {
+                fac = &fac / &Rational::from(k as i64);
+            }
+            res.push(derivative(k) * &Atom::new_num(fac.clone()));
+        }
+        res
+    }
+
+    pub fn exp(&self) -> Self {
+        let c = self.get_constant();
+        let e = FunctionBuilder::new(State::EXP).add_arg(&c).finish();
+        let coeffs = self.taylor_coefficients(|_| e.clone());
+        self.compose_univariate(&coeffs)
+    }
+
+    pub fn log(&self) -> Result<Self, &'static str> {
+        let c = self.get_constant();
+        if c.is_zero() {
+            return Err("Cannot compute the logarithm of a series without a constant term");
+        }
+
+        // log(c + p) = log(c) + sum_k (-1)^(k+1) (p/c)^k / k
+        let mut coeffs = vec![FunctionBuilder::new(State::LOG).add_arg(&c).finish()];
+        for k in 1..=self.truncation.max_degree() as i64 {
+            let s = if k % 2 == 1 { 1 } else { -1 };
+            coeffs.push(c.npow(Integer::from(-k)) * &Atom::new_num((s, k)));
+        }
+
+        Ok(self.compose_univariate(&coeffs))
+    }
+
+    pub fn sin(&self) -> Self {
+        let c = self.get_constant();
+        let sin = FunctionBuilder::new(State::SIN).add_arg(&c).finish();
+        let cos = FunctionBuilder::new(State::COS).add_arg(&c).finish();
+
+        let coeffs = self.taylor_coefficients(|k| match k % 4 {
+            0 => sin.clone(),
+            1 => cos.clone(),
+            2 => -&sin,
+            _ => -&cos,
+        });
+        self.compose_univariate(&coeffs)
+    }
+
+    pub fn cos(&self) -> Self {
+        let c = self.get_constant();
+        let sin = FunctionBuilder::new(State::SIN).add_arg(&c).finish();
+        let cos = FunctionBuilder::new(State::COS).add_arg(&c).finish();
+
+        let coeffs = self.taylor_coefficients(|k| match k % 4 {
+            0 => cos.clone(),
+            1 => -&sin,
+            2 => -&cos,
+            _ => sin.clone(),
+        });
+        self.compose_univariate(&coeffs)
+    }
+
+    /// Take the series to the power of another series.
+    pub fn pow(&self, pow: &Self) -> Result<Self, &'static str> {
+        Ok((self.log()? * pow).exp())
+    }
+
+    /// Take the series to the power of a rational number.
+    pub fn rpow(&self, pow: Rational) -> Result<Self, &'static str> {
+        if pow.is_integer() && !pow.is_negative() {
+            return Ok(self.npow(pow.numerator().to_i64().unwrap() as usize));
+        }
+
+        let c = self.get_constant();
+        if c.is_zero() {
+            return Err(
+                "Cannot take a negative or fractional power of a series without a constant term",
+            );
+        }
+
+        // (c + p)^r = sum_k binom(r, k) c^(r-k) p^k
+        let mut coeffs = vec![];
+        let mut binom = Rational::one();
+        for k in 0..=self.truncation.max_degree() as i64 {
+            if k > 0 {
+                binom = &(&binom * &(&pow - &Rational::from(k - 1))) / &Rational::from(k);
+            }
+
+            coeffs.push(c.npow(&pow - &Rational::from(k)) * &Atom::new_num(binom.clone()));
+        }
+
+        Ok(self.compose_univariate(&coeffs))
+    }
+
+    /// Convert the series to an expression, without the truncation.
+    pub fn to_atom(&self) -> Atom {
+        let shifted: Vec<_> = self
+            .poly
+            .get_vars_ref()
+            .iter()
+            .zip(&self.expansion_point)
+            .map(|(v, p)| v.to_atom() - p)
+            .collect();
+
+        let mut out = Atom::new_num(0);
+        for t in &self.poly {
+            let mut term = t.coefficient.clone();
+            for (s, e) in shifted.iter().zip(t.exponents) {
+                if !e.is_zero() {
+                    term = term * &s.npow(Integer::from(e.to_u32() as u64));
+                }
+            }
+
+            out = &out + &term;
+        }
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        atom::Atom,
+        domains::{
+            rational::{RationalField, Q},
+            Ring,
+        },
+    };
+
+    use super::{MultivariateSeries, SeriesTruncation};
+
+    fn series(input: &str, truncation: SeriesTruncation) -> MultivariateSeries<RationalField, u8> {
+        let vars = Atom::parse("v1+v2")
+            .unwrap()
+            .to_polynomial::<_, u8>(&Q, None);
+        let p = Atom::parse(input)
+            .unwrap()
+            .to_polynomial::<_, u8>(&Q, Some(vars.get_vars()));
+        MultivariateSeries::from_polynomial(p, vec![Q.zero(), Q.zero()], truncation)
+    }
+
+    #[test]
+    fn truncation_min() {
+        let total = SeriesTruncation::TotalDegree(2);
+        let per_var = SeriesTruncation::PerVariable(vec![1, 3]);
+        assert_eq!(
+            total.min(&per_var),
+            SeriesTruncation::TotalAndPerVariable(2, vec![1, 3])
+        );
+        assert_eq!(
+            total.min(&SeriesTruncation::PerVariable(vec![1, 1])),
+            SeriesTruncation::PerVariable(vec![1, 1])
+        );
+        assert_eq!(
+            total.min(&SeriesTruncation::PerVariable(vec![2, 3])),
+            SeriesTruncation::TotalDegree(2)
+        );
+
+        let mixed = total.min(&per_var);
+        assert!(mixed.keeps(&[1u8, 1]));
+        assert!(!mixed.keeps(&[2u8, 0]));
+        assert!(!mixed.keeps(&[0u8, 3]));
+        assert_eq!(mixed.max_degree(), 2);
+    }
+
+    #[test]
+    fn mixed_truncation() {
+        let a = series("1+v1+v2", SeriesTruncation::TotalDegree(2));
+        let b = series("1+v1+v2", SeriesTruncation::PerVariable(vec![1, 2]));
+
+        let r = &a * &b;
+        assert_eq!(
+            r.truncation(),
+            &SeriesTruncation::TotalAndPerVariable(2, vec![1, 2])
+        );
+        assert_eq!(
+            r.poly().to_expression(),
+            Atom::parse("1+2*v1+2*v2+2*v1*v2+v2^2").unwrap()
+        );
+
+        let r = &a + &b;
+        assert_eq!(
+            r.poly().to_expression(),
+            Atom::parse("2+2*v1+2*v2").unwrap()
+        );
+    }
+
+    #[test]
+    fn truncated_product() {
+        let a = series("1+v1+v2", SeriesTruncation::TotalDegree(2));
+        assert_eq!(
+            a.npow(3).poly().to_expression(),
+            Atom::parse("1+3*v1+3*v2+3*v1^2+6*v1*v2+3*v2^2").unwrap()
+        );
+
+        let a = series("1-v1-v2", SeriesTruncation::PerVariable(vec![1, 2]));
+        let inv = a.inv().unwrap();
+        assert_eq!(
+            inv.poly().to_expression(),
+            Atom::parse("1+v1+v2+2*v1*v2+v2^2+3*v1*v2^2").unwrap()
+        );
+        assert!((&inv * &a).poly().is_one());
+    }
+}
@@ -355,6 +355,18 @@ impl<F: Ring, E: Exponent, O: MonomialOrder> MultivariatePolynomial<F, E, O> {
         return self.exponents.iter().all(|e| e.is_zero());
     }
 
+    /// Returns true if all monomials of the polynomial have the same total degree.
+    pub fn is_homogeneous(&self) -> bool {
+        let mut degree = None;
+        for t in self {
+            let d: u64 = t.exponents.iter().map(|x| x.to_u32() as u64).sum();
+            if *degree.get_or_insert(d) != d {
+                return false;
+            }
+        }
+        true
+    }
+
     /// Get the constant term of the polynomial.
     #[inline]
     pub fn get_constant(&self) -> F::Element {
@@ -0,0 +1,568 @@
+use std::sync::Arc;
+
+use crate::{
+    domains::{
+        finite_field::{FiniteFieldCore, Zp64},
+        integer::{FromFiniteField, Integer, IntegerRing, Z},
+        rational::{Rational, Q},
+        rational_polynomial::{FromNumeratorAndDenominator, RationalPolynomial},
+        Field, Ring,
+    },
+    tensors::matrix::Matrix,
+};
+
+use super::{
+    gcd::LARGE_U64_PRIMES, polynomial::MultivariatePolynomial, univariate::ThieleInterpolation,
+    Exponent, Variable,
+};
+
+/// The maximal number of sample points used to reconstruct a univariate rational function.
+const MAX_THIELE_POINTS: usize = 1000;
+
+/// The number of times the reconstruction over a prime field is attempted with
+/// new random sample points, before moving to the next prime.
+const MAX_ATTEMPTS: usize = 3;
+
+/// The coefficients of the numerator and the denominator of a univariate rational function over a prime field.
+type UnivariateRationalCoefficients = (Vec<<Zp64 as Ring>::Element>, Vec<<Zp64 as Ring>::Element>);
+
+/// The numerator and the denominator of a multivariate rational function over a prime field.
+pub type ModularRationalFunction<E> = (
+    MultivariatePolynomial<Zp64, E>,
+    MultivariatePolynomial<Zp64, E>,
+);
+
+/// Reconstruct the univariate rational functions `f_i(t * z + s)` in `t` using Thiele interpolation,
+/// where `s` is the shift and `f_i` are the components of the black box `f`. The results are normalized such that
+/// the constant term of the denominator is 1.
+/// Returns the coefficients of the numerator and the denominator of every component, or `None` when the
+/// reconstruction failed for this line.
+fn sample_line<F>(
+    f: &F,
+    field: &Zp64,
+    z: &[<Zp64 as Ring>::Element],
+    shift: &[<Zp64 as Ring>::Element],
+    max_points: usize,
+) -> Option<Vec<UnivariateRationalCoefficients>>
+where
+    F: Fn(&Zp64, &[<Zp64 as Ring>::Element]) -> Option<Vec<<Zp64 as Ring>::Element>>,
+{
+    let mut thiele: Vec<ThieleInterpolation<Zp64>> = vec![];
+    let mut done: Vec<bool> = vec![];
+    let mut x = vec![field.zero(); z.len()];
+    let mut failures = 0;
+    let mut t = 0;
+    loop {
+        if failures > max_points || thiele.iter().any(|th| th.len() > max_points) {
+            return None;
+        }
+
+        t += 1;
+        let te = field.to_element(t);
+        for ((xi, zi), si) in x.iter_mut().zip(z).zip(shift) {
+            *xi = field.add(&field.mul(&te, zi), si);
+        }
+
+        let Some(y) = f(field, &x) else {
+            failures += 1;
+            continue;
+        };
+
+        if thiele.is_empty() {
+            thiele = y
+                .iter()
+                .map(|_| ThieleInterpolation::new(field.clone()))
+                .collect();
+            done = vec![false; y.len()];
+        } else if y.len() != thiele.len() {
+            return None;
+        }
+
+        // every component is sampled until it is predicted correctly
+        for ((th, d), yi) in thiele.iter_mut().zip(&mut done).zip(y) {
+            if *d {
+                continue;
+            }
+
+            if th.evaluate(&te).as_ref() == Some(&yi) {
+                *d = true;
+            } else if !th.add_point(te, yi) {
+                failures += 1;
+            }
+        }
+
+        if done.iter().all(|d| *d) {
+            break;
+        }
+    }
+
+    let mut res = Vec::with_capacity(thiele.len());
+    for th in thiele {
+        let (num, den) = th.to_rational_function(Arc::new(Variable::Temporary(0)));
+
+        let c = &den.coefficients[0];
+        if Zp64::is_zero(c) {
+            return None;
+        }
+
+        let inv = field.inv(c);
+        res.push((
+            num.coefficients
+                .iter()
+                .map(|x| field.mul(x, &inv))
+                .collect(),
+            den.coefficients
+                .iter()
+                .map(|x| field.mul(x, &inv))
+                .collect(),
+        ));
+    }
+
+    Some(res)
+}
+
+/// Determine the coefficients of a polynomial that has the same monomials as `shape`
+/// from the values `samples[q][index]` at the points `x_k = alphas[k]^(q+1)`, by solving
+/// a Vandermonde system.
+fn fit_shape<E: Exponent>(
+    shape: &MultivariatePolynomial<Zp64, E>,
+    alphas: &[<Zp64 as Ring>::Element],
+    samples: &[Vec<<Zp64 as Ring>::Element>],
+    index: usize,
+) -> Option<MultivariatePolynomial<Zp64, E>> {
+    let field = &shape.field;
+    let nterms = shape.nterms();
+    if nterms == 0 {
+        return Some(shape.clone());
+    }
+
+    let mut m = Matrix::new(nterms as u32, nterms as u32, field.clone());
+    for (i, e) in shape.exponents_iter().enumerate() {
+        let mut v = field.one();
+        for (a, p) in alphas.iter().zip(e) {
+            if !p.is_zero() {
+                field.mul_assign(&mut v, &field.pow(a, p.to_u32() as u64));
+            }
+        }
+
+        let mut vp = v;
+        for q in 0..nterms {
+            m[(q as u32, i as u32)] = vp;
+            field.mul_assign(&mut vp, &v);
+        }
+    }
+
+    let b = Matrix::new_vec(
+        samples[..nterms].iter().map(|s| s[index]).collect(),
+        field.clone(),
+    );
+
+    let sol = m.solve(&b).ok()?;
+
+    let mut res = shape.zero_with_capacity(nterms);
+    for (c, e) in sol.data.into_iter().zip(shape.exponents_iter()) {
+        res.append_monomial(c, e);
+    }
+    Some(res)
+}
+
+/// Reconstruct a rational function in `variables` over the prime field `field` from a black box `f`
+/// that evaluates the function at a point, or returns `None` if the function is undefined there.
+/// The numerator and the denominator are returned, where the denominator is monic.
+///
+/// See [reconstruct_rational_functions_modular] for the algorithm.
+///
+/// This function returns `None` if a random sample point was unlucky.
+pub fn reconstruct_rational_function_modular<E: Exponent, F>(
+    f: &F,
+    field: &Zp64,
+    variables: Arc<Vec<Variable>>,
+) -> Option<(
+    MultivariatePolynomial<Zp64, E>,
+    MultivariatePolynomial<Zp64, E>,
+)>
+where
+    F: Fn(&Zp64, &[<Zp64 as Ring>::Element]) -> Option<<Zp64 as Ring>::Element>,
+{
+    reconstruct_rational_functions_modular(
+        &|field: &Zp64, x: &[<Zp64 as Ring>::Element]| f(field, x).map(|y| vec![y]),
+        field,
+        variables,
+    )?
+    .pop()
+}
+
+/// Reconstruct several rational functions in `variables` over the prime field `field` from a black box `f`
+/// that evaluates all functions at a point at once, or returns `None` if a function is undefined there.
+/// The black box should always return the same number of values.
+/// The numerators and the denominators are returned, where the denominators are monic.
+///
+/// The variables are shifted by a random amount and the functions are sampled on lines
+/// `t * (1, z_2, ..., z_n)`, which are reconstructed in `t` using Thiele interpolation. The coefficient of `t^k`
+/// is a homogeneous polynomial of degree `k`, which is reconstructed in `z_2, ..., z_n`
+/// variable by variable using Zippel's sparse interpolation. Every evaluation of the black box
+/// is shared between all functions.
+///
+/// This function returns `None` if a random sample point was unlucky.
+pub fn reconstruct_rational_functions_modular<E: Exponent, F>(
+    f: &F,
+    field: &Zp64,
+    variables: Arc<Vec<Variable>>,
+) -> Option<Vec<ModularRationalFunction<E>>>
+where
+    F: Fn(&Zp64, &[<Zp64 as Ring>::Element]) -> Option<Vec<<Zp64 as Ring>::Element>>,
+{
+    let n = variables.len();
+    let mut rng = rand::thread_rng();
+    let mut random = || field.sample(&mut rng, (1, i64::MAX));
+
+    let shift: Vec<_> = (0..n).map(|_| random()).collect();
+
+    // the point at which the variables that have not been reconstructed yet are fixed
+    let mut z: Vec<_> = (0..n).map(|_| random()).collect();
+    if let Some(z0) = z.first_mut() {
+        *z0 = field.one();
+    }
+
+    let lines = sample_line(f, field, &z, &shift, MAX_THIELE_POINTS)?;
+    let lens: Vec<_> = lines
+        .iter()
+        .map(|(num, den)| (num.len(), den.len()))
+        .collect();
+    let max_points = lens.iter().map(|(n, d)| n + d + 1).max().unwrap_or(1);
+
+    // sample the coefficients of the numerators and denominators in `t`
+    let sample = |z: &[<Zp64 as Ring>::Element]| {
+        let lines = sample_line(f, field, z, &shift, max_points)?;
+        if lines.len() != lens.len() {
+            return None;
+        }
+
+        let mut res = vec![];
+        for ((mut num, den), (num_len, den_len)) in lines.into_iter().zip(&lens) {
+            if num.len() > *num_len || den.len() > *den_len {
+                return None;
+            }
+
+            num.resize(*num_len, field.zero());
+            num.extend(den);
+            num.resize(num_len + den_len, field.zero());
+            res.extend(num);
+        }
+        Some(res)
+    };
+
+    let zero = MultivariatePolynomial::<_, E>::new(field, None, variables);
+    let mut polys: Vec<_> = lines
+        .into_iter()
+        .flat_map(|(num, den)| num.into_iter().chain(den))
+        .map(|c| zero.constant(c))
+        .collect();
+
+    // the degree in every variable is bounded by the total degree
+    let npoints = lens.iter().map(|(n, d)| *n.max(d)).max().unwrap_or(0);
+
+    for j in 1..n {
+        let ys: Vec<_> = (0..npoints).map(|_| random()).collect();
+        let alphas: Vec<_> = (0..j).map(|_| random()).collect();
+        let max_terms = polys.iter().map(|p| p.nterms()).max().unwrap_or(0);
+
+        let mut images = vec![Vec::with_capacity(npoints); polys.len()];
+        for y in &ys {
+            let mut zq = z.clone();
+            zq[j] = *y;
+            for zk in &mut zq[1..j] {
+                *zk = field.one();
+            }
+
+            let mut samples = Vec::with_capacity(max_terms);
+            for _ in 0..max_terms {
+                for (zk, a) in zq[1..j].iter_mut().zip(&alphas[1..]) {
+                    field.mul_assign(zk, a);
+                }
+                samples.push(sample(&zq)?);
+            }
+
+            for (c, (p, im)) in polys.iter().zip(&mut images).enumerate() {
+                im.push(fit_shape(p, &alphas, &samples, c)?);
+            }
+        }
+
+        for (p, im) in polys.iter_mut().zip(&images) {
+            *p = MultivariatePolynomial::newton_interpolation(&ys, im, j);
+        }
+    }
+
+    let mut polys = polys.into_iter();
+    let mut res = Vec::with_capacity(lens.len());
+    for (num_len, den_len) in lens {
+        // homogenize the coefficient of `t^k` to degree `k` using the first variable
+        let mut num = zero.clone();
+        let mut den = zero.clone();
+        for (k, p) in polys.by_ref().take(num_len + den_len).enumerate() {
+            let (r, deg) = if k < num_len {
+                (&mut num, k as u32)
+            } else {
+                (&mut den, (k - num_len) as u32)
+            };
+
+            for (c, e) in p.coefficients.iter().zip(p.exponents_iter()) {
+                let d: u32 = e.iter().map(|x| x.to_u32()).sum();
+                if d > deg {
+                    return None;
+                }
+
+                let mut e = e.to_vec();
+                if let Some(e0) = e.first_mut() {
+                    *e0 = E::from_u32(deg - d);
+                } else if deg > 0 {
+                    return None;
+                }
+
+                r.append_monomial(*c, &e);
+            }
+        }
+
+        for (i, s) in shift.iter().enumerate() {
+            num = num.shift_var(i, &field.neg(s));
+            den = den.shift_var(i, &field.neg(s));
+        }
+
+        let inv = field.inv(&den.lcoeff());
+        res.push((num.mul_coeff(inv), den.mul_coeff(inv)));
+    }
+
+    // check the result at a random point
+    let x: Vec<_> = (0..n).map(|_| random()).collect();
+    if let Some(y) = f(field, &x) {
+        for ((num, den), y) in res.iter().zip(y) {
+            let d = den.replace_all(&x);
+            if !Zp64::is_zero(&d) && y != field.div(&num.replace_all(&x), &d) {
+                return None;
+            }
+        }
+    }
+
+    Some(res)
+}
+
+/// Reconstruct a rational function with rational coefficients in `variables` from a black box `f`
+/// that evaluates the function at a point over a prime field, or returns `None` if the
+/// function is undefined there.
+///
+/// See [reconstruct_rational_functions] for the algorithm.
+pub fn reconstruct_rational_function<E: Exponent, F>(
+    f: F,
+    variables: Arc<Vec<Variable>>,
+) -> Result<RationalPolynomial<IntegerRing, E>, String>
+where
+    F: Fn(&Zp64, &[<Zp64 as Ring>::Element]) -> Option<<Zp64 as Ring>::Element>,
+{
+    reconstruct_rational_functions(
+        |field: &Zp64, x: &[<Zp64 as Ring>::Element]| f(field, x).map(|y| vec![y]),
+        variables,
+    )?
+    .pop()
+    .ok_or_else(|| "The black box did not return a value".to_owned())
+}
+
+/// Reconstruct several rational functions with rational coefficients in `variables` from a black box `f`
+/// that evaluates all functions at a point over a prime field at once, or returns `None` if a
+/// function is undefined there. This is more efficient than reconstructing every function separately
+/// when the functions share an expensive computation, such as the solution of a linear system.
+///
+/// The functions are reconstructed over several prime fields using [reconstruct_rational_functions_modular],
+/// after which the coefficients are lifted to the rationals using the Chinese remainder theorem
+/// and rational reconstruction. The result is accepted once it no longer changes when another prime is added.
+pub fn reconstruct_rational_functions<E: Exponent, F>(
+    f: F,
+    variables: Arc<Vec<Variable>>,
+) -> Result<Vec<RationalPolynomial<IntegerRing, E>>, String>
+where
+    F: Fn(&Zp64, &[<Zp64 as Ring>::Element]) -> Option<Vec<<Zp64 as Ring>::Element>>,
+{
+    let nvars = variables.len();
+
+    // the number of terms of every numerator and denominator, and their exponents
+    let mut shape: Option<(Vec<usize>, Vec<E>)> = None;
+    let mut images: Vec<Integer> = vec![];
+    let mut modulus = Integer::one();
+    let mut last_result: Option<Vec<Rational>> = None;
+
+    for p in LARGE_U64_PRIMES {
+        let field = Zp64::new(p);
+
+        let Some(rfs) = (0..MAX_ATTEMPTS).find_map(|_| {
+            reconstruct_rational_functions_modular::<E, F>(&f, &field, variables.clone())
+        }) else {
+            continue;
+        };
+
+        let mut nterms = Vec::with_capacity(2 * rfs.len());
+        let mut exponents = vec![];
+        let mut new_images = vec![];
+        for (num, den) in rfs {
+            for p in [num, den] {
+                nterms.push(p.nterms());
+                exponents.extend_from_slice(&p.exponents);
+                new_images.extend(
+                    p.coefficients
+                        .into_iter()
+                        .map(|c| Integer::from_finite_field(&field, c)),
+                );
+            }
+        }
+        let new_shape = (nterms, exponents);
+
+        // a prime for which terms are missing is unlucky
+        match &shape {
+            Some(s) if *s == new_shape => {
+                for (x, n) in images.iter_mut().zip(new_images) {
+                    *x = Integer::chinese_remainder(
+                        std::mem::replace(x, Integer::zero()),
+                        n,
+                        modulus.clone(),
+                        Integer::from(p),
+                    );
+                }
+                modulus *= &Integer::from(p);
+            }
+            Some(_) if images.len() >= new_images.len() => {
+                continue;
+            }
+            _ => {
+                shape = Some(new_shape);
+                images = new_images;
+                modulus = Integer::from(p);
+                last_result = None;
+            }
+        }
+
+        let result: Result<Vec<_>, _> = images
+            .iter()
+            .map(|x| Rational::maximal_quotient_reconstruction(x, &modulus, None))
+            .collect();
+
+        let Ok(result) = result else {
+            continue;
+        };
+
+        if last_result.as_ref() != Some(&result) {
+            last_result = Some(result);
+            continue;
+        }
+
+        let (nterms, exponents) = shape.unwrap();
+        let mut coeffs = result.into_iter().enumerate();
+        let mut polys = nterms.into_iter().map(|n| {
+            let mut poly = MultivariatePolynomial::new(&Q, Some(n), variables.clone());
+            for (i, c) in coeffs.by_ref().take(n) {
+                poly.append_monomial(c, &exponents[i * nvars..(i + 1) * nvars]);
+            }
+            poly
+        });
+
+        let mut res = vec![];
+        while let (Some(num), Some(den)) = (polys.next(), polys.next()) {
+            res.push(RationalPolynomial::from_num_den(num, den, &Z, true));
+        }
+        return Ok(res);
+    }
+
+    Err("Ran out of primes for rational function reconstruction".to_owned())
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use crate::{
+        atom::Atom,
+        domains::{
+            finite_field::{FiniteFieldCore, Zp64},
+            integer::Z,
+            rational::Q,
+            rational_polynomial::RationalPolynomial,
+            Field, Ring,
+        },
+        poly::Variable,
+        state::State,
+    };
+
+    use super::{reconstruct_rational_function, reconstruct_rational_functions};
+
+    #[test]
+    fn black_box() {
+        let vars = Arc::new(vec![
+            Variable::Symbol(State::get_symbol("v1")),
+            Variable::Symbol(State::get_symbol("v2")),
+            Variable::Symbol(State::get_symbol("v3")),
+        ]);
+
+        // evaluate (3*v1^2*v2 - v2*v3/2 + 7)/(v1*v2^2 + 5/3*v3^3 - 2)
+        let f = |field: &Zp64, x: &[<Zp64 as Ring>::Element]| {
+            let c = |n: u64| field.to_element(n);
+
+            let mut num = field.mul(&c(3), &field.mul(&field.mul(&x[0], &x[0]), &x[1]));
+            field.sub_assign(&mut num, &field.div(&field.mul(&x[1], &x[2]), &c(2)));
+            field.add_assign(&mut num, &c(7));
+
+            let mut den = field.mul(&x[0], &field.mul(&x[1], &x[1]));
+            let v3_cubed = field.pow(&x[2], 3);
+            field.add_assign(&mut den, &field.div(&field.mul(&c(5), &v3_cubed), &c(3)));
+            field.sub_assign(&mut den, &c(2));
+
+            if Zp64::is_zero(&den) {
+                None
+            } else {
+                Some(field.div(&num, &den))
+            }
+        };
+
+        let r: RationalPolynomial<_, u8> = reconstruct_rational_function(f, vars.clone()).unwrap();
+
+        let expected: RationalPolynomial<_, u8> =
+            Atom::parse("(3*v1^2*v2 - v2*v3/2 + 7)/(v1*v2^2 + 5/3*v3^3 - 2)")
+                .unwrap()
+                .to_rational_polynomial(&Q, &Z, Some(vars));
+
+        assert_eq!(r, expected);
+    }
+
+    #[test]
+    fn black_box_shared_samples() {
+        let vars = Arc::new(vec![
+            Variable::Symbol(State::get_symbol("v1")),
+            Variable::Symbol(State::get_symbol("v2")),
+        ]);
+
+        // evaluate (v1 + 2*v2)/(v1 - 3) and v1*v2^2/5 with a shared denominator
+        let f = |field: &Zp64, x: &[<Zp64 as Ring>::Element]| {
+            let den = field.sub(&x[0], &field.to_element(3));
+            if Zp64::is_zero(&den) {
+                return None;
+            }
+
+            let num = field.add(&x[0], &field.mul(&field.to_element(2), &x[1]));
+            let prod = field.mul(&x[0], &field.mul(&x[1], &x[1]));
+            Some(vec![
+                field.div(&num, &den),
+                field.div(&prod, &field.to_element(5)),
+            ])
+        };
+
+        let r: Vec<RationalPolynomial<_, u8>> =
+            reconstruct_rational_functions(f, vars.clone()).unwrap();
+
+        let expected: Vec<RationalPolynomial<_, u8>> = ["(v1 + 2*v2)/(v1 - 3)", "v1*v2^2/5"]
+            .iter()
+            .map(|x| {
+                Atom::parse(x)
+                    .unwrap()
+                    .to_rational_polynomial(&Q, &Z, Some(vars.clone()))
+            })
+            .collect();
+
+        assert_eq!(r, expected);
+    }
+}
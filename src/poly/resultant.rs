@@ -1,56 +1,140 @@
-use crate::domains::{EuclideanDomain, Field};
+use crate::domains::{
+    finite_field::{FiniteField, FiniteFieldCore, ToFiniteField},
+    integer::{FromFiniteField, Integer, IntegerRing, Z},
+    EuclideanDomain, Field,
+};
 
-use super::univariate::UnivariatePolynomial;
+use super::{
+    gcd::{PolynomialGCD, LARGE_U32_PRIMES},
+    polynomial::MultivariatePolynomial,
+    univariate::UnivariatePolynomial,
+    Exponent,
+};
 
 impl<F: EuclideanDomain> UnivariatePolynomial<F> {
-    /// Compute the resultant using Brown's polynomial remainder sequence algorithm.
+    /// Compute the resultant using Brown's subresultant polynomial remainder sequence algorithm.
     pub fn resultant_prs(&self, other: &Self) -> F::Element {
-        if self.degree() < other.degree() {
-            return other.resultant_prs(self);
+        if self.is_zero() || other.is_zero() {
+            return self.field.zero();
         }
 
         let mut a = self.clone();
-        let mut a_new = other.clone();
+        let mut b = other.clone();
+        let mut sign = false;
 
-        let mut deg = a.degree() as u64 - a_new.degree() as u64;
-        let mut neg_lc = self.field.one(); //unused
-        let mut init = false;
-        let mut beta = self.field.pow(&self.field.neg(&self.field.one()), deg + 1);
-        let mut psi = self.field.neg(&self.field.one());
-        while !a_new.is_constant() {
-            if init {
-                psi = if deg == 0 {
-                    // can only happen on the first iteration
-                    psi
-                } else if deg == 1 {
-                    neg_lc.clone()
-                } else {
-                    let a = self.field.pow(&neg_lc, deg);
-                    let psi_old = self.field.pow(&psi, deg - 1);
-                    let (q, r) = self.field.quot_rem(&a, &psi_old);
-                    debug_assert!(F::is_zero(&r));
-                    q
-                };
-
-                deg = a.degree() as u64 - a_new.degree() as u64;
-                beta = self.field.mul(&neg_lc, &self.field.pow(&psi, deg));
-            } else {
-                init = true;
+        if a.degree() < b.degree() {
+            std::mem::swap(&mut a, &mut b);
+            sign = a.degree() % 2 == 1 && b.degree() % 2 == 1;
+        }
+
+        let mut g = self.field.one();
+        let mut h = self.field.one();
+        while b.degree() > 0 {
+            let deg = (a.degree() - b.degree()) as u64;
+            if a.degree() % 2 == 1 && b.degree() % 2 == 1 {
+                sign = !sign;
+            }
+
+            let (_, r) = a
+                .mul_coeff(&self.field.pow(&b.lcoeff(), deg + 1))
+                .quot_rem(&b);
+            if r.is_zero() {
+                return self.field.zero();
             }
 
-            neg_lc = self.field.neg(&a_new.coefficients.last().unwrap());
+            let div = self.field.mul(&g, &self.field.pow(&h, deg));
+            (a, b) = (b, r.div_coeff(&div));
 
-            let (_, mut r) = a
-                .mul_coeff(&self.field.pow(&neg_lc, deg + 1))
-                .quot_rem(&a_new);
-            if (deg + 1) % 2 == 1 {
-                r = -r;
+            g = a.lcoeff();
+            h = if deg == 0 {
+                h
+            } else {
+                let (q, r) = self
+                    .field
+                    .quot_rem(&self.field.pow(&g, deg), &self.field.pow(&h, deg - 1));
+                debug_assert!(F::is_zero(&r));
+                q
+            };
+        }
+
+        // the last element of the sequence is a constant
+        let deg = a.degree() as u64;
+        let (mut res, r) = self.field.quot_rem(
+            &self.field.pow(&b.lcoeff(), deg),
+            &self.field.pow(&h, deg.saturating_sub(1)),
+        );
+        debug_assert!(F::is_zero(&r));
+
+        if sign {
+            res = self.field.neg(&res);
+        }
+        res
+    }
+
+    /// Compute the subresultant polynomial remainder sequence of the two polynomials, starting with
+    /// the polynomial of highest degree. Every next element is proportional to a subresultant
+    /// of decreasing degree and the last element is the last non-zero subresultant, which is
+    /// proportional to the gcd.
+    pub fn subresultants(&self, other: &Self) -> Vec<Self> {
+        let mut a = self.clone();
+        let mut b = other.clone();
+        if a.degree() < b.degree() {
+            std::mem::swap(&mut a, &mut b);
+        }
+
+        if b.is_zero() {
+            return vec![a];
+        }
+
+        let mut seq = vec![a.clone(), b.clone()];
+
+        let mut g = self.field.one();
+        let mut h = self.field.one();
+        while b.degree() > 0 {
+            let deg = (a.degree() - b.degree()) as u64;
+
+            let (_, r) = a
+                .mul_coeff(&self.field.pow(&b.lcoeff(), deg + 1))
+                .quot_rem(&b);
+            if r.is_zero() {
+                break;
             }
 
-            (a, a_new) = (a_new, r.div_coeff(&beta));
+            let div = self.field.mul(&g, &self.field.pow(&h, deg));
+            (a, b) = (b, r.div_coeff(&div));
+            seq.push(b.clone());
+
+            g = a.lcoeff();
+            h = if deg == 0 {
+                h
+            } else {
+                let (q, r) = self
+                    .field
+                    .quot_rem(&self.field.pow(&g, deg), &self.field.pow(&h, deg - 1));
+                debug_assert!(F::is_zero(&r));
+                q
+            };
+        }
+
+        seq
+    }
+
+    /// Compute the discriminant of the polynomial.
+    pub fn discriminant(&self) -> F::Element {
+        let d = self.degree();
+        if d == 0 {
+            return self.field.zero();
+        }
+
+        let r = self.resultant_prs(&self.derivative());
+        let (mut disc, rem) = self.field.quot_rem(&r, &self.lcoeff());
+        debug_assert!(F::is_zero(&rem));
+
+        if (d * (d - 1) / 2) % 2 == 1 {
+            disc = self.field.neg(&disc);
         }
 
-        a_new.coefficients.pop().unwrap()
+        disc
     }
 }
 
@@ -98,12 +182,155 @@ impl<F: Field> UnivariatePolynomial<F> {
     }
 }
 
+impl<R: EuclideanDomain + PolynomialGCD<E>, E: Exponent> MultivariatePolynomial<R, E> {
+    /// Compute the resultant of two polynomials with respect to the variable `var`,
+    /// using subresultants. For polynomials over the integers with many variables,
+    /// [`Self::resultant_modular`] may be faster.
+    pub fn resultant(&self, other: &Self, var: usize) -> Self {
+        self.to_univariate(var)
+            .resultant_prs(&other.to_univariate(var))
+    }
+
+    /// Compute the subresultant polynomial remainder sequence of two polynomials with respect
+    /// to the variable `var`.
+    pub fn subresultants(&self, other: &Self, var: usize) -> Vec<Self> {
+        self.to_univariate(var)
+            .subresultants(&other.to_univariate(var))
+            .into_iter()
+            .map(|p| p.to_multivariate())
+            .collect()
+    }
+
+    /// Compute the discriminant of the polynomial with respect to the variable `var`.
+    pub fn discriminant(&self, var: usize) -> Self {
+        self.to_univariate(var).discriminant()
+    }
+}
+
+impl<F: Field, E: Exponent> MultivariatePolynomial<F, E> {
+    /// Compute the resultant with respect to `var` by evaluating the variables `vars`
+    /// and interpolating the resultants of the images. Returns `None` when not enough
+    /// suitable sample points could be found.
+    fn resultant_dense(&self, other: &Self, var: usize, vars: &[usize]) -> Option<Self> {
+        if vars.is_empty() {
+            let r = self
+                .to_univariate_from_univariate(var)
+                .resultant_prs(&other.to_univariate_from_univariate(var));
+            return Some(self.constant(r));
+        }
+
+        let (y, rest) = (vars[0], &vars[1..]);
+        let (da, db) = (self.degree(var), other.degree(var));
+
+        // the degree of the resultant in y is bounded by the Sylvester matrix
+        let bound = da.to_u32() as usize * other.degree(y).to_u32() as usize
+            + db.to_u32() as usize * self.degree(y).to_u32() as usize;
+
+        let mut points = vec![];
+        let mut images = vec![];
+        let mut k = 0;
+        while points.len() <= bound {
+            k += 1;
+            if k > 2 * bound + 10 {
+                return None;
+            }
+
+            let v = self.field.nth(k as u64);
+            if points.contains(&v) {
+                return None;
+            }
+
+            // the leading coefficients may not vanish
+            let a = self.replace(y, &v);
+            let b = other.replace(y, &v);
+            if a.degree(var) != da || b.degree(var) != db {
+                continue;
+            }
+
+            images.push(a.resultant_dense(&b, var, rest)?);
+            points.push(v);
+        }
+
+        Some(Self::newton_interpolation(&points, &images, y))
+    }
+}
+
+impl<E: Exponent> MultivariatePolynomial<IntegerRing, E> {
+    /// Compute the resultant of two polynomials with respect to the variable `var`,
+    /// by computing resultants modulo primes using dense evaluation and interpolation,
+    /// and combining them using the Chinese remainder theorem.
+    ///
+    /// The number of primes is determined by a bound on the coefficients of the resultant,
+    /// as the Sylvester matrix has `deg(other)` rows with coefficients of `self` and `deg(self)`
+    /// rows with coefficients of `other`. If the bound exceeds the available primes,
+    /// the resultant is computed using subresultants.
+    pub fn resultant_modular(&self, other: &Self, var: usize) -> Self {
+        let (da, db) = (self.degree(var), other.degree(var));
+        let vars: Vec<_> = (0..self.nvars())
+            .filter(|v| *v != var && (self.degree(*v) > E::zero() || other.degree(*v) > E::zero()))
+            .collect();
+
+        let norm = |p: &Self| {
+            p.coefficients
+                .iter()
+                .fold(Integer::zero(), |acc, c| &acc + &c.abs())
+        };
+        let bound = &Integer::from(2)
+            * &(&norm(self).pow(db.to_u32() as u64) * &norm(other).pow(da.to_u32() as u64));
+
+        let mut res: Option<(Self, Integer)> = None;
+        for p in LARGE_U32_PRIMES {
+            let field = FiniteField::<u32>::new(p);
+            let a = self.map_coeff(|c| c.to_finite_field(&field), field.clone());
+            let b = other.map_coeff(|c| c.to_finite_field(&field), field.clone());
+
+            // skip primes that divide the leading coefficients
+            if a.degree(var) != da || b.degree(var) != db {
+                continue;
+            }
+
+            let Some(r) = a.resultant_dense(&b, var, &vars) else {
+                continue;
+            };
+
+            let Some((gm, m)) = &mut res else {
+                let m = Integer::from_prime(&field);
+                let gm = r.map_coeff(|c| field.to_symmetric_integer(c), Z);
+                if m > bound {
+                    return gm;
+                }
+
+                res = Some((gm, m));
+                continue;
+            };
+
+            // merge the images using Garner's algorithm
+            let diff = r - gm.map_coeff(|c| c.to_finite_field(&field), field.clone());
+            if !diff.is_zero() {
+                let m_inv = field.inv(&m.to_finite_field(&field));
+                let t = diff
+                    .mul_coeff(m_inv)
+                    .map_coeff(|c| field.to_symmetric_integer(c), Z);
+                *gm = &*gm + &t.mul_coeff(m.clone());
+            }
+
+            *m = &*m * &Integer::from_prime(&field);
+            if *m > bound {
+                return gm.clone();
+            }
+        }
+
+        self.resultant(other, var)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use crate::atom::Atom;
     use crate::domains::integer::Z;
     use crate::domains::rational::Q;
     use crate::poly::polynomial::MultivariatePolynomial;
+    use crate::state::State;
 
     #[test]
     fn resultant() {
@@ -119,6 +346,85 @@ mod test {
         assert_eq!(r, 11149673028381u64.into());
     }
 
+    #[test]
+    fn resultant_prs_degree_drop() {
+        let mut system = [
+            Atom::parse("a^3-2")
+                .unwrap()
+                .to_polynomial::<_, u8>(&Z, None),
+            Atom::parse("x^2+a*x+a^2")
+                .unwrap()
+                .to_polynomial::<_, u8>(&Z, None),
+        ];
+        MultivariatePolynomial::unify_variables_list(&mut system);
+
+        let var = system[0]
+            .get_vars_ref()
+            .iter()
+            .position(|v| *v == State::get_symbol("a").into())
+            .unwrap();
+
+        let r = system[0]
+            .to_univariate(var)
+            .resultant_prs(&system[1].to_univariate(var));
+
+        let res = Atom::parse("(x^3-2)^2")
+            .unwrap()
+            .expand()
+            .to_polynomial::<_, u8>(&Z, system[0].variables.clone().into());
+        assert_eq!(r, res);
+    }
+
+    #[test]
+    fn resultant_multivariate() {
+        let mut system = [
+            Atom::parse("x^3*y+2*x*z^2-y*z+3")
+                .unwrap()
+                .to_polynomial::<_, u8>(&Z, None),
+            Atom::parse("x^2*z-y^2*x+5*z-1")
+                .unwrap()
+                .to_polynomial::<_, u8>(&Z, None),
+        ];
+        MultivariatePolynomial::unify_variables_list(&mut system);
+
+        let var = system[0]
+            .get_vars_ref()
+            .iter()
+            .position(|v| *v == State::get_symbol("x").into())
+            .unwrap();
+
+        let r = system[0].resultant(&system[1], var);
+        assert_eq!(r.degree(var), 0);
+        assert_eq!(r, system[0].resultant_modular(&system[1], var));
+
+        let sub = system[0].subresultants(&system[1], var);
+        assert_eq!(sub.len(), 4);
+        assert_eq!(sub.last().unwrap(), &r);
+    }
+
+    #[test]
+    fn discriminant() {
+        let p = Atom::parse("v1^3-v1")
+            .unwrap()
+            .to_polynomial::<_, u8>(&Z, None)
+            .to_univariate_from_univariate(0);
+        assert_eq!(p.discriminant(), 4.into());
+
+        let p = Atom::parse("a*x^2+b*x+c")
+            .unwrap()
+            .to_polynomial::<_, u8>(&Z, None);
+        let var = p
+            .get_vars_ref()
+            .iter()
+            .position(|v| *v == State::get_symbol("x").into())
+            .unwrap();
+
+        let res = Atom::parse("b^2-4*a*c")
+            .unwrap()
+            .to_polynomial::<_, u8>(&Z, p.variables.clone().into());
+        assert_eq!(p.discriminant(var), res);
+    }
+
     #[test]
     fn resultant_prs_large() {
         let system = [
@@ -0,0 +1,676 @@
+use rug::Float as MultiPrecisionFloat;
+
+use crate::domains::{
+    float::{Complex, NumericalFloatComparison, NumericalFloatLike, Real},
+    integer::{Integer, IntegerRing},
+    rational::{Rational, RationalField, Q},
+    Ring,
+};
+
+use super::univariate::UnivariatePolynomial;
+
+impl UnivariatePolynomial<RationalField> {
+    /// Compute the square-free factorization of the polynomial using Yun's algorithm.
+    /// The factors are monic and are returned with their multiplicity. The constant factor is not included.
+    pub fn square_free_factorization(&self) -> Vec<(Self, usize)> {
+        if self.is_constant() {
+            return vec![];
+        }
+
+        let f = self.clone().make_monic();
+        let df = f.derivative();
+        let g = f.gcd(&df);
+
+        let mut b = &f / &g;
+        let mut d = &(&df / &g) - &b.derivative();
+
+        let mut factors = vec![];
+        let mut i = 1;
+        while !b.is_constant() {
+            let a = b.gcd(&d);
+            b = &b / &a;
+            let c = &d / &a;
+            d = &c - &b.derivative();
+
+            if !a.is_constant() {
+                factors.push((a, i));
+            }
+
+            i += 1;
+        }
+
+        factors
+    }
+
+    /// Compute the square-free part of the polynomial, normalized to be monic.
+    fn square_free_part(&self) -> Self {
+        let f = self.clone().make_monic();
+        &f / &f.gcd(&f.derivative())
+    }
+
+    /// Divide out the linear factor `x - r` if `r` is a root of the square-free polynomial.
+    fn remove_root(&self, r: &Rational) -> Self {
+        if self.evaluate(r).is_zero() {
+            let lin = &self.monomial(Rational::one(), 1) - &self.constant(r.clone());
+            self / &lin
+        } else {
+            self.clone()
+        }
+    }
+
+    /// Check if the square-free polynomial has a root in the open interval `(a, b)`
+    /// that is isolated from the other roots.
+    fn has_sign_change(&self, a: &Rational, b: &Rational) -> bool {
+        let p = self.remove_root(a).remove_root(b);
+        p.evaluate(a).is_negative() != p.evaluate(b).is_negative()
+    }
+
+    /// Isolate the real roots of the polynomial. Every root is returned as a disjoint
+    /// interval `(a, b)` together with its multiplicity, sorted in ascending order.
+    /// The root lies in the open interval `(a, b)`, unless `a == b`, in which case `a` is
+    /// the (rational) root itself.
+    ///
+    /// The roots are isolated using the Vincent-Collins-Akritas algorithm.
+    pub fn isolate_real_roots(&self) -> Vec<(Rational, Rational, usize)> {
+        assert!(
+            !self.is_zero(),
+            "Cannot isolate the roots of the zero polynomial"
+        );
+
+        let factors = self.square_free_factorization();
+        if factors.is_empty() {
+            return vec![];
+        }
+
+        // isolate the roots of the product of all square-free factors, so that
+        // the intervals are disjoint, and determine the multiplicity afterwards
+        let mut sqf = self.one();
+        for (f, _) in &factors {
+            sqf = &sqf * f;
+        }
+
+        let mut roots = vec![];
+        for (a, b) in isolate_square_free(&to_primitive_integer_coefficients(&sqf)) {
+            let mult = if a == b {
+                factors
+                    .iter()
+                    .find(|(f, _)| f.evaluate(&a).is_zero())
+                    .unwrap()
+                    .1
+            } else {
+                factors
+                    .iter()
+                    .find(|(f, _)| f.has_sign_change(&a, &b))
+                    .unwrap()
+                    .1
+            };
+
+            roots.push((a, b, mult));
+        }
+
+        roots
+    }
+
+    /// Refine the real root in the interval `(lower, upper)` to a floating point number with
+    /// `prec` bits of precision. The interval must contain exactly one real root, as is
+    /// the case for the intervals constructed by [`Self::isolate_real_roots`].
+    pub fn refine_real_root(
+        &self,
+        lower: &Rational,
+        upper: &Rational,
+        prec: u32,
+    ) -> MultiPrecisionFloat {
+        if lower == upper {
+            return lower.to_multi_prec_float(prec);
+        }
+
+        // the endpoints may be roots themselves
+        let p = self
+            .square_free_part()
+            .remove_root(lower)
+            .remove_root(upper);
+
+        let mut lower = lower.clone();
+        let mut upper = upper.clone();
+        let lower_negative = p.evaluate(&lower).is_negative();
+
+        // bisect until the width of the interval is small compared to the magnitude of the root
+        let two = Rational::from(2);
+        while lower.is_zero()
+            || upper.is_zero()
+            || lower.is_negative() != upper.is_negative()
+            || (&upper - &lower) * Rational::from(Integer::from(2).pow(prec as u64 + 1))
+                > std::cmp::min(lower.abs(), upper.abs())
+        {
+            let mid = (&lower + &upper) / &two;
+            let v = p.evaluate(&mid);
+            if v.is_zero() {
+                return mid.to_multi_prec_float(prec);
+            }
+
+            if v.is_negative() == lower_negative {
+                lower = mid;
+            } else {
+                upper = mid;
+            }
+        }
+
+        ((&lower + &upper) / &two).to_multi_prec_float(prec)
+    }
+
+    /// Compute all real roots of the polynomial with `prec` bits of precision,
+    /// together with their multiplicity, sorted in ascending order.
+    pub fn real_roots(&self, prec: u32) -> Vec<(MultiPrecisionFloat, usize)> {
+        self.isolate_real_roots()
+            .into_iter()
+            .map(|(a, b, m)| (self.refine_real_root(&a, &b, prec), m))
+            .collect()
+    }
+
+    /// Compute numerical approximations of all complex roots of the polynomial with `prec` bits of precision
+    /// using the Aberth-Ehrlich method. Every root is returned with an error estimate and its multiplicity,
+    /// which is determined exactly from the square-free factorization.
+    pub fn complex_roots(
+        &self,
+        prec: u32,
+        max_iterations: usize,
+    ) -> Result<Vec<ApproximateRoot<MultiPrecisionFloat>>, String> {
+        if self.is_zero() {
+            return Err("Cannot compute the roots of the zero polynomial".to_owned());
+        }
+
+        let tolerance = Rational::from((
+            Integer::one(),
+            Integer::from(2).pow(prec.saturating_sub(4) as u64),
+        ))
+        .to_multi_prec_float(prec);
+
+        let mut roots = vec![];
+        for (f, m) in self.square_free_factorization() {
+            for (r, err, m2) in
+                f.approximate_roots(|c| c.to_multi_prec_float(prec), max_iterations, &tolerance)?
+            {
+                roots.push((r, err, m * m2));
+            }
+        }
+
+        Ok(roots)
+    }
+}
+
+impl UnivariatePolynomial<IntegerRing> {
+    /// Isolate the real roots of the polynomial. Every root is returned as a disjoint
+    /// interval `(a, b)` together with its multiplicity, sorted in ascending order.
+    /// The root lies in the open interval `(a, b)`, unless `a == b`, in which case `a` is
+    /// the (rational) root itself.
+    pub fn isolate_real_roots(&self) -> Vec<(Rational, Rational, usize)> {
+        self.map_coeff(|c| c.into(), Q).isolate_real_roots()
+    }
+
+    /// Compute all real roots of the polynomial with `prec` bits of precision,
+    /// together with their multiplicity, sorted in ascending order.
+    pub fn real_roots(&self, prec: u32) -> Vec<(MultiPrecisionFloat, usize)> {
+        self.map_coeff(|c| c.into(), Q).real_roots(prec)
+    }
+}
+
+/// A numerical approximation of a root, an upper bound on its error and its multiplicity.
+pub type ApproximateRoot<T> = (Complex<T>, T, usize);
+
+impl<R: Ring> UnivariatePolynomial<R> {
+    /// Compute numerical approximations of all complex roots of the polynomial using the Aberth-Ehrlich method,
+    /// after converting the coefficients to floats with `to_float`, for example `|c| f64::from(c)` or
+    /// `|c| c.to_multi_prec_float(prec)` for rational coefficients. See [approximate_roots] for the
+    /// meaning of `tolerance` and the returned error estimates and multiplicities.
+    ///
+    /// For polynomials over the rationals, [UnivariatePolynomial::complex_roots] determines the
+    /// multiplicities exactly instead.
+    pub fn approximate_roots<T: Real + NumericalFloatComparison>(
+        &self,
+        to_float: impl Fn(&R::Element) -> T,
+        max_iterations: usize,
+        tolerance: &T,
+    ) -> Result<Vec<ApproximateRoot<T>>, String> {
+        let coeffs: Vec<_> = self.coefficients.iter().map(to_float).collect();
+        approximate_roots(&coeffs, max_iterations, tolerance)
+    }
+}
+
+/// Compute numerical approximations of all complex roots of the polynomial with coefficients `coeffs`,
+/// given in ascending order, using the Aberth-Ehrlich method. The iteration for a root stops when its
+/// correction is smaller than `tolerance` relative to the root, or when the polynomial vanishes
+/// up to the rounding error `tolerance`.
+///
+/// Every root is returned with an error estimate and its multiplicity. The error estimate is the radius of
+/// the inclusion disk of the root, which is guaranteed in exact arithmetic. Approximations whose inclusion disks
+/// overlap are clustered into a single root with a multiplicity.
+pub fn approximate_roots<T: Real + NumericalFloatComparison>(
+    coeffs: &[T],
+    max_iterations: usize,
+    tolerance: &T,
+) -> Result<Vec<ApproximateRoot<T>>, String> {
+    let Some(last) = coeffs.iter().rposition(|c| !c.is_zero()) else {
+        return Err("Cannot compute the roots of the zero polynomial".to_owned());
+    };
+
+    // roots at zero are exact
+    let zero_roots = coeffs.iter().position(|c| !c.is_zero()).unwrap();
+    let coeffs = &coeffs[zero_roots..=last];
+    let zero = coeffs[0].zero();
+    let one = coeffs[0].one();
+
+    let mut roots = vec![];
+    if zero_roots > 0 {
+        roots.push((
+            Complex::new(zero.clone(), zero.clone()),
+            zero.clone(),
+            zero_roots,
+        ));
+    }
+
+    let n = coeffs.len() - 1;
+    if n == 0 {
+        return Ok(roots);
+    }
+
+    let abs_coeffs: Vec<_> = coeffs.iter().map(|c| c.norm()).collect();
+
+    // evaluate the polynomial, its derivative and the rounding error bound at `z`
+    let eval = |z: &Complex<T>| {
+        let mut p = Complex::new(coeffs[n].clone(), zero.clone());
+        let mut dp = Complex::new(zero.clone(), zero.clone());
+        let mut bound = abs_coeffs[n].clone();
+        let abs_z = z.norm_squared().sqrt();
+        for k in (0..n).rev() {
+            dp = dp * z + &p;
+            p = p * z + Complex::new(coeffs[k].clone(), zero.clone());
+            bound = bound * &abs_z + &abs_coeffs[k];
+        }
+        (p, dp, bound)
+    };
+
+    // start on a circle with a radius equal to the geometric mean of the roots
+    let radius = (abs_coeffs[0].clone() / &abs_coeffs[n]).powf(one.clone() / one.from_usize(n));
+    let two_pi = zero.atan2(&-one.clone()) * one.from_usize(2);
+    let mut z: Vec<_> = (0..n)
+        .map(|k| {
+            let phi = two_pi.clone() * one.from_usize(k) / one.from_usize(n)
+                + one.from_usize(1) / one.from_usize(4);
+            Complex::from_polar_coordinates(radius.clone(), phi)
+        })
+        .collect();
+
+    let mut converged = vec![false; n];
+    for _ in 0..max_iterations {
+        if converged.iter().all(|c| *c) {
+            break;
+        }
+
+        for i in 0..n {
+            if converged[i] {
+                continue;
+            }
+
+            let (p, dp, bound) = eval(&z[i]);
+            if p.norm_squared().sqrt() <= tolerance.clone() * &bound {
+                converged[i] = true;
+                continue;
+            }
+
+            let ratio = p / &dp;
+            let mut sum = Complex::new(zero.clone(), zero.clone());
+            for (j, zj) in z.iter().enumerate() {
+                if j != i {
+                    sum += (&z[i] - zj).inv();
+                }
+            }
+
+            let w = &ratio / (Complex::new(one.clone(), zero.clone()) - ratio.clone() * &sum);
+            z[i] -= &w;
+
+            if w.norm_squared() <= tolerance.clone() * tolerance * &z[i].norm_squared() {
+                converged[i] = true;
+            }
+        }
+    }
+
+    if !converged.iter().all(|c| *c) {
+        return Err(format!(
+            "Root finding did not converge in {} iterations",
+            max_iterations
+        ));
+    }
+
+    // compute the radii of the inclusion disks
+    let radii: Vec<_> = (0..n)
+        .map(|i| {
+            let p = eval(&z[i]).0.norm_squared().sqrt();
+            if p.is_zero() {
+                return zero.clone();
+            }
+
+            let mut den = abs_coeffs[n].clone();
+            for (j, zj) in z.iter().enumerate() {
+                if j != i {
+                    den *= &(&z[i] - zj).norm_squared().sqrt();
+                }
+            }
+
+            p * one.from_usize(n) / den
+        })
+        .collect();
+
+    // cluster roots with overlapping inclusion disks
+    let mut cluster: Vec<_> = (0..n).collect();
+    for i in 0..n {
+        for j in i + 1..n {
+            if (&z[i] - &z[j]).norm_squared().sqrt() <= radii[i].clone() + &radii[j] {
+                let (ci, cj) = (cluster[i], cluster[j]);
+                for c in &mut cluster {
+                    if *c == cj {
+                        *c = ci;
+                    }
+                }
+            }
+        }
+    }
+
+    for c in 0..n {
+        let members: Vec<_> = (0..n).filter(|i| cluster[*i] == c).collect();
+        if members.is_empty() {
+            continue;
+        }
+
+        let mut center = Complex::new(zero.clone(), zero.clone());
+        for i in &members {
+            center += &z[*i];
+        }
+        center /= Complex::new(one.from_usize(members.len()), zero.clone());
+
+        let mut err = zero.clone();
+        for i in &members {
+            let e = (&center - &z[*i]).norm_squared().sqrt() + &radii[*i];
+            if e > err {
+                err = e;
+            }
+        }
+
+        roots.push((center, err, members.len()));
+    }
+
+    Ok(roots)
+}
+
+/// Convert a polynomial with rational coefficients to a primitive polynomial with integer
+/// coefficients, stored in ascending order.
+fn to_primitive_integer_coefficients(p: &UnivariatePolynomial<RationalField>) -> Vec<Integer> {
+    let mut lcm = Integer::one();
+    for c in &p.coefficients {
+        lcm = lcm.lcm(&c.denominator());
+    }
+
+    let mut coeffs: Vec<_> = p
+        .coefficients
+        .iter()
+        .map(|c| &(&lcm / &c.denominator()) * &c.numerator())
+        .collect();
+    make_primitive(&mut coeffs);
+    coeffs
+}
+
+/// Divide the coefficients by their content.
+fn make_primitive(coeffs: &mut [Integer]) {
+    let mut g = Integer::zero();
+    for c in coeffs.iter() {
+        g = g.gcd(c);
+        if g.is_one() {
+            return;
+        }
+    }
+
+    if !g.is_zero() {
+        for c in coeffs.iter_mut() {
+            *c = &*c / &g;
+        }
+    }
+}
+
+/// Compute `p(x+1)` in place.
+fn taylor_shift_one(coeffs: &mut [Integer]) {
+    let n = coeffs.len() - 1;
+    for i in 0..n {
+        for j in (i..n).rev() {
+            let c = coeffs[j + 1].clone();
+            coeffs[j] += &c;
+        }
+    }
+}
+
+/// Count the number of sign changes in the coefficient list, ignoring zeros.
+fn sign_variations(coeffs: &[Integer]) -> usize {
+    let mut last_negative = None;
+    let mut count = 0;
+    for c in coeffs {
+        if !c.is_zero() {
+            let neg = c.is_negative();
+            if last_negative.is_some_and(|l| l != neg) {
+                count += 1;
+            }
+            last_negative = Some(neg);
+        }
+    }
+    count
+}
+
+/// Isolate the real roots of a square-free polynomial with integer coefficients.
+fn isolate_square_free(coeffs: &[Integer]) -> Vec<(Rational, Rational)> {
+    let mut coeffs = coeffs.to_vec();
+
+    let mut roots = vec![];
+    if coeffs[0].is_zero() {
+        roots.push((Rational::zero(), Rational::zero()));
+        coeffs.remove(0);
+    }
+
+    if coeffs.len() > 1 {
+        roots.extend(isolate_positive(&coeffs));
+
+        let neg: Vec<_> = coeffs
+            .iter()
+            .enumerate()
+            .map(|(i, c)| if i % 2 == 1 { -c } else { c.clone() })
+            .collect();
+        roots.extend(isolate_positive(&neg).into_iter().map(|(a, b)| (-b, -a)));
+    }
+
+    roots.sort();
+    roots
+}
+
+/// Isolate the positive real roots of a square-free polynomial with integer coefficients
+/// that does not vanish at 0.
+fn isolate_positive(coeffs: &[Integer]) -> Vec<(Rational, Rational)> {
+    if sign_variations(coeffs) == 0 {
+        return vec![];
+    }
+
+    // find a power of two that bounds all roots using Cauchy's bound
+    let n = coeffs.len() - 1;
+    let lcoeff = coeffs[n].abs();
+    let max = coeffs[..n].iter().map(|c| c.abs()).max().unwrap();
+    let mut k = 0;
+    let mut bound = Integer::one();
+    while &(&bound - &Integer::one()) * &lcoeff <= max {
+        bound = &bound * &Integer::from(2);
+        k += 1;
+    }
+
+    // map the roots to the interval (0, 1)
+    let mut q: Vec<_> = coeffs
+        .iter()
+        .enumerate()
+        .map(|(i, c)| c * &Integer::from(2).pow((k * i) as u64))
+        .collect();
+    make_primitive(&mut q);
+
+    let interval = |c: &Integer, j: u64| Rational::from((c * &bound, Integer::from(2).pow(j)));
+
+    let mut roots = vec![];
+    let mut stack = vec![(q, Integer::zero(), 0)];
+    while let Some((q, c, j)) = stack.pop() {
+        // count the roots in (0, 1) using Descartes' rule of signs on (x+1)^n q(1/(x+1))
+        let mut r: Vec<_> = q.iter().rev().cloned().collect();
+        taylor_shift_one(&mut r);
+
+        match sign_variations(&r) {
+            0 => continue,
+            1 => {
+                roots.push((interval(&c, j), interval(&(&c + &Integer::one()), j)));
+                continue;
+            }
+            _ => {}
+        }
+
+        // bisect into 2^n q(x/2) and 2^n q((x+1)/2)
+        let n = q.len() - 1;
+        let mut left: Vec<_> = q
+            .iter()
+            .enumerate()
+            .map(|(i, x)| x * &Integer::from(2).pow((n - i) as u64))
+            .collect();
+        make_primitive(&mut left);
+
+        let mut right = left.clone();
+        taylor_shift_one(&mut right);
+
+        let c2 = &c * &Integer::from(2);
+        let c2_plus_one = &c2 + &Integer::one();
+        if right[0].is_zero() {
+            roots.push((interval(&c2_plus_one, j + 1), interval(&c2_plus_one, j + 1)));
+            right.remove(0);
+        }
+        make_primitive(&mut right);
+
+        stack.push((left, c2, j + 1));
+        stack.push((right, c2_plus_one, j + 1));
+    }
+
+    roots
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        atom::Atom,
+        domains::{
+            float::{Complex, NumericalFloatLike},
+            integer::Z,
+            rational::Q,
+        },
+    };
+
+    #[test]
+    fn isolate_real_roots() {
+        let p = Atom::parse("(x^2-2)*(x-1/2)^2*(x^3-x)*(2x-3)^3")
+            .unwrap()
+            .to_polynomial::<_, u8>(&Q, None)
+            .to_univariate_from_univariate(0);
+
+        let roots = p.isolate_real_roots();
+        assert_eq!(
+            roots.iter().map(|r| r.2).collect::<Vec<_>>(),
+            vec![1, 1, 1, 2, 1, 1, 3]
+        );
+
+        for w in roots.windows(2) {
+            assert!(w[0].1 <= w[1].0);
+        }
+
+        let exact = [-2f64.sqrt(), -1., 0., 0.5, 1., 2f64.sqrt(), 1.5];
+        for ((a, b, _), r) in roots.iter().zip(exact) {
+            assert!(f64::from(a) <= r && r <= f64::from(b));
+        }
+    }
+
+    #[test]
+    fn refine_real_roots() {
+        let p = Atom::parse("x^3-2")
+            .unwrap()
+            .to_polynomial::<_, u8>(&Z, None)
+            .to_univariate_from_univariate(0);
+
+        let roots = p.real_roots(200);
+        assert_eq!(roots.len(), 1);
+
+        let r = &roots[0].0;
+        let err = (r.clone() * r * r - 2u32).abs();
+        assert!(err < rug::Float::with_val(200, 1e-58));
+
+        // the isolating interval of 2^(1/2) has roots at its endpoints
+        let p = Atom::parse("(x^2-2)*(x^3-x)*(2x-3)^2")
+            .unwrap()
+            .to_polynomial::<_, u8>(&Z, None)
+            .to_univariate_from_univariate(0);
+
+        let roots = p.real_roots(100);
+        assert_eq!(roots.len(), 6);
+        let err = (roots[4].0.clone().square() - 2u32).abs();
+        assert!(err < rug::Float::with_val(100, 1e-28));
+        assert_eq!(roots[5].0, 1.5);
+        assert_eq!(roots[5].1, 2);
+    }
+
+    #[test]
+    fn approximate_roots() {
+        // (x-1)^2*(x^2+1)*(x-3)
+        let coeffs = [-3., 7., -8., 8., -5., 1.];
+        let mut roots = super::approximate_roots(&coeffs, 100, &1e-14).unwrap();
+        roots.sort_by(|a, b| (a.0.re, a.0.im).partial_cmp(&(b.0.re, b.0.im)).unwrap());
+
+        let expected = [(0., -1., 1), (0., 1., 1), (1., 0., 2), (3., 0., 1)];
+        assert_eq!(roots.len(), expected.len());
+        for ((r, err, m), (re, im, em)) in roots.iter().zip(expected) {
+            assert_eq!(*m, em);
+            assert!(*err < 1e-6);
+            assert!((r.re - re).abs() <= err + 1e-12 && (r.im - im).abs() <= err + 1e-12);
+        }
+
+        let p = Atom::parse("(x-1)^2*(x^2+1)*(x-3)")
+            .unwrap()
+            .to_polynomial::<_, u8>(&Q, None)
+            .to_univariate_from_univariate(0);
+        let mut roots = p.approximate_roots(|c| c.into(), 100, &1e-14).unwrap();
+        roots.sort_by(|a, b| (a.0.re, a.0.im).partial_cmp(&(b.0.re, b.0.im)).unwrap());
+        assert_eq!(
+            roots.iter().map(|r| r.2).collect::<Vec<_>>(),
+            vec![1, 1, 2, 1]
+        );
+        for ((r, err, _), (re, im, _)) in roots.iter().zip(expected) {
+            assert!((r.re - re).abs() <= err + 1e-12 && (r.im - im).abs() <= err + 1e-12);
+        }
+    }
+
+    #[test]
+    fn complex_roots() {
+        let p = Atom::parse("(x^3-2)*(x^2+1)^2")
+            .unwrap()
+            .to_polynomial::<_, u8>(&Q, None)
+            .to_univariate_from_univariate(0);
+
+        let roots = p.complex_roots(128, 100).unwrap();
+        assert_eq!(roots.len(), 5);
+        assert_eq!(roots.iter().map(|r| r.2).sum::<usize>(), 7);
+
+        for (r, err, m) in roots {
+            let v = if m == 1 {
+                r.clone() * &r * &r - Complex::new(r.re.from_i64(2), r.re.zero())
+            } else {
+                r.clone() * &r + Complex::new(r.re.one(), r.re.zero())
+            };
+            assert!(v.norm_squared() < 1e-70);
+            assert!(err < 1e-35);
+        }
+
+        assert!(p.zero().complex_roots(128, 100).is_err());
+    }
+}
@@ -430,10 +430,11 @@ impl<F: Ring> UnivariatePolynomial<F> {
     pub fn evaluate(&self, x: &F::Element) -> F::Element {
         let mut res = self.field.zero();
 
+        // the number of zero coefficients since the last non-zero coefficient
         let mut last_non_zero = 0;
         for c in self.coefficients.iter().rev() {
             if !F::is_zero(c) {
-                if last_non_zero == 1 {
+                if last_non_zero == 0 {
                     self.field.mul_assign(&mut res, x);
                 } else {
                     let p = self.field.pow(x, last_non_zero + 1); // TODO: cache powers?
@@ -450,7 +451,7 @@ impl<F: Ring> UnivariatePolynomial<F> {
         if last_non_zero == 1 {
             self.field.mul_assign(&mut res, x);
         } else if last_non_zero > 1 {
-            let p = self.field.pow(x, last_non_zero + 1);
+            let p = self.field.pow(x, last_non_zero);
             self.field.mul_assign(&mut res, &p);
         }
 
@@ -1011,6 +1012,106 @@ impl<F: Field> UnivariatePolynomial<F> {
     }
 }
 
+/// Thiele's continued-fraction interpolation of a univariate rational function,
+/// built up point by point from the inverse differences
+/// `f(x) = a_0 + (x - x_0) / (a_1 + (x - x_1) / (a_2 + ...))`.
+#[derive(Clone, Debug)]
+pub struct ThieleInterpolation<F: Field> {
+    field: F,
+    points: Vec<F::Element>,
+    coeffs: Vec<F::Element>,
+}
+
+impl<F: Field> ThieleInterpolation<F> {
+    /// Create a new interpolation without sample points.
+    pub fn new(field: F) -> ThieleInterpolation<F> {
+        ThieleInterpolation {
+            field,
+            points: vec![],
+            coeffs: vec![],
+        }
+    }
+
+    /// Get the number of sample points.
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    /// Returns `true` if there are no sample points.
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// Add the sample `f(x) = y`. Returns `false` and leaves the
+    /// interpolation unchanged if an inverse difference is undefined
+    /// at this point, in which case another sample point should be tried.
+    pub fn add_point(&mut self, x: F::Element, y: F::Element) -> bool {
+        let mut t = y;
+        for (xi, ai) in self.points.iter().zip(&self.coeffs) {
+            let d = self.field.sub(&t, ai);
+            if F::is_zero(&d) {
+                return false;
+            }
+            t = self.field.div(&self.field.sub(&x, xi), &d);
+        }
+
+        self.points.push(x);
+        self.coeffs.push(t);
+        true
+    }
+
+    /// Evaluate the interpolating function at `x`. Returns `None`
+    /// when a denominator of the continued fraction vanishes.
+    pub fn evaluate(&self, x: &F::Element) -> Option<F::Element> {
+        let mut r = self.coeffs.last()?.clone();
+        for (xi, ai) in self.points.iter().zip(&self.coeffs).rev().skip(1) {
+            if F::is_zero(&r) {
+                return None;
+            }
+            r = self
+                .field
+                .add(ai, &self.field.div(&self.field.sub(x, xi), &r));
+        }
+
+        Some(r)
+    }
+
+    /// Convert the continued fraction to a numerator and denominator in `variable`
+    /// that have no common factor. The denominator is monic.
+    pub fn to_rational_function(
+        &self,
+        variable: Arc<Variable>,
+    ) -> (UnivariatePolynomial<F>, UnivariatePolynomial<F>) {
+        let zero = UnivariatePolynomial::new(&self.field, None, variable);
+        let Some(last) = self.coeffs.last() else {
+            return (zero.clone(), zero.one());
+        };
+
+        let mut num = zero.constant(last.clone());
+        let mut den = zero.one();
+        for (xi, ai) in self.points.iter().zip(&self.coeffs).rev().skip(1) {
+            // a_i + (x - x_i) * den / num
+            let lin = zero.monomial(self.field.one(), 1) - zero.constant(xi.clone());
+            let new_num = num.clone().mul_coeff(ai) + lin * &den;
+            den = num;
+            num = new_num;
+        }
+
+        if num.is_zero() {
+            return (num, zero.one());
+        }
+
+        let g = num.gcd(&den);
+        if !g.is_one() {
+            num = num.quot_rem(&g).0;
+            den = den.quot_rem(&g).0;
+        }
+
+        let inv = self.field.inv(&den.lcoeff());
+        (num.mul_coeff(&inv), den.mul_coeff(&inv))
+    }
+}
+
 impl<R: Ring, E: Exponent> UnivariatePolynomial<PolynomialRing<R, E>> {
     // Convert from a univariate polynomial to a polynomial.
     pub fn to_multivariate(self) -> MultivariatePolynomial<R, E> {
@@ -1098,3 +1199,20 @@ fn test_uni() {
     let c = a.evaluate(&5.into());
     assert_eq!(c, 78178.into());
 }
+
+#[test]
+fn evaluate_sparse() {
+    use crate::atom::Atom;
+    use crate::domains::integer::Z;
+    let a = Atom::parse("x^5+2*x^2")
+        .unwrap()
+        .to_polynomial::<_, u8>(&Z, None)
+        .to_univariate_from_univariate(0);
+    assert_eq!(a.evaluate(&3.into()), 261.into());
+
+    let b = Atom::parse("x^7+x^6+3")
+        .unwrap()
+        .to_polynomial::<_, u8>(&Z, None)
+        .to_univariate_from_univariate(0);
+    assert_eq!(b.evaluate(&2.into()), 195.into());
+}
@@ -1,16 +1,194 @@
 use std::{ops::Neg, sync::Arc};
 
+use rug::Float as MultiPrecisionFloat;
+
 use crate::{
     atom::{Atom, AtomView, Symbol},
     domains::{
+        algebraic_number::{AlgebraicNumber, AlgebraicNumberRing},
+        finite_field::{FiniteFieldCore, ToFiniteField, Zp64},
+        float::Complex,
         integer::{IntegerRing, Z},
-        rational::Q,
+        rational::{Rational, RationalField, Q},
         rational_polynomial::{RationalPolynomial, RationalPolynomialField},
+        Field, Ring,
+    },
+    poly::{
+        factor::Factorize, gcd::LARGE_U64_PRIMES, groebner::GroebnerBasis,
+        polynomial::MultivariatePolynomial, reconstruct::reconstruct_rational_functions, Exponent,
+        GrevLexOrder, LexOrder, Variable,
     },
-    poly::{Exponent, Variable},
-    tensors::matrix::Matrix,
+    tensors::{matrix::Matrix, sparse::SparseLinearSystem},
 };
 
+/// The maximal number of linear forms that are tried to bring a zero-dimensional
+/// polynomial system in shape position.
+const MAX_SEPARATING_ATTEMPTS: i64 = 100;
+
+/// A triangular set of a zero-dimensional polynomial system, in which every variable is
+/// a polynomial in a root `a` of an irreducible minimal polynomial. Every root of the
+/// minimal polynomial yields a different solution of the system.
+#[derive(Clone, Debug)]
+pub struct TriangularSet {
+    /// The number field `Q[a]/m(a)`, where `m` is the monic minimal polynomial of `a`.
+    pub field: AlgebraicNumberRing<RationalField>,
+    /// The value of every variable, as an element of `field`.
+    pub values: Vec<AlgebraicNumber<RationalField>>,
+}
+
+impl TriangularSet {
+    /// Get the number of solutions described by the triangular set.
+    pub fn degree(&self) -> usize {
+        self.field.poly().degree(0) as usize
+    }
+
+    /// Express the solutions in terms of radicals, which is possible
+    /// when the minimal polynomial is linear or quadratic.
+    pub fn to_radicals(&self) -> Option<Vec<Vec<Atom>>> {
+        let m = self.field.poly().to_univariate_from_univariate(0);
+
+        let roots = match m.degree() {
+            1 => vec![Atom::new_num(m.coefficients[0].clone().neg())],
+            2 => {
+                // a^2 + b*a + c has roots -b/2 +- sqrt(b^2/4 - c)
+                let half_b = &m.coefficients[1] / &Rational::from(2);
+                let d = &(&half_b * &half_b) - &m.coefficients[0];
+                let sqrt_d = Atom::new_num(d).npow((1, 2));
+
+                vec![
+                    Atom::new_num(half_b.clone().neg()) + &sqrt_d,
+                    Atom::new_num(half_b.neg()) - &sqrt_d,
+                ]
+            }
+            _ => return None,
+        };
+
+        Some(
+            roots
+                .iter()
+                .map(|r| {
+                    self.values
+                        .iter()
+                        .map(|v| {
+                            let mut res = Atom::new_num(0);
+                            for t in &v.poly {
+                                res = res
+                                    + &(Atom::new_num(t.coefficient.clone())
+                                        * &r.npow(t.exponents[0] as i64));
+                            }
+                            res.expand()
+                        })
+                        .collect()
+                })
+                .collect(),
+        )
+    }
+
+    /// Compute numerical approximations of all solutions with `prec` bits of precision,
+    /// by computing the complex roots of the minimal polynomial using at most
+    /// `max_iterations` iterations of the Aberth-Ehrlich method.
+    pub fn to_numerical(
+        &self,
+        prec: u32,
+        max_iterations: usize,
+    ) -> Result<Vec<Vec<Complex<MultiPrecisionFloat>>>, String> {
+        let roots = self
+            .field
+            .poly()
+            .to_univariate_from_univariate(0)
+            .complex_roots(prec, max_iterations)?;
+
+        let zero = MultiPrecisionFloat::new(prec);
+        Ok(roots
+            .iter()
+            .map(|(r, _, _)| {
+                self.values
+                    .iter()
+                    .map(|v| {
+                        let mut res = Complex::new(zero.clone(), zero.clone());
+                        for c in v
+                            .poly
+                            .to_univariate_from_univariate(0)
+                            .coefficients
+                            .iter()
+                            .rev()
+                        {
+                            res = res * r + Complex::new(c.to_multi_prec_float(prec), zero.clone());
+                        }
+                        res
+                    })
+                    .collect()
+            })
+            .collect())
+    }
+}
+
+/// The non-zero coefficients of every equation of a linear system as `(column, coefficient)`
+/// pairs, and the right-hand side of every equation.
+pub(crate) type LinearCoefficients<E> = (
+    Vec<Vec<(u32, RationalPolynomial<IntegerRing, E>)>>,
+    Vec<RationalPolynomial<IntegerRing, E>>,
+);
+
+/// Extract the coefficients of `vars` from the expressions in `system`, which should be linear
+/// in `vars` and are understood to yield 0. The coefficients and the right-hand sides
+/// are rational functions in all other variables, with the same variable map.
+pub(crate) fn linear_coefficients<E: Exponent>(
+    system: &[AtomView],
+    vars: &[Symbol],
+) -> Result<LinearCoefficients<E>, String> {
+    let vars: Vec<_> = vars.iter().map(|v| Variable::Symbol(*v)).collect();
+
+    let mut rows = Vec::with_capacity(system.len());
+    let mut rhs = Vec::with_capacity(system.len());
+    for a in system {
+        let rat: RationalPolynomial<IntegerRing, E> = a.to_rational_polynomial(&Q, &Z, None);
+        let poly = rat.to_polynomial(&vars, true)?;
+
+        let mut row = vec![];
+        let mut constant = poly.field.zero();
+
+        // get linear coefficients
+        'next_monomial: for e in poly.into_iter() {
+            if e.exponents.iter().cloned().sum::<E>() > E::one() {
+                Err("Not a linear system")?;
+            }
+
+            for (col, p) in e.exponents.iter().enumerate() {
+                if !p.is_zero() {
+                    row.push((col as u32, e.coefficient.clone()));
+                    continue 'next_monomial;
+                }
+            }
+
+            // constant term
+            constant = e.coefficient.clone().neg();
+        }
+
+        rows.push(row);
+        rhs.push(constant);
+    }
+
+    let mut entries: Vec<_> = rows
+        .iter_mut()
+        .flatten()
+        .map(|(_, c)| c)
+        .chain(rhs.iter_mut())
+        .collect();
+
+    let Some((first, rest)) = entries.split_first_mut() else {
+        return Err("Empty system".to_owned());
+    };
+
+    for _ in 0..2 {
+        for x in &mut *rest {
+            first.unify_variables(x);
+        }
+    }
+
+    Ok((rows, rhs))
+}
+
 impl<'a> AtomView<'a> {
     /// Solve a system that is linear in `vars`, if possible.
     /// Each expression in `system` is understood to yield 0.
@@ -18,73 +196,290 @@ impl<'a> AtomView<'a> {
         system: &[AtomView],
         vars: &[Symbol],
     ) -> Result<Vec<Atom>, String> {
-        let vars: Vec<_> = vars.iter().map(|v| Variable::Symbol(*v)).collect();
+        let (rows, rhs) = linear_coefficients::<E>(system, vars)?;
+
+        let field = RationalPolynomialField::new(Z, rhs[0].numerator.get_vars());
+
+        let mut mat = vec![field.zero(); rows.len() * vars.len()];
+        for (i, row) in rows.into_iter().enumerate() {
+            for (col, c) in row {
+                mat[i * vars.len() + col as usize] = c;
+            }
+        }
+
+        let m =
+            Matrix::from_linear(mat, rhs.len() as u32, vars.len() as u32, field.clone()).unwrap();
+        let b = Matrix::new_vec(rhs, field);
+
+        let sol = match m.solve(&b) {
+            Ok(sol) => sol,
+            Err(e) => Err(format!("Could not solve {:?}", e))?,
+        };
 
-        let mut mat = Vec::with_capacity(system.len() * vars.len());
-        let mut row = vec![RationalPolynomial::<_, E>::new(&Z, Arc::new(vec![])); vars.len()];
-        let mut rhs = vec![RationalPolynomial::<_, E>::new(&Z, Arc::new(vec![])); vars.len()];
+        // replace the temporary variables
+        let mut result = Vec::with_capacity(vars.len());
+
+        for s in sol.data {
+            result.push(s.to_expression());
+        }
+
+        Ok(result)
+    }
 
-        for (si, a) in system.iter().enumerate() {
-            let rat: RationalPolynomial<IntegerRing, E> = a.to_rational_polynomial(&Q, &Z, None);
+    /// Solve a large sparse system that is linear in `vars`, if possible.
+    /// Each expression in `system` is understood to yield 0. The coefficients
+    /// may be rational functions in any other variables.
+    ///
+    /// Instead of performing Gaussian elimination over rational functions, the system
+    /// is solved with structured sparse elimination over prime fields, for numerical values of the parameters.
+    /// All unknowns are recovered from these solutions at once with [reconstruct_rational_functions].
+    pub fn solve_linear_system_sparse<E: Exponent>(
+        system: &[AtomView],
+        vars: &[Symbol],
+    ) -> Result<Vec<Atom>, String> {
+        let nvars = vars.len();
+        let (rows, rhs) = linear_coefficients::<E>(system, vars)?;
 
-            let poly = rat.to_polynomial(&vars, true).unwrap();
+        // a system without parameters is reconstructed as a constant function
+        let mut params = rhs[0].numerator.get_vars();
+        if params.is_empty() {
+            params = Arc::new(vec![Variable::Temporary(0)]);
+        }
 
-            for e in &mut row {
-                *e = RationalPolynomial::<_, E>::new(&Z, poly.variables.clone());
+        let eval = |p: &MultivariatePolynomial<IntegerRing, E>,
+                    field: &Zp64,
+                    x: &[<Zp64 as Ring>::Element]| {
+            let mut r = field.zero();
+            for t in p {
+                let mut c = t.coefficient.to_finite_field(field);
+                for (xi, e) in x.iter().zip(t.exponents) {
+                    if !e.is_zero() {
+                        field.mul_assign(&mut c, &field.pow(xi, e.to_u32() as u64));
+                    }
+                }
+                field.add_assign(&mut r, &c);
             }
+            r
+        };
 
-            // get linear coefficients
-            'next_monomial: for e in poly.into_iter() {
-                if e.exponents.iter().cloned().sum::<E>() > E::one() {
-                    Err("Not a linear system")?;
+        // solve the system at a sample point, returning `None` when a coefficient is singular
+        let sample = |field: &Zp64, x: &[<Zp64 as Ring>::Element]| {
+            let value = |c: &RationalPolynomial<IntegerRing, E>| {
+                let den = eval(&c.denominator, field, x);
+                if Zp64::is_zero(&den) {
+                    None
+                } else {
+                    Some(field.div(&eval(&c.numerator, field, x), &den))
                 }
+            };
 
-                for (rv, p) in row.iter_mut().zip(e.exponents) {
-                    if !p.is_zero() {
-                        *rv = e.coefficient.clone();
-                        continue 'next_monomial;
-                    }
+            let mut s = SparseLinearSystem::new(nvars as u32, field.clone());
+            for (r, b) in rows.iter().zip(&rhs) {
+                let mut row = Vec::with_capacity(r.len());
+                for (col, c) in r {
+                    row.push((*col, value(c)?));
+                }
+                s.add_row(row, value(b)?);
+            }
+            Some(s.solve())
+        };
+
+        // check that the system has a unique solution at a random point, as
+        // the reconstruction would otherwise only fail after exhausting all primes
+        let field = Zp64::new(LARGE_U64_PRIMES[0]);
+        let mut rng = rand::thread_rng();
+        let mut solvable = Err("Could not evaluate the coefficients".to_owned());
+        for _ in 0..5 {
+            let x: Vec<_> = params
+                .iter()
+                .map(|_| field.sample(&mut rng, (1, i64::MAX)))
+                .collect();
+
+            match sample(&field, &x) {
+                Some(Ok(_)) => {
+                    solvable = Ok(());
+                    break;
                 }
+                Some(Err(e)) => solvable = Err(format!("Could not solve {:?}", e)),
+                None => {}
+            }
+        }
+        solvable?;
 
-                // constant term
-                rhs[si] = e.coefficient.clone().neg();
+        // every sample solves the system once, which is shared by all unknowns
+        let r: Vec<RationalPolynomial<IntegerRing, E>> = reconstruct_rational_functions(
+            |field: &Zp64, x: &[<Zp64 as Ring>::Element]| sample(field, x)?.ok(),
+            params,
+        )?;
+
+        Ok(r.into_iter().map(|r| r.to_expression()).collect())
+    }
+
+    /// Solve a polynomial system in `vars` that has finitely many solutions.
+    /// Each expression in `system` is understood to yield 0.
+    ///
+    /// The solutions are returned as a list of triangular sets, whose values are expressed in
+    /// a root of an irreducible polynomial in the variable `primitive_element`, which should not appear
+    /// in the system. The solutions can be obtained in terms of radicals with [TriangularSet::to_radicals]
+    /// or numerically with [TriangularSet::to_numerical].
+    ///
+    /// The Groebner basis of the radical of the ideal, extended by the
+    /// separating element `primitive_element - x_1 - c*x_2 - c^2*x_3 - ...`, is converted to
+    /// the lexicographical order using FGLM. For a suitable `c`, this basis is in shape position:
+    /// every variable is a polynomial in the separating element, which is a root of a univariate polynomial.
+    /// Factoring this polynomial yields the triangular sets.
+    ///
+    /// An error is returned if no suitable `c` is found within a fixed number of attempts.
+    pub fn solve_polynomial_system<E: Exponent + Send + Sync>(
+        system: &[AtomView],
+        vars: &[Symbol],
+        primitive_element: Symbol,
+    ) -> Result<Vec<TriangularSet>, String> {
+        let n = vars.len();
+        let var_map: Arc<Vec<_>> = Arc::new(vars.iter().map(|v| Variable::Symbol(*v)).collect());
+
+        let mut ideal = Vec::with_capacity(system.len());
+        for a in system {
+            let poly: MultivariatePolynomial<_, E> =
+                a.expand().to_polynomial(&Q, Some(var_map.clone()));
+            if poly.nvars() != n {
+                Err(format!("{} is not a polynomial in the variables", a))?;
             }
+            ideal.push(poly.reorder::<GrevLexOrder>());
+        }
 
-            mat.extend_from_slice(&row);
+        let mut gb = GroebnerBasis::new_modular(&ideal, false);
+        if gb.system.iter().any(|p| p.is_constant()) {
+            return Ok(vec![]);
         }
 
-        let Some((first, rest)) = mat.split_first_mut() else {
-            return Err("Empty system".to_owned());
-        };
+        if !gb.is_zero_dimensional() {
+            Err("The system does not have finitely many solutions")?;
+        }
 
-        for _ in 0..2 {
-            for x in &mut *rest {
-                first.unify_variables(x);
+        // make the ideal radical by adding the square-free part of the univariate polynomial
+        // in every variable (Seidenberg's lemma)
+        let mut radical = false;
+        for v in 0..n {
+            let u = gb.univariate_polynomial(v).unwrap().reorder::<LexOrder>();
+            let mut sqf = u.one();
+            for (f, _) in u.square_free_factorization() {
+                sqf = &sqf * &f;
             }
-            for x in &mut rhs {
-                first.unify_variables(x);
+
+            if sqf.degree(v) < u.degree(v) {
+                ideal.push(sqf.reorder::<GrevLexOrder>());
+                radical = true;
             }
         }
 
-        let field = RationalPolynomialField::new(Z, rhs[0].numerator.get_vars().into());
+        if radical {
+            gb = GroebnerBasis::new_modular(&ideal, false);
+        }
 
-        let nrows = (mat.len() / rhs.len()) as u32;
-        let m = Matrix::from_linear(mat, nrows, rhs.len() as u32, field.clone()).unwrap();
-        let b = Matrix::new_vec(rhs, field);
+        // append the primitive element as the last variable
+        let mut ext_var_map = (*var_map).clone();
+        ext_var_map.push(Variable::Symbol(primitive_element));
+        let ext_var_map = Arc::new(ext_var_map);
+
+        let mut ext_ideal = vec![];
+        for p in &gb.system {
+            let mut ext: MultivariatePolynomial<_, E, GrevLexOrder> =
+                MultivariatePolynomial::new(&Q, Some(p.nterms()), ext_var_map.clone());
+            let mut exp = vec![E::zero(); n + 1];
+            for t in p {
+                exp[..n].copy_from_slice(t.exponents);
+                ext.append_monomial(t.coefficient.clone(), &exp);
+            }
+            ext_ideal.push(ext);
+        }
 
-        let sol = match m.solve(&b) {
-            Ok(sol) => sol,
-            Err(e) => Err(format!("Could not solve {:?}", e))?,
+        let mut exp = vec![E::zero(); n + 1];
+        let mut c: i64 = 1;
+        let lex = loop {
+            if c > MAX_SEPARATING_ATTEMPTS {
+                Err("Could not find a separating linear form")?;
+            }
+
+            let mut separating = MultivariatePolynomial::new(&Q, None, ext_var_map.clone());
+            exp[n] = E::one();
+            separating.append_monomial(Q.one(), &exp);
+            exp[n] = E::zero();
+
+            let mut coeff = Rational::one();
+            for v in 0..n {
+                exp[v] = E::one();
+                separating.append_monomial(coeff.clone().neg(), &exp);
+                exp[v] = E::zero();
+                coeff = &coeff * &Rational::from(c);
+            }
+
+            let mut sys = ext_ideal.clone();
+            sys.push(separating);
+
+            let lex = GroebnerBasis::new_modular(&sys, false)
+                .fglm::<LexOrder>()
+                .ok_or("The system does not have finitely many solutions")?;
+
+            // check if the basis is in shape position
+            if lex.system.len() == n + 1
+                && (0..n).all(|v| {
+                    lex.system[v].max_exp().iter().enumerate().all(|(i, e)| {
+                        if i == v {
+                            *e == E::one()
+                        } else {
+                            e.is_zero()
+                        }
+                    })
+                })
+            {
+                break lex;
+            }
+
+            c += 1;
         };
 
-        // replace the temporary variables
-        let mut result = Vec::with_capacity(vars.len());
+        let mut sets = vec![];
+        for (f, _) in lex.system[n].factor() {
+            if f.is_constant() {
+                continue;
+            }
 
-        for s in sol.data {
-            result.push(s.to_expression());
+            if f.degree(n).to_u32() > u8::MAX as u32 {
+                Err("The degree of the minimal polynomial is too high")?;
+            }
+
+            let mut min_poly = MultivariatePolynomial::new(
+                &Q,
+                None,
+                Arc::new(vec![Variable::Symbol(primitive_element)]),
+            );
+            for t in &f {
+                min_poly.append_monomial(t.coefficient.clone(), &[t.exponents[n].to_u32() as u8]);
+            }
+
+            let field = AlgebraicNumberRing::new(min_poly.make_monic());
+
+            let mut values = Vec::with_capacity(n);
+            for p in &lex.system[..n] {
+                // the polynomial is of the form x_i - g_i(a)
+                let mut g = field.poly().zero();
+                for t in p {
+                    if t.exponents[..n].iter().all(|e| e.is_zero()) {
+                        g.append_monomial(
+                            t.coefficient.clone().neg(),
+                            &[t.exponents[n].to_u32() as u8],
+                        );
+                    }
+                }
+
+                values.push(field.to_element(g));
+            }
+
+            sets.push(TriangularSet { field, values });
         }
 
-        Ok(result)
+        Ok(sets)
     }
 }
 
@@ -133,6 +528,47 @@ mod test {
         assert_eq!(sol, res);
     }
 
+    #[test]
+    fn solve_sparse() {
+        let x = State::get_symbol("v1");
+        let y = State::get_symbol("v2");
+        let z = State::get_symbol("v3");
+        let eqs = [
+            "v4*v1 + 2*v2 + v3 - 1",
+            "v1 + v4*v2 + v3/v4 - 2",
+            "(v4-1)v1 + v4*v3",
+            "3/7*v1 - v2",
+        ];
+
+        let atoms: Vec<_> = eqs.iter().map(|e| Atom::parse(e).unwrap()).collect();
+        let system: Vec<_> = atoms.iter().map(|x| x.as_view()).collect();
+
+        let sol = AtomView::solve_linear_system_sparse::<u8>(&system[..3], &[x, y, z]).unwrap();
+        let dense_sol = AtomView::solve_linear_system::<u8>(&system[..3], &[x, y, z]).unwrap();
+        assert_eq!(sol, dense_sol);
+
+        let sol = AtomView::solve_linear_system_sparse::<u8>(&system[1..], &[x, y, z]).unwrap();
+        let dense_sol = AtomView::solve_linear_system::<u8>(&system[1..], &[x, y, z]).unwrap();
+        assert_eq!(sol, dense_sol);
+
+        // multiple parameters and no parameters
+        for eqs in [
+            [
+                "v4*v1 + v5*v2 - 1",
+                "v1 - v5*v2 + v3 - v4",
+                "v1 + v2/v4 + v5^2*v3 - 2",
+            ],
+            ["v1 + v2 - 3", "v1 - v2 - 1/2", "v3 - 2*v1"],
+        ] {
+            let atoms: Vec<_> = eqs.iter().map(|e| Atom::parse(e).unwrap()).collect();
+            let system: Vec<_> = atoms.iter().map(|x| x.as_view()).collect();
+
+            let sol = AtomView::solve_linear_system_sparse::<u8>(&system, &[x, y, z]).unwrap();
+            let dense_sol = AtomView::solve_linear_system::<u8>(&system, &[x, y, z]).unwrap();
+            assert_eq!(sol, dense_sol);
+        }
+    }
+
     #[test]
     fn solve_from_matrix() {
         let system = [
@@ -194,4 +630,49 @@ mod test {
 
         assert_eq!(sol.data, res);
     }
+
+    #[test]
+    fn solve_polynomial_system() {
+        let x = State::get_symbol("v1");
+        let y = State::get_symbol("v2");
+        let a = State::get_symbol("v5");
+
+        let eqs = ["v1^2 + v2^2 - 5", "v1*v2 - 2"];
+        let atoms: Vec<_> = eqs.iter().map(|e| Atom::parse(e).unwrap()).collect();
+        let system: Vec<_> = atoms.iter().map(|x| x.as_view()).collect();
+
+        let sets = AtomView::solve_polynomial_system::<u16>(&system, &[x, y], a).unwrap();
+        assert_eq!(sets.iter().map(|s| s.degree()).sum::<usize>(), 4);
+
+        let sols: Vec<_> = sets.iter().flat_map(|s| s.to_radicals().unwrap()).collect();
+        assert_eq!(sols.len(), 4);
+
+        for r in [["1", "2"], ["2", "1"], ["-1", "-2"], ["-2", "-1"]] {
+            let r: Vec<_> = r.iter().map(|x| Atom::parse(x).unwrap()).collect();
+            assert!(sols.contains(&r));
+        }
+    }
+
+    #[test]
+    fn solve_polynomial_system_numerical() {
+        let x = State::get_symbol("v1");
+        let y = State::get_symbol("v2");
+        let a = State::get_symbol("v5");
+
+        let eqs = ["v1^3 - v1 - 1", "v2 - v1^2"];
+        let atoms: Vec<_> = eqs.iter().map(|e| Atom::parse(e).unwrap()).collect();
+        let system: Vec<_> = atoms.iter().map(|x| x.as_view()).collect();
+
+        let sets = AtomView::solve_polynomial_system::<u16>(&system, &[x, y], a).unwrap();
+        assert_eq!(sets.len(), 1);
+        assert_eq!(sets[0].degree(), 3);
+        assert!(sets[0].to_radicals().is_none());
+
+        let sols = sets[0].to_numerical(64, 100).unwrap();
+        assert_eq!(sols.len(), 3);
+        for s in &sols {
+            let e = s[1].clone() - s[0].clone() * &s[0];
+            assert!(e.norm_squared().to_f64() < 1e-20);
+        }
+    }
 }
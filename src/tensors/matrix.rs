@@ -2,10 +2,23 @@ use std::{
     fmt::Display,
     ops::{Add, Index, IndexMut, Mul, Neg, Sub},
     slice::Chunks,
+    sync::Arc,
 };
 
 use crate::{
-    domains::{EuclideanDomain, Field, Ring},
+    domains::{
+        algebraic_number::AlgebraicNumberRing,
+        integer::{Integer, IntegerRing, Z},
+        rational::{Rational, RationalField, Q},
+        rational_polynomial::{
+            FromNumeratorAndDenominator, RationalPolynomial, RationalPolynomialField,
+        },
+        EuclideanDomain, Field, Ring,
+    },
+    poly::{
+        factor::Factorize, polynomial::MultivariatePolynomial, univariate::UnivariatePolynomial,
+        Exponent, Variable,
+    },
     printer::MatrixPrinter,
 };
 
@@ -201,6 +214,89 @@ impl<F: Ring> Matrix<F> {
             field,
         }
     }
+
+    /// Compute the trace of a square matrix, i.e. the sum of the entries on the main diagonal.
+    pub fn trace(&self) -> Result<F::Element, MatrixError<F>> {
+        if self.nrows != self.ncols {
+            Err(MatrixError::NotSquare)?;
+        }
+
+        let mut t = self.field.zero();
+        for i in 0..self.nrows {
+            self.field.add_assign(&mut t, &self[(i, i)]);
+        }
+        Ok(t)
+    }
+
+    /// Compute the `n`th power of a square matrix using binary exponentiation.
+    pub fn pow(&self, mut n: u64) -> Result<Matrix<F>, MatrixError<F>> {
+        if self.nrows != self.ncols {
+            Err(MatrixError::NotSquare)?;
+        }
+
+        let mut res = Matrix::identity(self.nrows, self.field.clone());
+        let mut x = self.clone();
+        while n > 0 {
+            if n % 2 == 1 {
+                res = &res * &x;
+            }
+
+            n /= 2;
+            if n > 0 {
+                x = &x * &x;
+            }
+        }
+
+        Ok(res)
+    }
+
+    /// Compute the Kronecker product of `self` and `other`, which is the block matrix
+    /// in which block `(i, j)` is `other` multiplied by the entry `(i, j)` of `self`.
+    pub fn kronecker_product(&self, other: &Matrix<F>) -> Matrix<F> {
+        let mut m = Matrix::new(
+            self.nrows * other.nrows,
+            self.ncols * other.ncols,
+            self.field.clone(),
+        );
+
+        for i in 0..self.nrows {
+            for j in 0..self.ncols {
+                let a = &self[(i, j)];
+                if F::is_zero(a) {
+                    continue;
+                }
+
+                for k in 0..other.nrows {
+                    for l in 0..other.ncols {
+                        m[(i * other.nrows + k, j * other.ncols + l)] =
+                            self.field.mul(a, &other[(k, l)]);
+                    }
+                }
+            }
+        }
+
+        m
+    }
+
+    /// Swap row `i` and row `j`.
+    pub fn swap_rows(&mut self, i: u32, j: u32) {
+        if i != j {
+            for l in 0..self.ncols {
+                self.data
+                    .swap((i * self.ncols + l) as usize, (j * self.ncols + l) as usize);
+            }
+        }
+    }
+
+    /// Swap column `i` and column `j`.
+    pub fn swap_cols(&mut self, i: u32, j: u32) {
+        if i != j {
+            for r in 0..self.nrows {
+                self.data
+                    .swap((r * self.ncols + i) as usize, (r * self.ncols + j) as usize);
+            }
+        }
+    }
 }
 
 impl<F: Ring> Index<u32> for Matrix<F> {
@@ -321,6 +417,7 @@ impl<F: Ring> Neg for Matrix<F> {
 
 /// Errors that can occur when performing matrix operations.
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum MatrixError<F: Ring> {
     Underdetermined {
         min_rank: u32,
@@ -332,6 +429,8 @@ pub enum MatrixError<F: Ring> {
     Singular,
     ShapeMismatch,
     RightHandSideIsNotVector,
+    /// An irreducible factor of the characteristic polynomial has a degree above 255.
+    DegreeTooLarge,
 }
 
 impl<F: Ring> std::fmt::Display for MatrixError<F> {
@@ -359,6 +458,10 @@ impl<F: Ring> std::fmt::Display for MatrixError<F> {
             MatrixError::RightHandSideIsNotVector => {
                 write!(f, "The right-hand side is not a vector")
             }
+            MatrixError::DegreeTooLarge => write!(
+                f,
+                "The degree of an irreducible factor of the characteristic polynomial is too large"
+            ),
         }
     }
 }
@@ -401,6 +504,392 @@ impl<F: EuclideanDomain> Matrix<F> {
             self.div_scalar(&c)
         }
     }
+
+    /// Transform the matrix in-place into row echelon form using fraction-free Bareiss elimination,
+    /// where only the first `max_col` columns are used for pivots. All intermediate
+    /// entries are minors of the original matrix, so that no fractions appear and entries do not grow needlessly.
+    /// Returns the columns of the pivots.
+    pub fn row_reduce_fraction_free(&mut self, max_col: u32) -> Vec<u32> {
+        self.bareiss(max_col).0
+    }
+
+    /// Perform Bareiss elimination and return the pivots and whether an odd number of rows was swapped.
+    fn bareiss(&mut self, max_col: u32) -> (Vec<u32>, bool) {
+        let zero = self.field.zero();
+        let mut pivots = vec![];
+        let mut odd_swaps = false;
+        let mut prev = self.field.one();
+
+        let mut i = 0;
+        for j in 0..max_col.min(self.ncols) {
+            if i == self.nrows {
+                break;
+            }
+
+            let Some(k) = (i..self.nrows).find(|k| !F::is_zero(&self[(*k, j)])) else {
+                continue;
+            };
+            if k != i {
+                self.swap_rows(i, k);
+                odd_swaps = !odd_swaps;
+            }
+
+            for r in i + 1..self.nrows {
+                for l in j + 1..self.ncols {
+                    let mut e = self.field.mul(&self[(i, j)], &self[(r, l)]);
+                    self.field
+                        .sub_mul_assign(&mut e, &self[(r, j)], &self[(i, l)]);
+
+                    let (q, rem) = self.field.quot_rem(&e, &prev);
+                    debug_assert!(F::is_zero(&rem));
+                    self[(r, l)] = q;
+                }
+                self[(r, j)] = zero.clone();
+            }
+
+            prev = self[(i, j)].clone();
+            pivots.push(j);
+            i += 1;
+        }
+
+        (pivots, odd_swaps)
+    }
+
+    /// Compute the determinant of the matrix using fraction-free Bareiss elimination,
+    /// which only performs exact divisions in the ring.
+    pub fn det_fraction_free(&self) -> Result<F::Element, MatrixError<F>> {
+        if self.nrows != self.ncols {
+            Err(MatrixError::NotSquare)?;
+        }
+
+        if self.nrows == 0 {
+            return Err(MatrixError::Singular);
+        }
+
+        let mut m = self.clone();
+        let (pivots, odd_swaps) = m.bareiss(self.ncols);
+
+        if pivots.len() < self.nrows as usize {
+            return Ok(self.field.zero());
+        }
+
+        let d = m[(self.nrows - 1, self.ncols - 1)].clone();
+        if odd_swaps {
+            Ok(self.field.neg(&d))
+        } else {
+            Ok(d)
+        }
+    }
+
+    /// Compute the Hermite normal form `H` of the matrix, together with a unimodular
+    /// transformation matrix `U` such that `U * A = H`. The matrix `H` is in row echelon form,
+    /// its pivots are normalized and the entries above each pivot are reduced modulo the pivot.
+    pub fn hermite_normal_form(&self) -> (Matrix<F>, Matrix<F>) {
+        let mut h = self.clone();
+        let mut u = Matrix::identity(self.nrows, self.field.clone());
+
+        let mut i = 0;
+        for j in 0..self.ncols {
+            if i == self.nrows {
+                break;
+            }
+
+            if !h.eliminate_column(i, j, &mut u) {
+                continue;
+            }
+
+            let unit = unit_normalization(&self.field, &h[(i, j)]);
+            if !self.field.is_one(&unit) {
+                h.mul_row(i, &unit);
+                u.mul_row(i, &unit);
+            }
+
+            for k in 0..i {
+                let (q, _) = self.field.quot_rem(&h[(k, j)], &h[(i, j)]);
+                if !F::is_zero(&q) {
+                    h.sub_mul_row(k, i, &q);
+                    u.sub_mul_row(k, i, &q);
+                }
+            }
+
+            i += 1;
+        }
+
+        (h, u)
+    }
+
+    /// Compute the Smith normal form `S` of the matrix, together with unimodular
+    /// transformation matrices `U` and `V` such that `U * A * V = S`. The matrix `S` is diagonal,
+    /// with normalized entries such that each diagonal entry divides the next.
+    pub fn smith_normal_form(&self) -> (Matrix<F>, Matrix<F>, Matrix<F>) {
+        let mut s = self.clone();
+        let mut u = Matrix::identity(self.nrows, self.field.clone());
+        let mut v = Matrix::identity(self.ncols, self.field.clone());
+
+        for t in 0..self.nrows.min(self.ncols) {
+            'pivot: loop {
+                if F::is_zero(&s[(t, t)]) {
+                    let Some((k, l)) = (t..self.nrows)
+                        .flat_map(|k| (t..self.ncols).map(move |l| (k, l)))
+                        .find(|(k, l)| !F::is_zero(&s[(*k, *l)]))
+                    else {
+                        return (s, u, v);
+                    };
+
+                    s.swap_rows(t, k);
+                    u.swap_rows(t, k);
+                    s.swap_cols(t, l);
+                    v.swap_cols(t, l);
+                }
+
+                // alternate between clearing the column and the row until both are zero
+                loop {
+                    s.eliminate_column(t, t, &mut u);
+
+                    let mut st = s.transpose();
+                    let mut vt = v.transpose();
+                    st.eliminate_column(t, t, &mut vt);
+                    s = st.into_transposed();
+                    v = vt.into_transposed();
+
+                    if (t + 1..self.nrows).all(|k| F::is_zero(&s[(k, t)])) {
+                        break;
+                    }
+                }
+
+                // make sure the pivot divides all remaining entries
+                for k in t + 1..self.nrows {
+                    for l in t + 1..self.ncols {
+                        if !F::is_zero(&self.field.rem(&s[(k, l)], &s[(t, t)])) {
+                            s.add_row(t, k);
+                            u.add_row(t, k);
+                            continue 'pivot;
+                        }
+                    }
+                }
+
+                break;
+            }
+
+            let unit = unit_normalization(&self.field, &s[(t, t)]);
+            if !self.field.is_one(&unit) {
+                s.mul_row(t, &unit);
+                u.mul_row(t, &unit);
+            }
+        }
+
+        (s, u, v)
+    }
+
+    /// Use unimodular row operations to make all entries in column `j` below row `i` zero,
+    /// so that entry `(i, j)` becomes their gcd. The row operations are also applied to `u`.
+    /// Returns `false` if all these entries are zero.
+    fn eliminate_column(&mut self, i: u32, j: u32, u: &mut Matrix<F>) -> bool {
+        for k in i + 1..self.nrows {
+            if F::is_zero(&self[(k, j)]) {
+                continue;
+            }
+
+            if F::is_zero(&self[(i, j)]) {
+                self.swap_rows(i, k);
+                u.swap_rows(i, k);
+                continue;
+            }
+
+            let (g, s, t) = extended_gcd(&self.field, &self[(i, j)], &self[(k, j)]);
+            let a = self.field.quot_rem(&self[(i, j)], &g).0;
+            let b = self.field.quot_rem(&self[(k, j)], &g).0;
+            let c = [s, t, self.field.neg(&b), a];
+
+            self.combine_rows(i, k, &c);
+            u.combine_rows(i, k, &c);
+        }
+
+        !F::is_zero(&self[(i, j)])
+    }
+
+    /// Replace row `i` by `c[0] * row_i + c[1] * row_k` and row `k` by `c[2] * row_i + c[3] * row_k`.
+    fn combine_rows(&mut self, i: u32, k: u32, c: &[F::Element; 4]) {
+        for l in 0..self.ncols {
+            let (x, y) = (&self[(i, l)], &self[(k, l)]);
+
+            let mut ri = self.field.mul(&c[0], x);
+            self.field.add_mul_assign(&mut ri, &c[1], y);
+            let mut rk = self.field.mul(&c[2], x);
+            self.field.add_mul_assign(&mut rk, &c[3], y);
+
+            self[(i, l)] = ri;
+            self[(k, l)] = rk;
+        }
+    }
+
+    /// Multiply row `i` by `c`.
+    fn mul_row(&mut self, i: u32, c: &F::Element) {
+        for l in 0..self.ncols {
+            let e = self.field.mul(&self[(i, l)], c);
+            self[(i, l)] = e;
+        }
+    }
+
+    /// Subtract `c` times row `i` from row `k`.
+    fn sub_mul_row(&mut self, k: u32, i: u32, c: &F::Element) {
+        for l in 0..self.ncols {
+            let mut e = self[(k, l)].clone();
+            self.field.sub_mul_assign(&mut e, &self[(i, l)], c);
+            self[(k, l)] = e;
+        }
+    }
+
+    /// Add row `k` to row `i`.
+    fn add_row(&mut self, i: u32, k: u32) {
+        for l in 0..self.ncols {
+            let e = self.field.add(&self[(i, l)], &self[(k, l)]);
+            self[(i, l)] = e;
+        }
+    }
+}
+
+/// Compute the unit `u` such that `u * a` is normalized, for non-zero `a`.
+fn unit_normalization<F: EuclideanDomain>(field: &F, a: &F::Element) -> F::Element {
+    let g = field.gcd(a, &field.zero());
+    field.quot_rem(&g, a).0
+}
+
+/// Compute `(g, s, t)` where `s * a + t * b = g` and `g` is the normalized gcd of `a` and `b`,
+/// using the extended Euclidean algorithm.
+fn extended_gcd<F: EuclideanDomain>(
+    field: &F,
+    a: &F::Element,
+    b: &F::Element,
+) -> (F::Element, F::Element, F::Element) {
+    let (mut r0, mut r1) = (a.clone(), b.clone());
+    let (mut s0, mut s1) = (field.one(), field.zero());
+    let (mut t0, mut t1) = (field.zero(), field.one());
+
+    while !F::is_zero(&r1) {
+        let (q, r) = field.quot_rem(&r0, &r1);
+        (r0, r1) = (r1, r);
+
+        let s = field.sub(&s0, &field.mul(&q, &s1));
+        (s0, s1) = (s1, s);
+        let t = field.sub(&t0, &field.mul(&q, &t1));
+        (t0, t1) = (t1, t);
+    }
+
+    let unit = unit_normalization(field, &r0);
+    (
+        field.mul(&r0, &unit),
+        field.mul(&s0, &unit),
+        field.mul(&t0, &unit),
+    )
+}
+
+impl Matrix<IntegerRing> {
+    /// Reduce the lattice basis formed by the rows of the matrix using the
+    /// Lenstra-Lenstra-Lovász algorithm with parameter `delta`, which should be in `(1/4, 1]`.
+    /// A common choice is `delta = 3/4`. The rows must be linearly independent,
+    /// otherwise `MatrixError::Singular` is returned.
+    ///
+    /// The Gram-Schmidt coefficients are computed exactly using rational arithmetic.
+    pub fn lll(&self, delta: &Rational) -> Result<Matrix<IntegerRing>, MatrixError<IntegerRing>> {
+        let n = self.nrows as usize;
+        let mut b: Vec<Vec<Integer>> = self.row_iter().map(|r| r.to_vec()).collect();
+
+        // compute the Gram-Schmidt coefficients `mu` and squared norms `bb`
+        let mut mu = vec![vec![Rational::zero(); n]; n];
+        let mut bb = vec![Rational::zero(); n];
+        let mut bstar: Vec<Vec<Rational>> = Vec::with_capacity(n);
+        for i in 0..n {
+            let mut v: Vec<Rational> = b[i].iter().map(|x| x.into()).collect();
+            for j in 0..i {
+                let mut d = Rational::zero();
+                for (x, y) in b[i].iter().zip(&bstar[j]) {
+                    d += &(&Rational::from(x) * y);
+                }
+
+                mu[i][j] = &d / &bb[j];
+                for (vk, sk) in v.iter_mut().zip(&bstar[j]) {
+                    *vk -= &(&mu[i][j] * sk);
+                }
+            }
+
+            for x in &v {
+                bb[i] += &(x * x);
+            }
+
+            if bb[i].is_zero() {
+                return Err(MatrixError::Singular);
+            }
+
+            bstar.push(v);
+        }
+
+        let two = Integer::from(2);
+        let round = |r: &Rational| {
+            let (num, den) = (r.numerator(), r.denominator());
+            Z.quot_rem(&(&(&num * &two) + &den), &(&den * &two)).0
+        };
+
+        let mut k = 1;
+        while k < n {
+            // size reduction
+            for j in (0..k).rev() {
+                let q = round(&mu[k][j]);
+                if q.is_zero() {
+                    continue;
+                }
+
+                let (lower, upper) = b.split_at_mut(k);
+                for (bkl, bjl) in upper[0].iter_mut().zip(&lower[j]) {
+                    let t = &q * bjl;
+                    *bkl -= &t;
+                }
+
+                let qr = Rational::from(&q);
+                let (lower, upper) = mu.split_at_mut(k);
+                for (mkl, mjl) in upper[0][..j].iter_mut().zip(&lower[j][..j]) {
+                    let t = &qr * mjl;
+                    *mkl -= &t;
+                }
+                mu[k][j] -= &qr;
+            }
+
+            // Lovász condition
+            let bound = &(delta - &(&mu[k][k - 1] * &mu[k][k - 1])) * &bb[k - 1];
+            if bb[k] >= bound {
+                k += 1;
+                continue;
+            }
+
+            b.swap(k, k - 1);
+
+            let m = mu[k][k - 1].clone();
+            let new_bb = &bb[k] + &(&(&m * &m) * &bb[k - 1]);
+            mu[k][k - 1] = &(&m * &bb[k - 1]) / &new_bb;
+            bb[k] = &(&bb[k - 1] * &bb[k]) / &new_bb;
+            bb[k - 1] = new_bb;
+
+            let (lower, upper) = mu.split_at_mut(k);
+            for (a, b) in lower[k - 1][..k - 1].iter_mut().zip(&mut upper[0][..k - 1]) {
+                std::mem::swap(a, b);
+            }
+
+            for i in k + 1..n {
+                let t = mu[i][k].clone();
+                mu[i][k] = &mu[i][k - 1] - &(&m * &t);
+                mu[i][k - 1] = &t + &(&mu[k][k - 1] * &mu[i][k]);
+            }
+
+            k = (k - 1).max(1);
+        }
+
+        Ok(Matrix {
+            data: b.into_iter().flatten().collect(),
+            nrows: self.nrows,
+            ncols: self.ncols,
+            field: Z,
+        })
+    }
 }
 
 impl<F: Field> Matrix<F> {
@@ -604,6 +1093,323 @@ impl<F: Field> Matrix<F> {
         Ok(det)
     }
 
+    /// Transform the matrix in-place into reduced row echelon form, where only
+    /// the first `max_col` columns are used for pivots. The other columns are augmented.
+    /// Returns the columns of the pivots, whose length is the rank of the
+    /// first `max_col` columns.
+    pub fn row_reduce(&mut self, max_col: u32) -> Vec<u32> {
+        let zero = self.field.zero();
+        let mut pivots = vec![];
+
+        let mut i = 0;
+        for j in 0..max_col.min(self.ncols) {
+            if i == self.nrows {
+                break;
+            }
+
+            let Some(k) = (i..self.nrows).find(|k| !F::is_zero(&self[(*k, j)])) else {
+                continue;
+            };
+            self.swap_rows(i, k);
+
+            if !self.field.is_one(&self[(i, j)]) {
+                let inv_x = self.field.inv(&self[(i, j)]);
+                for l in j..self.ncols {
+                    self.field
+                        .mul_assign(&mut self.data[(i * self.ncols + l) as usize], &inv_x);
+                }
+            }
+
+            for k in 0..self.nrows {
+                if k == i || F::is_zero(&self[(k, j)]) {
+                    continue;
+                }
+
+                let s = self[(k, j)].clone();
+                for l in j..self.ncols {
+                    let mut e = std::mem::replace(&mut self[(k, l)], zero.clone());
+                    self.field.sub_mul_assign(&mut e, &self[(i, l)], &s);
+                    self[(k, l)] = e;
+                }
+            }
+
+            pivots.push(j);
+            i += 1;
+        }
+
+        pivots
+    }
+
+    /// Compute the rank of the matrix.
+    pub fn rank(&self) -> usize {
+        self.clone().row_reduce(self.ncols).len()
+    }
+
+    /// Compute a basis of the null space of the matrix, i.e. of all vectors `x`
+    /// that satisfy `A * x = 0`. The basis vectors are the rows of the returned matrix.
+    pub fn nullspace(&self) -> Matrix<F> {
+        let mut m = self.clone();
+        let pivots = m.row_reduce(self.ncols);
+
+        let free: Vec<_> = (0..self.ncols).filter(|c| !pivots.contains(c)).collect();
+
+        let mut basis = Matrix::new(free.len() as u32, self.ncols, self.field.clone());
+        for (r, f) in free.iter().enumerate() {
+            basis[(r as u32, *f)] = self.field.one();
+            for (pr, pc) in pivots.iter().enumerate() {
+                basis[(r as u32, *pc)] = self.field.neg(&m[(pr as u32, *f)]);
+            }
+        }
+
+        basis
+    }
+
+    /// Compute the characteristic polynomial `det(x*I - A)` of a square matrix in the variable `var`.
+    ///
+    /// The matrix is first transformed to upper Hessenberg form by similarity transformations,
+    /// after which the characteristic polynomial is computed with a recurrence over
+    /// the leading principal submatrices.
+    pub fn charpoly(&self, var: Variable) -> Result<UnivariatePolynomial<F>, MatrixError<F>> {
+        if self.nrows != self.ncols {
+            Err(MatrixError::NotSquare)?;
+        }
+
+        let n = self.nrows;
+        let mut h = self.clone();
+        for j in 0..n.saturating_sub(2) {
+            let Some(i) = (j + 1..n).find(|i| !F::is_zero(&h[(*i, j)])) else {
+                continue;
+            };
+
+            h.swap_rows(i, j + 1);
+            h.swap_cols(i, j + 1);
+
+            let inv_x = self.field.inv(&h[(j + 1, j)]);
+            for k in j + 2..n {
+                if F::is_zero(&h[(k, j)]) {
+                    continue;
+                }
+
+                let u = self.field.mul(&h[(k, j)], &inv_x);
+                for l in 0..n {
+                    let mut e = std::mem::replace(&mut h[(k, l)], self.field.zero());
+                    self.field.sub_mul_assign(&mut e, &h[(j + 1, l)], &u);
+                    h[(k, l)] = e;
+                }
+                for l in 0..n {
+                    let mut e = std::mem::replace(&mut h[(l, j + 1)], self.field.zero());
+                    self.field.add_mul_assign(&mut e, &h[(l, k)], &u);
+                    h[(l, j + 1)] = e;
+                }
+            }
+        }
+
+        let zero = UnivariatePolynomial::new(&self.field, None, Arc::new(var));
+        let x = zero.monomial(self.field.one(), 1);
+
+        let mut p = vec![zero.one()];
+        for m in 0..n {
+            let mut next = &(&x - &zero.constant(h[(m, m)].clone())) * &p[m as usize];
+
+            let mut t = self.field.one();
+            for i in (0..m).rev() {
+                self.field.mul_assign(&mut t, &h[(i + 1, i)]);
+                if F::is_zero(&t) {
+                    break;
+                }
+
+                let c = self.field.mul(&h[(i, m)], &t);
+                next = next - p[i as usize].clone().mul_coeff(&c);
+            }
+
+            p.push(next);
+        }
+
+        Ok(p.pop().unwrap())
+    }
+
+    /// Compute the minimal polynomial of a square matrix in the variable `var`, which
+    /// is the monic polynomial `p` of lowest degree that satisfies `p(A) = 0`.
+    ///
+    /// It is computed as the least common multiple of the minimal polynomials of the
+    /// unit vectors, which are obtained from the first linear dependency in their Krylov sequences.
+    pub fn minpoly(&self, var: Variable) -> Result<UnivariatePolynomial<F>, MatrixError<F>> {
+        if self.nrows != self.ncols {
+            Err(MatrixError::NotSquare)?;
+        }
+
+        let n = self.nrows;
+        let mut res = UnivariatePolynomial::new(&self.field, None, Arc::new(var)).one();
+
+        for i in 0..n {
+            let mut e = Matrix::new(n, 1, self.field.clone());
+            e[(i, 0)] = self.field.one();
+
+            // check if the vector is already annihilated by the current polynomial
+            let mut v = Matrix::new(n, 1, self.field.clone());
+            for c in res.coefficients.iter().rev() {
+                v = &(self * &v) + &e.mul_scalar(c);
+            }
+            if v.is_zero() {
+                continue;
+            }
+
+            let mut krylov = vec![e];
+            let dependency = loop {
+                let next = self * krylov.last().unwrap();
+                krylov.push(next);
+
+                let mut k = Matrix::new(n, krylov.len() as u32, self.field.clone());
+                for (c, kv) in krylov.iter().enumerate() {
+                    for r in 0..n {
+                        k[(r, c as u32)] = kv.data[r as usize].clone();
+                    }
+                }
+
+                let null = k.nullspace();
+                if null.nrows > 0 {
+                    break null;
+                }
+            };
+
+            let mut p = res.zero();
+            p.coefficients = dependency.data[..dependency.ncols as usize].to_vec();
+            let p = p.make_monic();
+
+            let g = res.gcd(&p);
+            res = (&res * &p).quot_rem(&g).0;
+        }
+
+        Ok(res)
+    }
+
+    /// Compute the eigenspace of the eigenvalue `eigenvalue` of a square matrix, consisting
+    /// of a Jordan chain for every Jordan block. If `eigenvalue` is not an eigenvalue of the matrix,
+    /// the eigenspace is empty.
+    ///
+    /// The chains are constructed from the kernels of the powers of `N = A - eigenvalue * I`, starting
+    /// from the longest chain. Its top vectors are chosen to be independent of the smaller kernel and of
+    /// the images under `N` of the tops of the longer chains.
+    pub fn eigenspace(&self, eigenvalue: &F::Element) -> Result<Eigenspace<F>, MatrixError<F>> {
+        if self.nrows != self.ncols {
+            Err(MatrixError::NotSquare)?;
+        }
+
+        let n = self.nrows;
+        let mut shifted = self.clone();
+        for i in 0..n {
+            self.field
+                .sub_assign(&mut shifted.data[(i * n + i) as usize], eigenvalue);
+        }
+
+        // the kernels of N^j grow until they reach the generalized eigenspace
+        let mut kernels = vec![Matrix::new(0, n, self.field.clone())];
+        let mut power = shifted.clone();
+        loop {
+            let k = power.nullspace();
+            if k.nrows == kernels.last().unwrap().nrows {
+                break;
+            }
+
+            let full = k.nrows == n;
+            kernels.push(k);
+            if full {
+                break;
+            }
+            power = &power * &shifted;
+        }
+
+        let apply =
+            |v: &[F::Element]| (&shifted * &Matrix::new_vec(v.to_vec(), self.field.clone())).data;
+        // reduce `v` by the echelon rows and add it when it is independent of them
+        let insert = |echelon: &mut Vec<(usize, Vec<F::Element>)>, mut v: Vec<F::Element>| {
+            for (p, row) in echelon.iter() {
+                if !F::is_zero(&v[*p]) {
+                    let c = v[*p].clone();
+                    for (a, b) in v.iter_mut().zip(row) {
+                        self.field.sub_mul_assign(a, b, &c);
+                    }
+                }
+            }
+
+            let Some(p) = v.iter().position(|x| !F::is_zero(x)) else {
+                return false;
+            };
+            let inv = self.field.inv(&v[p]);
+            for a in &mut v {
+                self.field.mul_assign(a, &inv);
+            }
+            echelon.push((p, v));
+            true
+        };
+
+        let mut tops: Vec<(usize, Vec<F::Element>)> = vec![];
+        for j in (1..kernels.len()).rev() {
+            let mut echelon = vec![];
+            for v in kernels[j - 1].row_iter() {
+                insert(&mut echelon, v.to_vec());
+            }
+            for (len, top) in &tops {
+                let mut v = top.clone();
+                for _ in j..*len {
+                    v = apply(&v);
+                }
+                insert(&mut echelon, v);
+            }
+
+            for v in kernels[j].row_iter() {
+                if insert(&mut echelon, v.to_vec()) {
+                    tops.push((j, v.to_vec()));
+                }
+            }
+        }
+
+        let mut jordan_chains = vec![];
+        for (len, top) in tops {
+            let mut chain = vec![top];
+            for _ in 1..len {
+                chain.push(apply(chain.last().unwrap()));
+            }
+            chain.reverse();
+
+            let data = chain.into_iter().flatten().collect();
+            jordan_chains
+                .push(Matrix::from_linear(data, len as u32, n, self.field.clone()).unwrap());
+        }
+
+        Ok(Eigenspace {
+            eigenvalue: eigenvalue.clone(),
+            multiplicity: kernels.last().unwrap().nrows as usize,
+            jordan_chains,
+            field: self.field.clone(),
+        })
+    }
+
+    /// Compute the eigenspaces of the matrix for every monic irreducible factor `p` of its characteristic
+    /// polynomial, whose eigenvalue is the root of `p` in the algebraic number ring with minimal polynomial `p`.
+    fn algebraic_eigenspaces(
+        &self,
+        factors: Vec<MultivariatePolynomial<F, u8>>,
+    ) -> Vec<Eigenspace<AlgebraicNumberRing<F>>> {
+        let mut res = vec![];
+        for f in factors {
+            let mut root = f.zero();
+            root.append_monomial(self.field.one(), &[1]);
+
+            let ring = AlgebraicNumberRing::new(f);
+            let root = ring.to_element(root);
+            let m = self.map(
+                |c| ring.to_element(ring.poly().constant(c.clone())),
+                ring.clone(),
+            );
+
+            // the matrix is square, since it has a characteristic polynomial
+            res.push(m.eigenspace(&root).unwrap());
+        }
+
+        res
+    }
+
     /// Solves `A * x = 0` for the first `max_col` columns in x.
     /// The other columns are augmented.
     pub fn solve_subsystem(&mut self, max_col: u32) -> Result<u32, MatrixError<F>> {
@@ -756,11 +1562,183 @@ impl<F: Field> Matrix<F> {
     }
 }
 
+impl Matrix<RationalField> {
+    /// Compute the eigenspaces of a square matrix over the rationals, one for every irreducible
+    /// factor of the characteristic polynomial in the variable `var`.
+    ///
+    /// The eigenvalue of each eigenspace is the root `var` of the irreducible factor, and the eigenvectors
+    /// are defined over the algebraic number ring with the factor as minimal polynomial, which
+    /// can be obtained from [Eigenspace::field]. The eigenspaces of the conjugate eigenvalues
+    /// follow by substituting the other roots of the minimal polynomial.
+    ///
+    /// Irreducible factors with a degree above 255 are not supported.
+    pub fn eigenspaces(
+        &self,
+        var: Variable,
+    ) -> Result<Vec<Eigenspace<AlgebraicNumberRing<RationalField>>>, MatrixError<RationalField>>
+    {
+        let p = self.charpoly(var.clone())?;
+
+        // the characteristic polynomial is factored with u16 exponents, whereas the
+        // minimal polynomials of the algebraic number rings have u8 exponents
+        let mut poly = MultivariatePolynomial::<_, u16>::new(&Q, None, Arc::new(vec![var]));
+        for (e, c) in p.coefficients.into_iter().enumerate() {
+            poly.append_monomial(c, &[e as u16]);
+        }
+
+        let mut factors = vec![];
+        for (f, _) in poly.factor() {
+            if f.is_constant() {
+                continue;
+            }
+            if f.degree(0) > u8::MAX as u16 {
+                return Err(MatrixError::DegreeTooLarge);
+            }
+
+            let mut min_poly = MultivariatePolynomial::new(&Q, None, f.get_vars());
+            for t in &f {
+                min_poly.append_monomial(t.coefficient.clone(), &[t.exponents[0] as u8]);
+            }
+            factors.push(min_poly.make_monic());
+        }
+
+        Ok(self.algebraic_eigenspaces(factors))
+    }
+}
+
+impl<E: Exponent> Matrix<RationalPolynomialField<IntegerRing, E>> {
+    /// Compute the eigenspaces of a square matrix over rational functions, one for every irreducible
+    /// factor of the characteristic polynomial in the variable `var`, which should not appear in the matrix.
+    ///
+    /// The eigenvalue of each eigenspace is the root `var` of the irreducible factor, and the eigenvectors
+    /// are defined over the algebraic extension of the rational functions with the factor as minimal polynomial,
+    /// which can be obtained from [Eigenspace::field].
+    ///
+    /// Irreducible factors with a degree above 255 in `var` are not supported.
+    pub fn eigenspaces(
+        &self,
+        var: Variable,
+    ) -> Result<
+        Vec<RationalFunctionEigenspace<E>>,
+        MatrixError<RationalPolynomialField<IntegerRing, E>>,
+    > {
+        let p = self.charpoly(var.clone())?;
+
+        // clear the denominators so that the characteristic polynomial
+        // can be factored as a polynomial in the parameters and `var`
+        let vars = p.lcoeff().numerator.get_vars();
+        let nvars = vars.len();
+
+        let mut lcm = p.lcoeff().numerator.one();
+        for c in &p.coefficients {
+            let g = lcm.gcd(&c.denominator);
+            lcm = &lcm * &(c.denominator.clone() / &g);
+        }
+
+        // the polynomial is factored with u32 exponents, so that the degree in `var`
+        // is only bounded by the u8 exponents of the minimal polynomials of the factors
+        let mut ext_vars = vars.as_ref().clone();
+        ext_vars.push(var.clone());
+        let mut poly = MultivariatePolynomial::<_, u32>::new(&Z, None, Arc::new(ext_vars));
+        for (k, c) in p.coefficients.iter().enumerate() {
+            let f = &c.numerator * &(lcm.clone() / &c.denominator);
+            for t in &f {
+                let mut e: Vec<_> = t.exponents.iter().map(|e| e.to_u32()).collect();
+                e.push(k as u32);
+                poly.append_monomial(t.coefficient.clone(), &e);
+            }
+        }
+
+        let mut factors = vec![];
+        let mut exp = vec![E::zero(); nvars];
+        for (f, _) in poly.factor() {
+            let d = f.degree(nvars) as usize;
+            if d == 0 {
+                continue;
+            }
+            if d > u8::MAX as usize {
+                return Err(MatrixError::DegreeTooLarge);
+            }
+
+            let mut coeffs = vec![MultivariatePolynomial::new(&Z, None, vars.clone()); d + 1];
+            for t in &f {
+                for (e, te) in exp.iter_mut().zip(&t.exponents[..nvars]) {
+                    *e = E::from_u32(*te);
+                }
+                coeffs[t.exponents[nvars] as usize].append_monomial(t.coefficient.clone(), &exp);
+            }
+
+            let mut min_poly =
+                MultivariatePolynomial::new(&self.field, None, Arc::new(vec![var.clone()]));
+            for (k, c) in coeffs.iter().enumerate() {
+                if !c.is_zero() {
+                    let c =
+                        RationalPolynomial::from_num_den(c.clone(), coeffs[d].clone(), &Z, true);
+                    min_poly.append_monomial(c, &[k as u8]);
+                }
+            }
+
+            factors.push(min_poly);
+        }
+
+        Ok(self.algebraic_eigenspaces(factors))
+    }
+}
+
+/// An eigenspace of a matrix over rational functions, defined over an algebraic extension of the rational functions.
+pub type RationalFunctionEigenspace<E> =
+    Eigenspace<AlgebraicNumberRing<RationalPolynomialField<IntegerRing, E>>>;
+
+/// An eigenvalue of a matrix `A` together with its Jordan chains.
+///
+/// Collecting the chains of all eigenvalues as the columns of a matrix `P`
+/// yields the Jordan decomposition `A = P * J * P^-1`.
+#[derive(Clone, Debug)]
+pub struct Eigenspace<F: Field> {
+    /// The eigenvalue `λ`.
+    pub eigenvalue: F::Element,
+    /// The algebraic multiplicity of the eigenvalue.
+    pub multiplicity: usize,
+    /// The Jordan chains, one for every Jordan block, with the vectors as rows.
+    /// The first vector `v_1` of a chain is an eigenvector and every next vector satisfies
+    /// `(A - λ * I) * v_{i+1} = v_i`.
+    pub jordan_chains: Vec<Matrix<F>>,
+    /// The field of the eigenvalue and the eigenvectors.
+    pub field: F,
+}
+
+impl<F: Field> Eigenspace<F> {
+    /// Get the geometric multiplicity of the eigenvalue, i.e., the number of Jordan blocks.
+    pub fn geometric_multiplicity(&self) -> usize {
+        self.jordan_chains.len()
+    }
+
+    /// Get a basis of eigenvectors, one per row.
+    pub fn eigenvectors(&self) -> Matrix<F> {
+        let data = self
+            .jordan_chains
+            .iter()
+            .flat_map(|c| c.row_iter().next().unwrap().to_vec())
+            .collect();
+        let n = self.jordan_chains.first().map(|c| c.ncols).unwrap_or(0);
+        Matrix::from_linear(data, self.jordan_chains.len() as u32, n, self.field.clone()).unwrap()
+    }
+}
+
 #[cfg(test)]
 mod test {
     use crate::{
-        domains::{integer::Z, rational::Q},
-        tensors::matrix::Matrix,
+        atom::Atom,
+        domains::{
+            algebraic_number::AlgebraicNumberRing,
+            integer::{IntegerRing, Z},
+            rational::Q,
+            rational_polynomial::{RationalPolynomial, RationalPolynomialField},
+            Field, Ring,
+        },
+        poly::Variable,
+        state::State,
+        tensors::matrix::{Eigenspace, Matrix, MatrixError},
     };
 
     #[test]
@@ -871,4 +1849,302 @@ mod test {
         let r = a.solve(&b).unwrap();
         assert_eq!(r.data, vec![(-1, 3).into(), (2, 3).into(), 0.into()]);
     }
+
+    #[test]
+    fn row_reduce() {
+        let a = Matrix::from_linear(
+            vec![
+                1.into(),
+                2.into(),
+                3.into(),
+                2.into(),
+                4.into(),
+                7.into(),
+                3.into(),
+                6.into(),
+                10.into(),
+            ],
+            3,
+            3,
+            Q,
+        )
+        .unwrap();
+
+        assert_eq!(a.rank(), 2);
+
+        let mut r = a.clone();
+        assert_eq!(r.row_reduce(3), vec![0, 2]);
+        assert_eq!(
+            r.data,
+            vec![
+                1.into(),
+                2.into(),
+                0.into(),
+                0.into(),
+                0.into(),
+                1.into(),
+                0.into(),
+                0.into(),
+                0.into()
+            ]
+        );
+
+        let n = a.nullspace();
+        assert_eq!(n.data, vec![(-2).into(), 1.into(), 0.into()]);
+        assert!((&a * &n.transpose()).is_zero());
+
+        let b = Matrix::from_linear(vec![0.into(), 1.into(), 1.into(), 0.into()], 2, 2, Z).unwrap();
+        assert_eq!(b.trace().unwrap(), 0.into());
+        assert_eq!(b.pow(3).unwrap(), b);
+        assert_eq!(
+            b.kronecker_product(&Matrix::identity(2, Z)).data,
+            vec![
+                0.into(),
+                0.into(),
+                1.into(),
+                0.into(),
+                0.into(),
+                0.into(),
+                0.into(),
+                1.into(),
+                1.into(),
+                0.into(),
+                0.into(),
+                0.into(),
+                0.into(),
+                1.into(),
+                0.into(),
+                0.into()
+            ]
+        );
+    }
+
+    #[test]
+    fn charpoly() {
+        let x = Variable::Symbol(State::get_symbol("x"));
+
+        let a = Matrix::from_linear(
+            vec![
+                1.into(),
+                2.into(),
+                3.into(),
+                4.into(),
+                5.into(),
+                16.into(),
+                7.into(),
+                8.into(),
+                9.into(),
+            ],
+            3,
+            3,
+            Q,
+        )
+        .unwrap();
+
+        let p = a.charpoly(x.clone()).unwrap();
+        assert_eq!(
+            p.coefficients,
+            vec![(-60).into(), (-98).into(), (-15).into(), 1.into()]
+        );
+        assert_eq!(a.minpoly(x.clone()).unwrap(), p);
+
+        let b = Matrix::from_linear(
+            vec![
+                2.into(),
+                0.into(),
+                0.into(),
+                0.into(),
+                2.into(),
+                1.into(),
+                0.into(),
+                0.into(),
+                2.into(),
+            ],
+            3,
+            3,
+            Q,
+        )
+        .unwrap();
+
+        assert_eq!(
+            b.charpoly(x.clone()).unwrap().coefficients,
+            vec![(-8).into(), 12.into(), (-6).into(), 1.into()]
+        );
+        assert_eq!(
+            b.minpoly(x).unwrap().coefficients,
+            vec![4.into(), (-4).into(), 1.into()]
+        );
+    }
+
+    #[test]
+    fn normal_forms() {
+        let a = Matrix::from_linear(
+            vec![
+                2.into(),
+                4.into(),
+                4.into(),
+                (-6).into(),
+                6.into(),
+                12.into(),
+                10.into(),
+                (-4).into(),
+                (-16).into(),
+            ],
+            3,
+            3,
+            Z,
+        )
+        .unwrap();
+
+        assert_eq!(a.det_fraction_free().unwrap(), (-144).into());
+
+        let mut r = a.clone();
+        assert_eq!(r.row_reduce_fraction_free(3), vec![0, 1, 2]);
+        assert_eq!(r[(2, 2)], (-144).into());
+
+        let (h, u) = a.hermite_normal_form();
+        assert_eq!(&u * &a, h);
+        assert!(u.det_fraction_free().unwrap().abs().is_one());
+        assert!(h[(1, 0)].is_zero() && h[(2, 0)].is_zero() && h[(2, 1)].is_zero());
+
+        let (s, u, v) = a.smith_normal_form();
+        assert_eq!(&(&u * &a) * &v, s);
+        assert_eq!(
+            s.data,
+            vec![
+                2.into(),
+                0.into(),
+                0.into(),
+                0.into(),
+                6.into(),
+                0.into(),
+                0.into(),
+                0.into(),
+                12.into()
+            ]
+        );
+    }
+
+    #[test]
+    fn lll() {
+        let a = Matrix::from_linear(
+            vec![
+                1.into(),
+                1.into(),
+                1.into(),
+                (-1).into(),
+                0.into(),
+                2.into(),
+                3.into(),
+                5.into(),
+                6.into(),
+            ],
+            3,
+            3,
+            Z,
+        )
+        .unwrap();
+
+        let r = a.lll(&(3, 4).into()).unwrap();
+        assert_eq!(
+            r.data,
+            vec![
+                0.into(),
+                1.into(),
+                0.into(),
+                1.into(),
+                0.into(),
+                1.into(),
+                (-2).into(),
+                0.into(),
+                1.into()
+            ]
+        );
+    }
+
+    /// Verify the Jordan chains of the eigenspaces of `a` and return the degree of the
+    /// minimal polynomial, the multiplicity and the chain lengths of every eigenspace.
+    fn check_eigenspaces<F: Field>(
+        a: &Matrix<F>,
+        spaces: &[Eigenspace<AlgebraicNumberRing<F>>],
+    ) -> Vec<(u8, usize, Vec<usize>)> {
+        let mut res = vec![];
+        for s in spaces {
+            let field = &s.field;
+            let mut shifted = a.map(
+                |c| field.to_element(field.poly().constant(c.clone())),
+                field.clone(),
+            );
+            for i in 0..a.nrows {
+                field.sub_assign(&mut shifted[(i, i)], &s.eigenvalue);
+            }
+
+            for c in &s.jordan_chains {
+                let mut prev = Matrix::new(a.nrows, 1, field.clone());
+                for v in c.row_iter() {
+                    let v = Matrix::new_vec(v.to_vec(), field.clone());
+                    assert!(!v.is_zero());
+                    assert_eq!(&shifted * &v, prev);
+                    prev = v;
+                }
+            }
+
+            let mut lens: Vec<_> = s.jordan_chains.iter().map(|c| c.nrows()).collect();
+            lens.sort();
+            res.push((field.poly().degree(0), s.multiplicity, lens));
+        }
+        res.sort();
+        res
+    }
+
+    #[test]
+    fn eigenspaces() {
+        let x = Variable::Symbol(State::get_symbol("x"));
+
+        let a = Matrix::from_nested_vec(
+            vec![
+                vec![3.into(), 1.into(), 0.into(), 0.into()],
+                vec![(-1).into(), 1.into(), 0.into(), 0.into()],
+                vec![0.into(), 0.into(), 1.into(), (-2).into()],
+                vec![0.into(), 0.into(), 1.into(), (-1).into()],
+            ],
+            Q,
+        )
+        .unwrap();
+
+        let spaces = a.eigenspaces(x.clone()).unwrap();
+        assert_eq!(
+            check_eigenspaces(&a, &spaces),
+            vec![(1, 2, vec![2]), (2, 1, vec![1])]
+        );
+
+        let y: RationalPolynomial<IntegerRing, u8> = Atom::parse("y")
+            .unwrap()
+            .to_rational_polynomial(&Q, &Z, None);
+        let field = RationalPolynomialField::new_from_poly(&y.numerator);
+        let (zero, one) = (field.zero(), field.one());
+
+        let b = Matrix::from_nested_vec(
+            vec![
+                vec![y.clone(), one.clone(), zero.clone(), zero.clone()],
+                vec![zero.clone(), y.clone(), zero.clone(), zero.clone()],
+                vec![zero.clone(), zero.clone(), zero.clone(), y.clone()],
+                vec![zero.clone(), zero.clone(), one.clone(), zero.clone()],
+            ],
+            field,
+        )
+        .unwrap();
+
+        let spaces = b.eigenspaces(x.clone()).unwrap();
+        assert_eq!(
+            check_eigenspaces(&b, &spaces),
+            vec![(1, 2, vec![2]), (2, 1, vec![1])]
+        );
+
+        // only the degree of the irreducible factors is bounded
+        let spaces = Matrix::identity(300, Q).eigenspaces(x).unwrap();
+        assert_eq!(spaces.len(), 1);
+        assert_eq!(spaces[0].multiplicity, 300);
+        assert_eq!(spaces[0].geometric_multiplicity(), 300);
+    }
 }
@@ -0,0 +1,689 @@
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+use crate::{
+    atom::{AtomView, Symbol},
+    domains::{
+        integer::{IntegerRing, Z},
+        rational_polynomial::{RationalPolynomial, RationalPolynomialField},
+        Field, Ring,
+    },
+    poly::Exponent,
+    solve::linear_coefficients,
+};
+
+use super::matrix::{Matrix, MatrixError};
+
+/// A sparse matrix over a ring `F`, stored in compressed sparse row (CSR) format.
+/// The non-zero entries of row `i` are found at positions `row_offsets[i]..row_offsets[i + 1]`
+/// of `cols` and `values`, sorted by column.
+///
+/// Matrices are built from a list of `(row, column, value)` triplets (COO format)
+/// using [SparseMatrix::from_triplets].
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct SparseMatrix<F: Ring> {
+    pub(crate) row_offsets: Vec<usize>,
+    pub(crate) cols: Vec<u32>,
+    pub(crate) values: Vec<F::Element>,
+    pub(crate) nrows: u32,
+    pub(crate) ncols: u32,
+    pub(crate) field: F,
+}
+
+impl<F: Ring> SparseMatrix<F> {
+    /// Create a new zeroed matrix with `nrows` rows and `ncols` columns.
+    pub fn new(nrows: u32, ncols: u32, field: F) -> SparseMatrix<F> {
+        SparseMatrix {
+            row_offsets: vec![0; nrows as usize + 1],
+            cols: vec![],
+            values: vec![],
+            nrows,
+            ncols,
+            field,
+        }
+    }
+
+    /// Create a new matrix from a list of `(row, column, value)` triplets.
+    /// The triplets may appear in any order, repeated entries are added together
+    /// and zeros are removed.
+    pub fn from_triplets(
+        nrows: u32,
+        ncols: u32,
+        mut triplets: Vec<(u32, u32, F::Element)>,
+        field: F,
+    ) -> Result<SparseMatrix<F>, String> {
+        if let Some((r, c, _)) = triplets.iter().find(|(r, c, _)| *r >= nrows || *c >= ncols) {
+            return Err(format!(
+                "Entry ({}, {}) is out of bounds for a {}x{} matrix",
+                r, c, nrows, ncols
+            ));
+        }
+
+        triplets.sort_by_key(|(r, c, _)| (*r, *c));
+
+        let mut row_offsets = Vec::with_capacity(nrows as usize + 1);
+        let mut cols: Vec<u32> = Vec::with_capacity(triplets.len());
+        let mut values: Vec<F::Element> = Vec::with_capacity(triplets.len());
+
+        row_offsets.push(0);
+        let mut cur_row = 0;
+        for (r, c, v) in triplets {
+            while cur_row < r {
+                Self::remove_last_zero(&mut cols, &mut values, *row_offsets.last().unwrap());
+                row_offsets.push(cols.len());
+                cur_row += 1;
+            }
+
+            if cols.len() > *row_offsets.last().unwrap() && *cols.last().unwrap() == c {
+                field.add_assign(values.last_mut().unwrap(), &v);
+                continue;
+            }
+
+            Self::remove_last_zero(&mut cols, &mut values, *row_offsets.last().unwrap());
+            cols.push(c);
+            values.push(v);
+        }
+
+        while row_offsets.len() <= nrows as usize {
+            Self::remove_last_zero(&mut cols, &mut values, *row_offsets.last().unwrap());
+            row_offsets.push(cols.len());
+        }
+
+        Ok(SparseMatrix {
+            row_offsets,
+            cols,
+            values,
+            nrows,
+            ncols,
+            field,
+        })
+    }
+
+    /// Remove the last entry if it is zero and belongs to the row starting at `row_start`.
+    fn remove_last_zero(cols: &mut Vec<u32>, values: &mut Vec<F::Element>, row_start: usize) {
+        if values.len() > row_start && F::is_zero(values.last().unwrap()) {
+            cols.pop();
+            values.pop();
+        }
+    }
+
+    /// Convert the matrix to a list of `(row, column, value)` triplets, sorted by row and column.
+    pub fn to_triplets(&self) -> Vec<(u32, u32, F::Element)> {
+        let mut res = Vec::with_capacity(self.values.len());
+        for r in 0..self.nrows {
+            for (c, v) in self.row(r) {
+                res.push((r, c, v.clone()));
+            }
+        }
+        res
+    }
+
+    /// Create a sparse matrix from the non-zero entries of a dense matrix.
+    pub fn from_dense(m: &Matrix<F>) -> SparseMatrix<F> {
+        let mut row_offsets = Vec::with_capacity(m.nrows as usize + 1);
+        let mut cols = vec![];
+        let mut values = vec![];
+
+        row_offsets.push(0);
+        for r in 0..m.nrows {
+            for c in 0..m.ncols {
+                let v = &m[(r, c)];
+                if !F::is_zero(v) {
+                    cols.push(c);
+                    values.push(v.clone());
+                }
+            }
+            row_offsets.push(cols.len());
+        }
+
+        SparseMatrix {
+            row_offsets,
+            cols,
+            values,
+            nrows: m.nrows,
+            ncols: m.ncols,
+            field: m.field.clone(),
+        }
+    }
+
+    /// Convert the matrix to a dense matrix.
+    pub fn to_dense(&self) -> Matrix<F> {
+        let mut data = vec![self.field.zero(); self.nrows as usize * self.ncols as usize];
+        for r in 0..self.nrows {
+            for (c, v) in self.row(r) {
+                data[r as usize * self.ncols as usize + c as usize] = v.clone();
+            }
+        }
+
+        Matrix {
+            data,
+            nrows: self.nrows,
+            ncols: self.ncols,
+            field: self.field.clone(),
+        }
+    }
+
+    /// Return the number of rows.
+    pub fn nrows(&self) -> usize {
+        self.nrows as usize
+    }
+
+    /// Return the number of columns.
+    pub fn ncols(&self) -> usize {
+        self.ncols as usize
+    }
+
+    /// Return the number of stored non-zero entries.
+    pub fn nnz(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Return the field of the matrix entries.
+    pub fn field(&self) -> &F {
+        &self.field
+    }
+
+    /// Iterate over the non-zero entries `(column, value)` of row `r`.
+    pub fn row(&self, r: u32) -> impl Iterator<Item = (u32, &F::Element)> {
+        let range = self.row_offsets[r as usize]..self.row_offsets[r as usize + 1];
+        self.cols[range.clone()]
+            .iter()
+            .cloned()
+            .zip(&self.values[range])
+    }
+
+    /// Get the entry at row `r` and column `c`, or `None` if it is zero.
+    pub fn get(&self, r: u32, c: u32) -> Option<&F::Element> {
+        let start = self.row_offsets[r as usize];
+        let end = self.row_offsets[r as usize + 1];
+        self.cols[start..end]
+            .binary_search(&c)
+            .ok()
+            .map(|i| &self.values[start + i])
+    }
+
+    /// Compute the matrix-vector product `A * v`.
+    pub fn mul_vec(&self, v: &[F::Element]) -> Result<Vec<F::Element>, MatrixError<F>> {
+        if v.len() != self.ncols as usize {
+            return Err(MatrixError::ShapeMismatch);
+        }
+
+        let mut res = vec![self.field.zero(); self.nrows as usize];
+        for (r, e) in res.iter_mut().enumerate() {
+            for (c, a) in self.row(r as u32) {
+                self.field.add_mul_assign(e, a, &v[c as usize]);
+            }
+        }
+
+        Ok(res)
+    }
+
+    /// Compute the transposed matrix.
+    pub fn transpose(&self) -> SparseMatrix<F> {
+        let mut row_offsets = vec![0; self.ncols as usize + 1];
+        for c in &self.cols {
+            row_offsets[*c as usize + 1] += 1;
+        }
+        for i in 0..self.ncols as usize {
+            row_offsets[i + 1] += row_offsets[i];
+        }
+
+        // fill the columns in order of increasing row so that every
+        // row of the transpose remains sorted
+        let mut pos = row_offsets.clone();
+        let mut cols = vec![0; self.values.len()];
+        let mut values = vec![self.field.zero(); self.values.len()];
+        for r in 0..self.nrows {
+            for (c, v) in self.row(r) {
+                let p = &mut pos[c as usize];
+                cols[*p] = r;
+                values[*p] = v.clone();
+                *p += 1;
+            }
+        }
+
+        SparseMatrix {
+            row_offsets,
+            cols,
+            values,
+            nrows: self.ncols,
+            ncols: self.nrows,
+            field: self.field.clone(),
+        }
+    }
+
+    /// Apply a function `f` to each non-zero entry of the matrix.
+    /// Entries that map to zero are removed.
+    pub fn map<G: Ring>(&self, f: impl Fn(&F::Element) -> G::Element, field: G) -> SparseMatrix<G> {
+        let mut row_offsets = Vec::with_capacity(self.row_offsets.len());
+        let mut cols = Vec::with_capacity(self.cols.len());
+        let mut values = Vec::with_capacity(self.values.len());
+
+        row_offsets.push(0);
+        for r in 0..self.nrows {
+            for (c, v) in self.row(r) {
+                let v = f(v);
+                if !G::is_zero(&v) {
+                    cols.push(c);
+                    values.push(v);
+                }
+            }
+            row_offsets.push(cols.len());
+        }
+
+        SparseMatrix {
+            row_offsets,
+            cols,
+            values,
+            nrows: self.nrows,
+            ncols: self.ncols,
+            field,
+        }
+    }
+}
+
+impl<F: Field> SparseMatrix<F> {
+    /// Solve `A * x = b` using structured Gaussian elimination. See [SparseLinearSystem::solve].
+    pub fn solve(&self, b: &[F::Element]) -> Result<Vec<F::Element>, MatrixError<F>> {
+        if b.len() != self.nrows as usize {
+            return Err(MatrixError::ShapeMismatch);
+        }
+
+        let mut system = SparseLinearSystem::new(self.ncols, self.field.clone());
+        for (r, rhs) in b.iter().enumerate() {
+            let row = self.row(r as u32).map(|(c, v)| (c, v.clone())).collect();
+            system.add_row(row, rhs.clone());
+        }
+
+        system.solve()
+    }
+}
+
+impl<E: Exponent> SparseMatrix<RationalPolynomialField<IntegerRing, E>> {
+    /// Create the coefficient matrix `A` and right-hand side `b` of the
+    /// system `A * vars = b`, where each expression in `system` that is linear
+    /// in `vars` is understood to yield 0. The coefficients are rational functions
+    /// in all other variables.
+    pub fn from_linear_equations(
+        system: &[AtomView],
+        vars: &[Symbol],
+    ) -> Result<(Self, Vec<RationalPolynomial<IntegerRing, E>>), String> {
+        let (rows, rhs) = linear_coefficients::<E>(system, vars)?;
+
+        let field = RationalPolynomialField::new(Z, rhs[0].numerator.get_vars());
+
+        let triplets = rows
+            .into_iter()
+            .enumerate()
+            .flat_map(|(r, row)| row.into_iter().map(move |(c, v)| (r as u32, c, v)))
+            .collect();
+
+        let m =
+            SparseMatrix::from_triplets(system.len() as u32, vars.len() as u32, triplets, field)?;
+        Ok((m, rhs))
+    }
+}
+
+/// A sparse linear system `A * x = b` over a field `F`. Each row
+/// stores only its non-zero entries as `(column, coefficient)` pairs, sorted by column.
+///
+/// The system is solved using structured Gaussian elimination, which
+/// keeps the fill-in low for the very sparse systems that appear in
+/// integration-by-parts reductions.
+#[derive(Clone, Debug)]
+pub struct SparseLinearSystem<F: Field> {
+    ncols: u32,
+    rows: Vec<Vec<(u32, F::Element)>>,
+    rhs: Vec<F::Element>,
+    field: F,
+}
+
+impl<F: Field> SparseLinearSystem<F> {
+    /// Create a new empty system in `ncols` unknowns.
+    pub fn new(ncols: u32, field: F) -> SparseLinearSystem<F> {
+        SparseLinearSystem {
+            ncols,
+            rows: vec![],
+            rhs: vec![],
+            field,
+        }
+    }
+
+    /// Add the equation `sum_i row[i].1 * x_{row[i].0} = rhs` to the system.
+    /// Entries may appear in any order and repeated columns are added together.
+    pub fn add_row(&mut self, mut row: Vec<(u32, F::Element)>, rhs: F::Element) {
+        row.sort_by_key(|(c, _)| *c);
+
+        let mut merged: Vec<(u32, F::Element)> = Vec::with_capacity(row.len());
+        for (c, v) in row {
+            assert!(c < self.ncols, "Column {} is out of bounds", c);
+
+            if let Some((lc, lv)) = merged.last_mut() {
+                if *lc == c {
+                    self.field.add_assign(lv, &v);
+                    continue;
+                }
+            }
+
+            merged.push((c, v));
+        }
+
+        merged.retain(|(_, v)| !F::is_zero(v));
+
+        self.rows.push(merged);
+        self.rhs.push(rhs);
+    }
+
+    /// Return the number of equations.
+    pub fn nrows(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Return the number of unknowns.
+    pub fn ncols(&self) -> usize {
+        self.ncols as usize
+    }
+
+    /// Return the field of the system.
+    pub fn field(&self) -> &F {
+        &self.field
+    }
+
+    /// Compute `a - c * b` for the sorted sparse rows `a` and `b` and
+    /// register the columns that are new in the result in `new_cols`.
+    fn sub_mul_row(
+        &self,
+        a: &[(u32, F::Element)],
+        b: &[(u32, F::Element)],
+        c: &F::Element,
+        new_cols: &mut Vec<u32>,
+    ) -> Vec<(u32, F::Element)> {
+        let mut res = Vec::with_capacity(a.len() + b.len());
+
+        let (mut i, mut j) = (0, 0);
+        while i < a.len() || j < b.len() {
+            if j == b.len() || i < a.len() && a[i].0 < b[j].0 {
+                res.push(a[i].clone());
+                i += 1;
+            } else if i == a.len() || b[j].0 < a[i].0 {
+                let v = self.field.neg(&self.field.mul(&b[j].1, c));
+                res.push((b[j].0, v));
+                new_cols.push(b[j].0);
+                j += 1;
+            } else {
+                let mut v = a[i].1.clone();
+                self.field.sub_mul_assign(&mut v, &b[j].1, c);
+                if !F::is_zero(&v) {
+                    res.push((a[i].0, v));
+                }
+                i += 1;
+                j += 1;
+            }
+        }
+
+        res
+    }
+
+    /// Solve the system.
+    ///
+    /// Rows are processed in order of increasing number of non-zero entries and
+    /// the pivot is chosen as the column of the row that appears in the fewest
+    /// other rows, after which the solution is obtained by back substitution.
+    pub fn solve(&self) -> Result<Vec<F::Element>, MatrixError<F>> {
+        let mut rows = self.rows.clone();
+        let mut rhs = self.rhs.clone();
+
+        let mut col_rows = vec![vec![]; self.ncols as usize];
+        for (ri, r) in rows.iter().enumerate() {
+            for (c, _) in r {
+                col_rows[*c as usize].push(ri);
+            }
+        }
+
+        let mut active = vec![true; rows.len()];
+        let mut queue: BinaryHeap<_> = rows
+            .iter()
+            .enumerate()
+            .map(|(ri, r)| Reverse((r.len(), ri)))
+            .collect();
+
+        let mut pivots = vec![];
+        let mut new_cols = vec![];
+        while let Some(Reverse((len, ri))) = queue.pop() {
+            if !active[ri] || rows[ri].len() != len {
+                continue;
+            }
+
+            active[ri] = false;
+
+            if rows[ri].is_empty() {
+                if !F::is_zero(&rhs[ri]) {
+                    return Err(MatrixError::Inconsistent);
+                }
+                continue;
+            }
+
+            // select the column that appears in the fewest rows to limit fill-in
+            let (pivot_index, pivot_col) = rows[ri]
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, (c, _))| col_rows[*c as usize].len())
+                .map(|(i, (c, _))| (i, *c))
+                .unwrap();
+
+            if !self.field.is_one(&rows[ri][pivot_index].1) {
+                let inv = self.field.inv(&rows[ri][pivot_index].1);
+                for (_, v) in &mut rows[ri] {
+                    self.field.mul_assign(v, &inv);
+                }
+                self.field.mul_assign(&mut rhs[ri], &inv);
+            }
+
+            let pivot_row = std::mem::take(&mut rows[ri]);
+            for r in std::mem::take(&mut col_rows[pivot_col as usize]) {
+                if !active[r] {
+                    continue;
+                }
+
+                let Ok(pos) = rows[r].binary_search_by_key(&pivot_col, |(c, _)| *c) else {
+                    continue;
+                };
+
+                let c = rows[r][pos].1.clone();
+
+                new_cols.clear();
+                rows[r] = self.sub_mul_row(&rows[r], &pivot_row, &c, &mut new_cols);
+
+                let mut e = rhs[r].clone();
+                self.field.sub_mul_assign(&mut e, &rhs[ri], &c);
+                rhs[r] = e;
+
+                for nc in &new_cols {
+                    if *nc != pivot_col {
+                        col_rows[*nc as usize].push(r);
+                    }
+                }
+
+                queue.push(Reverse((rows[r].len(), r)));
+            }
+            rows[ri] = pivot_row;
+
+            pivots.push((pivot_col, ri));
+        }
+
+        let rank = pivots.len() as u32;
+        if rank < self.ncols {
+            return Err(MatrixError::Underdetermined {
+                min_rank: rank,
+                max_rank: rank,
+                row_reduced_matrix: None,
+            });
+        }
+
+        // back substitution: every pivot row only contains its pivot
+        // column and the pivot columns of rows that were selected later
+        let mut sol = vec![self.field.zero(); self.ncols as usize];
+        for (pivot_col, ri) in pivots.into_iter().rev() {
+            let mut v = rhs[ri].clone();
+            for (c, e) in &rows[ri] {
+                if *c != pivot_col {
+                    self.field.sub_mul_assign(&mut v, e, &sol[*c as usize]);
+                }
+            }
+            sol[pivot_col as usize] = v;
+        }
+
+        Ok(sol)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        atom::{Atom, AtomView},
+        domains::{
+            finite_field::{FiniteFieldCore, Zp64},
+            integer::{IntegerRing, Z},
+            rational::Q,
+            rational_polynomial::RationalPolynomialField,
+        },
+        state::State,
+        tensors::matrix::{Matrix, MatrixError},
+    };
+
+    use super::{SparseLinearSystem, SparseMatrix};
+
+    #[test]
+    fn solve() {
+        let mut s = SparseLinearSystem::new(4, Q);
+        s.add_row(vec![(0, 1.into()), (3, 2.into())], 3.into());
+        s.add_row(vec![(1, 1.into()), (2, (-1).into())], 0.into());
+        s.add_row(vec![(2, 4.into()), (0, 1.into()), (2, 1.into())], 1.into());
+        s.add_row(vec![(3, 1.into()), (1, 1.into())], 2.into());
+        s.add_row(vec![(0, 2.into()), (3, 4.into())], 6.into());
+
+        let r = s.solve().unwrap();
+
+        let a = Matrix::from_linear(
+            vec![
+                1.into(),
+                0.into(),
+                0.into(),
+                2.into(),
+                0.into(),
+                1.into(),
+                (-1).into(),
+                0.into(),
+                1.into(),
+                0.into(),
+                5.into(),
+                0.into(),
+                0.into(),
+                1.into(),
+                0.into(),
+                1.into(),
+            ],
+            4,
+            4,
+            Q,
+        )
+        .unwrap();
+        let b = Matrix::new_vec(vec![3.into(), 0.into(), 1.into(), 2.into()], Q);
+
+        assert_eq!(r, a.solve(&b).unwrap().data);
+    }
+
+    #[test]
+    fn finite_field() {
+        let field = Zp64::new(18446744073709551557);
+        let e = |x: u64| field.to_element(x);
+
+        let mut s = SparseLinearSystem::new(3, field.clone());
+        s.add_row(vec![(0, e(1)), (1, e(1))], e(3));
+        s.add_row(vec![(1, e(1)), (2, e(1))], e(5));
+        s.add_row(vec![(0, e(1)), (2, e(1))], e(4));
+
+        assert_eq!(s.solve().unwrap(), vec![e(1), e(2), e(3)]);
+
+        s.add_row(vec![(0, e(1))], e(2));
+        assert!(matches!(s.solve(), Err(MatrixError::Inconsistent)));
+
+        let mut s = SparseLinearSystem::new(2, field.clone());
+        s.add_row(vec![(0, e(1)), (1, e(2))], e(3));
+        s.add_row(vec![(0, e(2)), (1, e(4))], e(6));
+        assert!(matches!(
+            s.solve(),
+            Err(MatrixError::Underdetermined { max_rank: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn sparse_matrix() {
+        let m = SparseMatrix::from_triplets(
+            3,
+            4,
+            vec![
+                (2, 1, 5.into()),
+                (0, 3, 1.into()),
+                (0, 0, 2.into()),
+                (2, 1, (-5).into()),
+                (0, 3, 2.into()),
+                (1, 2, 0.into()),
+                (2, 0, (-1).into()),
+            ],
+            Z,
+        )
+        .unwrap();
+
+        assert_eq!(m.nnz(), 3);
+        assert_eq!(
+            m.to_triplets(),
+            vec![(0, 0, 2.into()), (0, 3, 3.into()), (2, 0, (-1).into())]
+        );
+        assert_eq!(m.get(0, 3), Some(&3.into()));
+        assert_eq!(m.get(2, 1), None);
+
+        let d = m.to_dense();
+        assert_eq!(SparseMatrix::from_dense(&d), m);
+        assert_eq!(m.transpose().to_dense(), d.transpose());
+        assert_eq!(m.transpose().transpose(), m);
+
+        let v = vec![1.into(), 2.into(), 3.into(), 4.into()];
+        assert_eq!(m.mul_vec(&v).unwrap(), (&d * &Matrix::new_vec(v, Z)).data);
+        assert!(matches!(m.mul_vec(&[]), Err(MatrixError::ShapeMismatch)));
+
+        assert!(SparseMatrix::from_triplets(2, 2, vec![(2, 0, 1.into())], Z).is_err());
+    }
+
+    #[test]
+    fn linear_equations() {
+        let x = State::get_symbol("v1");
+        let y = State::get_symbol("v2");
+        let z = State::get_symbol("v3");
+        let eqs = [
+            "v4*v1 + 2*v2 + v3 - 1",
+            "v1 + v4*v2 + v3/v4 - 2",
+            "(v4-1)v1 + v4*v3",
+        ];
+
+        let atoms: Vec<_> = eqs.iter().map(|e| Atom::parse(e).unwrap()).collect();
+        let system: Vec<_> = atoms.iter().map(|x| x.as_view()).collect();
+
+        let (m, b) =
+            SparseMatrix::<RationalPolynomialField<IntegerRing, u8>>::from_linear_equations(
+                &system,
+                &[x, y, z],
+            )
+            .unwrap();
+
+        assert_eq!(m.nnz(), 8);
+
+        let sol: Vec<_> = m
+            .solve(&b)
+            .unwrap()
+            .iter()
+            .map(|s| s.to_expression())
+            .collect();
+        let dense_sol = AtomView::solve_linear_system::<u8>(&system, &[x, y, z]).unwrap();
+        assert_eq!(sol, dense_sol);
+    }
+}
@@ -1776,7 +1776,7 @@ class Polynomial:
         """
 
     @classmethod
-    def groebner_basis(_cls, system: list[Polynomial], grevlex: bool = True, print_stats: bool = False) -> list[Polynomial]:
+    def groebner_basis(_cls, system: list[Polynomial], grevlex: bool = True, print_stats: bool = False, modular: bool = False) -> list[Polynomial]:
         """Compute the Groebner basis of a polynomial system.
 
         If `grevlex=True`, reverse graded lexicographical ordering is used,
@@ -1784,6 +1784,9 @@ class Polynomial:
 
         If `print_stats=True` intermediate statistics will be printed.
 
+        If `modular=True`, the basis is computed modulo several primes
+        and is reconstructed using rational reconstruction.
+
         Examples
         --------
         >>> basis = Polynomial.groebner_basis(